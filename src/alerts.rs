@@ -0,0 +1,58 @@
+use tracing::error;
+use web_sys::AudioContext;
+
+use crate::api::{fetch_branch, Instance, MergeRequest, PipelineStatus};
+
+/// Local storage key of whether the sound alert is enabled
+pub const SOUND_STORAGE_KEY: &str = "lab-bench-sound";
+
+const BEEP_FREQUENCY: f32 = 880.0;
+const BEEP_SECONDS: f64 = 0.4;
+
+/// Whether a pipeline newly failed on a watched merge request, that is a pinned one, or one
+/// targeting a protected branch
+pub async fn critical_failure(
+    instance: &Instance,
+    previous: &[MergeRequest],
+    current: &[MergeRequest],
+    pinned: &[String],
+) -> bool {
+    let failed = |mr: &MergeRequest| {
+        mr.head_pipeline
+            .as_ref()
+            .is_some_and(|p| p.status == PipelineStatus::Failed)
+    };
+    for merge_request in current.iter().filter(|mr| failed(mr)) {
+        let newly_failed = previous
+            .iter()
+            .find(|mr| mr.id == merge_request.id)
+            .is_some_and(|mr| !failed(mr));
+        if !newly_failed {
+            continue;
+        }
+        if pinned.contains(&merge_request.references.full) {
+            return true;
+        }
+        match fetch_branch(instance, merge_request.project_id, &merge_request.target_branch).await
+        {
+            Ok(branch) if branch.protected => return true,
+            Ok(_) => {}
+            Err(e) => error!("failed checking if the target branch is protected: {e}"),
+        }
+    }
+    false
+}
+
+/// Play a short beep
+pub fn play_alert() {
+    let played = AudioContext::new().and_then(|context| {
+        let oscillator = context.create_oscillator()?;
+        oscillator.frequency().set_value(BEEP_FREQUENCY);
+        oscillator.connect_with_audio_node(&context.destination())?;
+        oscillator.start()?;
+        oscillator.stop_with_when(context.current_time() + BEEP_SECONDS)
+    });
+    if let Err(e) = played {
+        error!("failed playing the alert: {e:?}");
+    }
+}
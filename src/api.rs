@@ -1,14 +1,62 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, TimeDelta, Utc};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
 use futures::future::join_all;
+use futures::stream::{self, Stream, StreamExt};
+use gloo_timers::future::sleep;
 use percent_encoding::NON_ALPHANUMERIC;
-use reqwest::Client;
+use reqwest::header::USER_AGENT as USER_AGENT_HEADER;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
 use strum::{Display, EnumIter};
 use tracing::{error, info};
 
+/// How to reach and authenticate with a GitLab instance
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Instance {
+    /// Base url of the REST API including any subpath the instance is served under (e.g.
+    /// `https://example.com/gitlab/api/v4`)
+    pub url: String,
+    pub auth: Auth,
+    pub private_token: String,
+    /// Static headers sent with every request, needed by some gateways in front of instances
+    pub headers: Vec<(String, String)>,
+    /// Prefix of the `X-Request-Id` sent with every request, which GitLab uses as the correlation
+    /// id in its logs
+    pub request_tag: Option<String>,
+}
+
+/// How requests are authenticated
+#[derive(Clone, Copy, Debug, Default, Display, Deserialize, Serialize, PartialEq, Eq, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Auth {
+    /// The private token is sent with every request
+    #[default]
+    Token,
+    /// Rely on the browser's existing GitLab session cookies. Only works when the app is served
+    /// from the instance's domain, and instances may reject writes without a CSRF token.
+    Session,
+}
+
+impl Instance {
+    /// Parse headers given one per line as `Name: value`, skipping malformed lines
+    pub fn parse_headers(s: &str) -> Vec<(String, String)> {
+        s.lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .filter(|(name, _)| !name.is_empty())
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, EnumIter)]
 #[serde(rename_all = "snake_case")]
 pub enum OrderBy {
@@ -57,7 +105,41 @@ pub enum Wip {
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub enum MergeRequestsDomain {
     AuthorUsername(String),
+    AssigneeUsername(String),
+    ReviewerUsername(String),
     ProjectPath(String),
+    /// All the merge requests of the projects in a group and its subgroups
+    GroupPath(String),
+    /// All the merge requests with a label
+    Label(String),
+}
+
+/// Which API merge requests are fetched with
+#[derive(Clone, Copy, Debug, Default, Display, Deserialize, Serialize, PartialEq, Eq, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Backend {
+    /// A request per page, then a few GraphQL queries per project for the full data
+    #[default]
+    Rest,
+    /// A single paginated query per project or group which includes the pipeline, review states,
+    /// discussion counts, and diffs. Other domains still use REST, the reports, approval rules,
+    /// and status checks are not fetched. Queries with a scope, archived projects, or `sudo` are
+    /// rejected as GraphQL can't filter by them.
+    Graphql,
+}
+
+/// How pages of results are followed
+#[derive(Clone, Copy, Debug, Default, Display, Deserialize, Serialize, PartialEq, Eq, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Pagination {
+    /// Request numbered pages, which gets slow deep into large result sets
+    #[default]
+    Offset,
+    /// Follow a cursor, which stays fast on large instances. GitLab only supports it for some
+    /// resources and orderings and rejects the request otherwise.
+    Keyset,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -67,43 +149,337 @@ pub struct MergeRequestsQuery {
     pub order_by: OrderBy,
     pub scope: Scope,
     pub sort: Sort,
+    #[serde(default)]
+    pub source_branch: Option<String>,
     pub state: Option<State>,
     pub updated_after: Option<DateTime<Utc>>,
     pub updated_before: Option<DateTime<Utc>>,
     pub wip: Option<Wip>,
+    /// Only merge requests deployed to this environment
+    #[serde(default)]
+    pub environment: Option<String>,
+    #[serde(default)]
+    pub deployed_after: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub deployed_before: Option<DateTime<Utc>>,
+    /// Exclude merge requests with any of these comma separated labels
+    #[serde(default, rename = "not[labels]")]
+    pub not_labels: Option<String>,
+    /// Exclude merge requests by this author
+    #[serde(default, rename = "not[author_username]")]
+    pub not_author_username: Option<String>,
+    /// Exclude merge requests in this milestone
+    #[serde(default, rename = "not[milestone]")]
+    pub not_milestone: Option<String>,
+    /// Username to run the query as, sent as the `Sudo` header. Requires an admin token.
+    #[serde(skip)]
+    pub sudo: Option<String>,
+    #[serde(skip)]
+    pub pagination: Pagination,
+    /// How many merge requests to fetch per request, at most 100
+    #[serde(skip)]
+    pub per_page: Option<usize>,
+    /// Stop once this many merge requests have been fetched across all the domains
+    #[serde(skip)]
+    pub max_results: Option<usize>,
+    /// Only fetch the merge requests with these iids, only applies to project domains
+    #[serde(skip)]
+    pub iids: Vec<i64>,
+    /// Leave out the merge requests of archived projects, only applies to group domains
+    #[serde(skip, default = "non_archived_default")]
+    pub non_archived: bool,
+    #[serde(skip)]
+    pub backend: Backend,
+    /// Fetch with `view=simple`, which is much faster but leaves out everything except the
+    /// titles, states and timestamps
+    #[serde(skip)]
+    pub simple_view: bool,
 }
 
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+fn non_archived_default() -> bool {
+    true
+}
+
+impl Default for MergeRequestsQuery {
+    fn default() -> Self {
+        Self {
+            created_after: None,
+            created_before: None,
+            order_by: OrderBy::default(),
+            scope: Scope::default(),
+            sort: Sort::default(),
+            source_branch: None,
+            state: None,
+            updated_after: None,
+            updated_before: None,
+            wip: None,
+            environment: None,
+            deployed_after: None,
+            deployed_before: None,
+            not_labels: None,
+            not_author_username: None,
+            not_milestone: None,
+            sudo: None,
+            pagination: Pagination::default(),
+            per_page: None,
+            max_results: None,
+            iids: Vec::new(),
+            non_archived: non_archived_default(),
+            backend: Backend::default(),
+            simple_view: false,
+        }
+    }
+}
+
+/// Fields with `#[serde(default)]` are left out of `view=simple` payloads
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct MergeRequest {
+    #[serde(default)]
+    pub assignees: Vec<User>,
+    #[serde(default)]
     pub author: User,
+    #[serde(default)]
     pub blocking_discussions_resolved: bool,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
     pub detailed_merge_status: MergeStatus,
+    #[serde(default)]
     pub draft: bool,
+    #[serde(default)]
     pub has_conflicts: bool,
     pub head_pipeline: Option<Pipeline>,
     pub id: i64,
     pub iid: i64,
+    #[serde(default)]
+    pub labels: Vec<Label>,
     pub latest_build_finished_at: Option<DateTime<Utc>>,
     pub latest_build_started_at: Option<DateTime<Utc>>,
     pub merge_commit_sha: Option<String>,
     pub merge_user: Option<User>,
+    #[serde(default)]
     pub merge_when_pipeline_succeeds: bool,
+    #[serde(default)]
+    pub milestone: Option<Milestone>,
     pub merged_at: Option<DateTime<Utc>>,
     pub project_id: i64,
+    #[serde(default)]
     pub references: References,
     #[serde(default)]
     pub reviewers: Vec<User>,
     pub sha: Option<String>,
+    #[serde(default)]
     pub source_branch: String,
     pub state: State,
+    #[serde(default)]
+    pub target_branch: String,
+    #[serde(default)]
+    pub time_stats: TimeStats,
     pub title: String,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
     pub user_notes_count: i64,
     pub web_url: String,
+    /// New and fixed SAST/dependency scanning findings compared to the target branch. Only set
+    /// when the full data is fetched and the project runs security scans.
+    #[serde(skip)]
+    pub security_report: Option<ReportDelta>,
+    /// New and resolved code quality violations compared to the target branch. Only set when the
+    /// full data is fetched and the project runs code quality.
+    #[serde(skip)]
+    pub code_quality_report: Option<ReportDelta>,
+    /// External status checks, only fetched when they are what blocks the merge
+    #[serde(skip)]
+    pub status_checks: Vec<StatusCheck>,
+    /// The review state of each reviewer
+    #[serde(skip)]
+    pub reviewer_states: Vec<Reviewer>,
+    /// When a reviewer last requested changes
+    #[serde(skip)]
+    pub changes_requested_at: Option<DateTime<Utc>>,
+    /// When the source branch was last pushed to
+    #[serde(skip)]
+    pub last_pushed_at: Option<DateTime<Utc>>,
+    /// When a reviewer last approved
+    #[serde(skip)]
+    pub approved_at: Option<DateTime<Utc>>,
+    /// The head commit at the time of the last approval
+    #[serde(skip)]
+    pub approved_sha: Option<String>,
+    /// The changed files, fetched with the full data
+    #[serde(skip)]
+    pub diffs: Vec<Diff>,
+    /// How many approvals the approval rules require, 0 without rules. Fetched with the full data
+    /// like the other approval fields.
+    #[serde(skip)]
+    pub approvals_required: i64,
+    #[serde(skip)]
+    pub approvals_left: i64,
+    #[serde(skip)]
+    pub approved_by: Vec<User>,
+    #[serde(skip)]
+    pub approval_rules: Vec<ApprovalRule>,
+    /// The most recent comment which is not a system note, fetched with the full data
+    #[serde(skip)]
+    pub latest_comment: Option<Note>,
+    /// Names of the failed jobs which fail the head pipeline, fetched with the full data when it
+    /// failed
+    #[serde(skip)]
+    pub failed_jobs: Vec<String>,
+    /// Test case counts of the head pipeline, fetched with the full data when it failed
+    #[serde(skip)]
+    pub test_totals: Option<TestTotals>,
+    /// Coverage of the latest successful pipeline of the target branch, fetched with the full
+    /// data when the head pipeline reports coverage
+    #[serde(skip)]
+    pub target_coverage: Option<String>,
+    /// Resolvable threads which are not resolved yet, only set over GraphQL and with the full data
+    #[serde(skip)]
+    pub unresolved_threads: i64,
+    /// The JSON the merge request was decoded from, for the fields which are not modeled. Only
+    /// kept when listed over REST, and carried over to the full data.
+    #[serde(skip)]
+    pub raw: Option<Rc<serde_json::Value>>,
 }
 
+impl MergeRequest {
+    /// Decode a merge request, keeping the JSON it was decoded from
+    fn from_raw(raw: serde_json::Value) -> Result<MergeRequest> {
+        // Decoding the text again tells where decoding failed
+        let mut merge_request = MergeRequest::deserialize(&raw)
+            .or_else(|_| decode(&raw.to_string(), "merge request"))?;
+        merge_request.raw = Some(Rc::new(raw));
+        Ok(merge_request)
+    }
+
+    /// Carry over what only the REST list has when the merge request is replaced by its full data
+    fn keep_listed(&mut self, listed: &MergeRequest) {
+        self.merge_commit_sha = listed.merge_commit_sha.clone();
+        self.merge_user = listed.merge_user.clone();
+        self.raw = listed.raw.clone();
+    }
+
+    /// The full path of the project the merge request belongs to, or the `owner/repo` of a pull
+    /// request
+    pub fn project_path(&self) -> &str {
+        self.references
+            .full
+            .rsplit_once(['!', '#'])
+            .map_or(&self.references.full, |(project_path, _)| project_path)
+    }
+
+    /// Whether this merge request is stacked on the other one, that is it targets the other's
+    /// source branch in the same project
+    pub fn is_stacked_on(&self, other: &MergeRequest) -> bool {
+        self.project_id == other.project_id && self.target_branch == other.source_branch
+    }
+
+    /// The paths of the changed files, both before and after renames
+    pub fn changed_paths(&self) -> Vec<&str> {
+        let mut paths = Vec::new();
+        for diff in &self.diffs {
+            if diff.old_path != diff.new_path {
+                paths.push(diff.old_path.as_str());
+            }
+            paths.push(diff.new_path.as_str());
+        }
+        paths
+    }
+
+    /// The reviewers who have not approved yet. Without the full data every reviewer counts as
+    /// pending.
+    pub fn pending_reviewers(&self) -> Vec<&User> {
+        if self.reviewer_states.is_empty() {
+            return self.reviewers.iter().collect();
+        }
+        self.reviewer_states
+            .iter()
+            .filter(|r| r.state != ReviewState::Approved)
+            .map(|r| &r.user)
+            .collect()
+    }
+}
+
+/// A label with the colors it is shown with in GitLab. Only the name is known when the
+/// merge request was fetched without `with_labels_details`.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(from = "LabelPayload")]
+pub struct Label {
+    pub name: String,
+    pub color: String,
+    pub text_color: String,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LabelPayload {
+    Name(String),
+    Details {
+        name: String,
+        color: String,
+        text_color: String,
+        description: Option<String>,
+    },
+}
+
+impl From<LabelPayload> for Label {
+    fn from(label: LabelPayload) -> Self {
+        match label {
+            LabelPayload::Name(name) => Label {
+                name,
+                color: "#dbdbdb".to_string(),
+                text_color: "#333238".to_string(),
+                description: None,
+            },
+            LabelPayload::Details {
+                name,
+                color,
+                text_color,
+                description,
+            } => Label {
+                name,
+                color,
+                text_color,
+                description,
+            },
+        }
+    }
+}
+
+/// An approval rule of a merge request, only on GitLab Premium and above
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApprovalRule {
+    pub name: String,
+    pub rule_type: ApprovalRuleType,
+    pub approved: bool,
+    pub approvals_required: i64,
+    pub approved_by: Vec<User>,
+    /// The users whose approval counts towards the rule
+    pub eligible_approvers: Vec<User>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Display, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ApprovalRuleType {
+    Regular,
+    CodeOwner,
+    ReportApprover,
+    AnyApprover,
+    #[default]
+    Unknown,
+}
+
+/// Findings a merge request introduces and resolves in a report compared to its target branch
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReportDelta {
+    pub added: usize,
+    pub fixed: usize,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct User {
     pub avatar_url: String,
     pub id: i64,
@@ -113,6 +489,58 @@ pub struct User {
     pub web_url: String,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Reviewer {
+    pub user: User,
+    pub state: ReviewState,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Display, PartialEq, Eq, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ReviewState {
+    Unreviewed,
+    Reviewed,
+    RequestedChanges,
+    Approved,
+    Unapproved,
+    /// Not documented in gitlab
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Note {
+    pub id: i64,
+    pub body: String,
+    pub author: User,
+    pub created_at: DateTime<Utc>,
+    pub system: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Project {
+    pub id: i64,
+    pub path_with_namespace: String,
+    pub web_url: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Snippet {
+    pub id: i64,
+    pub title: String,
+    pub web_url: String,
+}
+
+/// A version of the merge request diff, a new one is created on every push
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct MergeRequestVersion {
+    pub id: i64,
+    pub head_commit_sha: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 pub struct Pipeline {
     pub id: i64,
@@ -123,16 +551,318 @@ pub struct Pipeline {
     pub duration: TimeDelta,
     #[serde(deserialize_with = "deserialize_time_delta_from_seconds_with_default")]
     pub queued_duration: TimeDelta,
+    /// Test coverage in percent parsed from the job logs, as GitLab formats it, e.g. `87.50`
+    #[serde(default)]
+    pub coverage: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+/// Test case counts of all the test reports of a pipeline
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct TestTotals {
+    pub count: i64,
+    pub success: i64,
+    pub failed: i64,
+    pub skipped: i64,
+    pub error: i64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+struct TestReportSummary {
+    total: TestTotals,
+}
+
+/// The test reports of a pipeline's jobs, merged by suite
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct TestReport {
+    pub test_suites: Vec<TestSuite>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct TestSuite {
+    pub name: String,
+    #[serde(default)]
+    pub test_cases: Vec<TestCase>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct TestCase {
+    /// `success`, `failed`, `skipped`, or `error`
+    pub status: String,
+    pub name: String,
+    pub classname: Option<String>,
+}
+
+/// A job of a pipeline, retried jobs are left out by GitLab
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Job {
+    pub id: i64,
+    pub name: String,
+    pub stage: String,
+    pub status: PipelineStatus,
+    pub web_url: String,
+    #[serde(default)]
+    pub allow_failure: bool,
+}
+
+impl Pipeline {
+    /// Pipelines summarized from the commit statuses of external CI systems link elsewhere and
+    /// have no jobs to fetch
+    pub fn has_jobs(&self) -> bool {
+        self.web_url.contains("/-/pipelines/")
+    }
+
+    /// Summarize the statuses reported by external CI systems (Jenkins, Buildkite, ...) as a
+    /// pipeline. The web url links to the status deciding the overall result.
+    pub fn from_commit_statuses(statuses: &[CommitStatus]) -> Option<Self> {
+        use PipelineStatus::*;
+
+        let required = || statuses.iter().filter(|s| !s.allow_failure);
+        let deciding = required()
+            .find(|s| matches!(s.status, Failed))
+            .or_else(|| {
+                required().find(|s| {
+                    matches!(
+                        s.status,
+                        Created | WaitingForResource | Preparing | Pending | Running
+                    )
+                })
+            })
+            .or_else(|| required().find(|s| matches!(s.status, Canceled)))
+            .or_else(|| statuses.first())?;
+
+        let started_at = statuses.iter().filter_map(|s| s.started_at).min();
+        let finished_at = statuses.iter().filter_map(|s| s.finished_at).max();
+        let status = match deciding.status {
+            _ if deciding.allow_failure => Success,
+            Failed => Failed,
+            Created | WaitingForResource | Preparing | Pending | Running => Running,
+            Canceled => Canceled,
+            _ => Success,
+        };
+        Some(Pipeline {
+            id: deciding.id,
+            sha: deciding.sha.clone(),
+            status,
+            web_url: deciding.target_url.clone().unwrap_or_default(),
+            duration: match (started_at, finished_at) {
+                (Some(started_at), Some(finished_at)) => finished_at - started_at,
+                _ => TimeDelta::zero(),
+            },
+            queued_duration: TimeDelta::zero(),
+            coverage: None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct StatusCheck {
+    pub id: i64,
+    pub name: String,
+    pub external_url: String,
+    pub status: StatusCheckStatus,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Display, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StatusCheckStatus {
+    Passed,
+    Pending,
+    Failed,
+    /// Not documented in gitlab
+    #[serde(other)]
+    Unknown,
+}
+
+/// The status of a commit as reported by an external CI system
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct CommitStatus {
+    pub id: i64,
+    pub sha: String,
+    pub name: String,
+    pub status: PipelineStatus,
+    pub target_url: Option<String>,
+    #[serde(default)]
+    pub allow_failure: bool,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct References {
     pub full: String,
     pub short: String,
     pub relative: String,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Display, Serialize, PartialEq, Eq)]
+impl References {
+    /// Recover the references of a merge request from its url, for payloads which leave them out
+    pub fn from_web_url(web_url: &str) -> Self {
+        let (project, iid) = web_url
+            .split_once("://")
+            .and_then(|(_, url)| url.split_once('/'))
+            .and_then(|(_, path)| path.split_once("/-/merge_requests/"))
+            .unwrap_or_default();
+        Self {
+            full: format!("{project}!{iid}"),
+            short: format!("!{iid}"),
+            relative: format!("!{iid}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Release {
+    pub name: String,
+    pub tag_name: String,
+    pub created_at: DateTime<Utc>,
+    pub released_at: Option<DateTime<Utc>>,
+    pub commit: Commit,
+    #[serde(rename = "_links")]
+    pub links: ReleaseLinks,
+}
+
+/// A deploy freeze of a project, the start and end are cron expressions
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct FreezePeriod {
+    pub freeze_start: String,
+    pub freeze_end: String,
+    pub cron_timezone: String,
+}
+
+/// A message administrators broadcast to everyone using the instance, usually announcing
+/// maintenance
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct BroadcastMessage {
+    pub id: i64,
+    pub message: String,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Either `banner` or `notification`
+    #[serde(default)]
+    pub broadcast_type: String,
+    /// Set by GitLab when the message is shown now
+    #[serde(default)]
+    pub active: bool,
+}
+
+impl BroadcastMessage {
+    /// GitLab computes `active` when responding, the dates are checked too as the response may
+    /// have been fetched a while ago
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.active
+            && self.starts_at.is_none_or(|t| t <= now)
+            && self.ends_at.is_none_or(|t| now < t)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct ReleaseLinks {
+    #[serde(rename = "self")]
+    pub self_url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Commit {
+    pub id: String,
+    pub short_id: String,
+    pub title: String,
+    /// The full message, including trailers like the `(cherry picked from commit ...)` line
+    #[serde(default)]
+    pub message: String,
+    pub author_name: String,
+    pub created_at: DateTime<Utc>,
+    pub web_url: String,
+}
+
+/// Time estimated for and spent on a merge request, in seconds
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct TimeStats {
+    pub time_estimate: i64,
+    pub total_time_spent: i64,
+}
+
+impl TimeStats {
+    pub fn human_time_estimate(&self) -> String {
+        human_time(self.time_estimate)
+    }
+
+    pub fn human_total_time_spent(&self) -> String {
+        human_time(self.total_time_spent)
+    }
+}
+
+/// Tracked time the way GitLab shows it, like `1w 2d 3h 30m` with 8 hour days and 5 day weeks
+fn human_time(seconds: i64) -> String {
+    const UNITS: [(&str, i64); 4] = [("w", 5 * 8 * 3600), ("d", 8 * 3600), ("h", 3600), ("m", 60)];
+
+    let mut rest = seconds.abs();
+    let parts = UNITS
+        .iter()
+        .filter_map(|(unit, size)| {
+            let count = rest / size;
+            rest %= size;
+            (count > 0).then(|| format!("{count}{unit}"))
+        })
+        .collect::<Vec<_>>();
+    let human = if parts.is_empty() {
+        "0m".to_string()
+    } else {
+        parts.join(" ")
+    };
+    if seconds < 0 {
+        format!("-{human}")
+    } else {
+        human
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Milestone {
+    pub id: i64,
+    pub title: String,
+    pub state: String,
+    pub due_date: Option<NaiveDate>,
+    pub web_url: String,
+}
+
+/// An issue a merge request closes when it is merged
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Issue {
+    pub id: i64,
+    pub iid: i64,
+    pub title: String,
+    pub state: State,
+    pub web_url: String,
+    pub references: References,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Branch {
+    pub name: String,
+    pub commit: Commit,
+    pub default: bool,
+    pub merged: bool,
+    pub protected: bool,
+    pub web_url: String,
+}
+
+/// How far a branch has diverged from the default branch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BranchDivergence {
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Compare {
+    pub commits: Vec<Commit>,
+    #[serde(default)]
+    pub compare_timeout: bool,
+    pub web_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Display, Serialize, PartialEq, Eq, EnumIter)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum MergeStatus {
@@ -168,11 +898,12 @@ pub enum MergeStatus {
     /// The merge request has reviewers who have requested changes.
     RequestedChanges,
     /// Not documented in gitlab
+    #[default]
     #[serde(other)]
     Unknown,
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Display, Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Deserialize, Display, Serialize, PartialEq, Eq, EnumIter)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum PipelineStatus {
@@ -195,126 +926,2748 @@ pub enum PipelineStatus {
 
 /// Fetch merge request from query params and a list of domains
 pub async fn fetch_merge_requests(
-    gitlab_url: &str,
-    private_token: &str,
+    instance: &Instance,
     query: &MergeRequestsQuery,
     domains: &[MergeRequestsDomain],
 ) -> Result<Vec<MergeRequest>> {
-    let futures = domains
-        .iter()
-        .map(|domain| fetch_merge_requests_helper(gitlab_url, private_token, query, domain));
+    fetch_merge_requests_staggered(instance, query, domains, &[]).await
+}
+
+/// Fetch merge requests like [`fetch_merge_requests`], waiting the given delay before fetching
+/// each domain so periodic refreshes do not send all their requests at once
+pub async fn fetch_merge_requests_staggered(
+    instance: &Instance,
+    query: &MergeRequestsQuery,
+    domains: &[MergeRequestsDomain],
+    delays: &[Duration],
+) -> Result<Vec<MergeRequest>> {
+    let futures = domains.iter().enumerate().map(|(i, domain)| async move {
+        if let Some(delay) = delays.get(i).filter(|d| !d.is_zero()) {
+            sleep(*delay).await;
+        }
+        fetch_merge_requests_helper(instance, query, domain).await
+    });
     let results = join_all(futures).await;
     // TODO: sort the results
-    Ok(results
+    let mut merge_requests = results
         .into_iter()
         .collect::<Result<Vec<_>>>()?
         .into_iter()
         .flatten()
-        .collect())
+        .collect::<Vec<MergeRequest>>();
+    // The same merge request can be matched by several domains
+    let mut seen = HashSet::new();
+    merge_requests.retain(|mr| seen.insert(mr.id));
+    if let Some(max_results) = query.max_results {
+        merge_requests.truncate(max_results);
+    }
+    Ok(merge_requests)
 }
 
-/// Fetch merge requests individually to get the full data (ie pipeline)
-pub async fn fetch_merge_requests_with_full_data(
-    gitlab_url: &str,
-    private_token: &str,
-    merge_requests: &[MergeRequest],
-) -> Result<Vec<MergeRequest>> {
-    let futures = merge_requests
-        .iter()
-        .map(|mr| fetch_merge_request_no_fail(gitlab_url, private_token, mr));
-    let results = join_all(futures).await;
-    Ok(results.into_iter().collect::<Vec<_>>())
+/// A forge whose review items are shown on the bench, mapped onto merge requests
+// Only run on the single threaded web runtime so the futures do not need to be `Send`
+#[allow(async_fn_in_trait)]
+pub trait Provider {
+    async fn fetch_review_items(
+        &self,
+        query: &MergeRequestsQuery,
+        domains: &[MergeRequestsDomain],
+    ) -> Result<Vec<MergeRequest>>;
 }
 
-async fn fetch_merge_requests_helper(
-    gitlab_url: &str,
-    private_token: &str,
-    query: &MergeRequestsQuery,
-    domain: &MergeRequestsDomain,
-) -> Result<Vec<MergeRequest>> {
-    info!("fetching merge requests with query {:?}", query);
-    info!("domain {:?}", domain);
+impl Provider for Instance {
+    async fn fetch_review_items(
+        &self,
+        query: &MergeRequestsQuery,
+        domains: &[MergeRequestsDomain],
+    ) -> Result<Vec<MergeRequest>> {
+        fetch_merge_requests(self, query, domains).await
+    }
+}
+
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// A GitHub instance, `url` is the REST API root such as [`GITHUB_API_URL`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitHub {
+    pub url: String,
+    pub token: String,
+}
+
+/// Only the state, draft, creation and update time filters, the ordering, and `max_results` of
+/// the query apply to pull requests. Only project domains, which are `owner/repo`, are supported.
+impl Provider for GitHub {
+    async fn fetch_review_items(
+        &self,
+        query: &MergeRequestsQuery,
+        domains: &[MergeRequestsDomain],
+    ) -> Result<Vec<MergeRequest>> {
+        let futures = domains.iter().map(|domain| async move {
+            match domain {
+                MergeRequestsDomain::ProjectPath(repo) => {
+                    self.fetch_pull_requests(query, repo).await
+                }
+                _ => Err(anyhow!("{domain:?} is not supported on GitHub")),
+            }
+        });
+        let mut merge_requests = join_all(futures)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        if let Some(max_results) = query.max_results {
+            merge_requests.truncate(max_results);
+        }
+        Ok(merge_requests)
+    }
+}
+
+impl GitHub {
+    async fn fetch_pull_requests(
+        &self,
+        query: &MergeRequestsQuery,
+        repo: &str,
+    ) -> Result<Vec<MergeRequest>> {
+        const PER_PAGE: usize = 100;
+
+        info!("fetching pull requests of {repo}");
+        let url = self.url.trim_end_matches('/');
+        let state = match query.state {
+            Some(State::Opened) => "open",
+            Some(State::Closed | State::Merged) => "closed",
+            _ => "all",
+        };
+        let sort = match query.order_by {
+            OrderBy::UpdatedAt => "updated",
+            OrderBy::CreatedAt | OrderBy::Title => "created",
+        };
+        let direction = match query.sort {
+            Sort::Desc => "desc",
+            Sort::Asc => "asc",
+        };
+        let per_page = query
+            .per_page
+            .or(query.max_results)
+            .unwrap_or(PER_PAGE)
+            .clamp(1, PER_PAGE)
+            .to_string();
+        let mut next_url = Some(reqwest::Url::parse_with_params(
+            &format!("{url}/repos/{repo}/pulls"),
+            &[
+                ("state", state),
+                ("sort", sort),
+                ("direction", direction),
+                ("per_page", &per_page),
+            ],
+        )?);
+
+        let mut merge_requests = Vec::new();
+        while let Some(url) = next_url {
+            let mut request = client()
+                .get(url)
+                .header("Accept", "application/vnd.github+json")
+                .header(USER_AGENT_HEADER, USER_AGENT);
+            if !self.token.is_empty() {
+                request = request.bearer_auth(&self.token);
+            }
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "pull requests request failed with status {}",
+                    response.status()
+                ));
+            }
+            next_url = next_link(&response);
+            let pull_requests: Vec<PullRequest> = decode(&response.text().await?, "pull requests")?;
+            merge_requests.extend(
+                pull_requests
+                    .into_iter()
+                    .map(MergeRequest::from)
+                    .filter(|mr| query.matches_pull_request(mr)),
+            );
+            if query
+                .max_results
+                .is_some_and(|max| merge_requests.len() >= max)
+            {
+                break;
+            }
+        }
+        info!("fetched {} pull requests", merge_requests.len());
+        Ok(merge_requests)
+    }
+}
+
+impl MergeRequestsQuery {
+    /// The filters GitHub does not support as parameters
+    fn matches_pull_request(&self, merge_request: &MergeRequest) -> bool {
+        self.state
+            .is_none_or(|s| s == State::Unknown || s == merge_request.state)
+            && self
+                .wip
+                .as_ref()
+                .is_none_or(|w| (*w == Wip::Yes) == merge_request.draft)
+            && self
+                .created_after
+                .is_none_or(|t| merge_request.created_at >= t)
+            && self
+                .created_before
+                .is_none_or(|t| merge_request.created_at <= t)
+            && self
+                .updated_after
+                .is_none_or(|t| merge_request.updated_at >= t)
+            && self
+                .updated_before
+                .is_none_or(|t| merge_request.updated_at <= t)
+    }
+}
+
+#[derive(Deserialize)]
+struct PullRequest {
+    id: i64,
+    number: i64,
+    html_url: String,
+    state: String,
+    title: String,
+    user: GitHubUser,
+    #[serde(default)]
+    labels: Vec<GitHubLabel>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    requested_reviewers: Vec<GitHubUser>,
+    #[serde(default)]
+    assignees: Vec<GitHubUser>,
+    head: GitRef,
+    base: GitRef,
+    auto_merge: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+    id: i64,
+    avatar_url: String,
+    html_url: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubLabel {
+    name: String,
+    /// Hex without the `#`
+    color: String,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitRef {
+    #[serde(rename = "ref")]
+    branch: String,
+    sha: String,
+    repo: Option<GitHubRepo>,
+}
+
+#[derive(Deserialize)]
+struct GitHubRepo {
+    id: i64,
+    full_name: String,
+}
+
+impl From<GitHubUser> for User {
+    fn from(user: GitHubUser) -> Self {
+        User {
+            avatar_url: user.avatar_url,
+            id: user.id,
+            name: user.login.clone(),
+            username: user.login,
+            state: "active".to_string(),
+            web_url: user.html_url,
+        }
+    }
+}
 
+impl From<GitHubLabel> for Label {
+    fn from(label: GitHubLabel) -> Self {
+        // GitHub picks the text color by the brightness of the background
+        let channel = |i: usize| {
+            label
+                .color
+                .get(i..i + 2)
+                .and_then(|c| u32::from_str_radix(c, 16).ok())
+                .unwrap_or_default()
+        };
+        let brightness = (channel(0) * 299 + channel(2) * 587 + channel(4) * 114) / 1000;
+        Label {
+            color: format!("#{}", label.color),
+            text_color: if brightness > 128 {
+                "#000000"
+            } else {
+                "#FFFFFF"
+            }
+            .to_string(),
+            name: label.name,
+            description: label.description,
+        }
+    }
+}
+
+impl From<PullRequest> for MergeRequest {
+    fn from(pull_request: PullRequest) -> Self {
+        let repo = pull_request.base.repo;
+        let short = format!("#{}", pull_request.number);
+        let state = match (pull_request.state.as_str(), pull_request.merged_at) {
+            ("open", _) => State::Opened,
+            (_, Some(_)) => State::Merged,
+            _ => State::Closed,
+        };
+        let reviewers = pull_request
+            .requested_reviewers
+            .into_iter()
+            .map(User::from)
+            .collect::<Vec<_>>();
+        MergeRequest {
+            assignees: pull_request.assignees.into_iter().map(User::from).collect(),
+            author: pull_request.user.into(),
+            created_at: pull_request.created_at,
+            draft: pull_request.draft,
+            id: pull_request.id,
+            iid: pull_request.number,
+            labels: pull_request.labels.into_iter().map(Label::from).collect(),
+            merge_commit_sha: pull_request.merge_commit_sha,
+            merge_when_pipeline_succeeds: pull_request.auto_merge.is_some(),
+            merged_at: pull_request.merged_at,
+            project_id: repo.as_ref().map(|r| r.id).unwrap_or_default(),
+            references: References {
+                full: format!("{}{short}", repo.map(|r| r.full_name).unwrap_or_default()),
+                relative: short.clone(),
+                short,
+            },
+            reviewer_states: reviewers
+                .iter()
+                .map(|user| Reviewer {
+                    user: user.clone(),
+                    state: ReviewState::Unreviewed,
+                    created_at: pull_request.created_at,
+                })
+                .collect(),
+            reviewers,
+            sha: Some(pull_request.head.sha),
+            source_branch: pull_request.head.branch,
+            state,
+            target_branch: pull_request.base.branch,
+            title: pull_request.title,
+            updated_at: pull_request.updated_at,
+            web_url: pull_request.html_url,
+            ..Default::default()
+        }
+    }
+}
+
+/// Fetch the open merge requests the authenticated user is a reviewer of and has not approved
+/// yet. Approvals are only known from the full data so it is always fetched.
+pub async fn fetch_merge_requests_to_review(
+    instance: &Instance,
+    query: &MergeRequestsQuery,
+) -> Result<Vec<MergeRequest>> {
+    let user = fetch_current_user(instance).await?;
+    let query = MergeRequestsQuery {
+        scope: Scope::All,
+        state: Some(State::Opened),
+        simple_view: false,
+        ..query.clone()
+    };
+    let domains = [MergeRequestsDomain::ReviewerUsername(user.username)];
+    let merge_requests = fetch_merge_requests(instance, &query, &domains).await?;
+    let mut merge_requests = fetch_merge_requests_with_full_data(instance, &merge_requests).await?;
+    merge_requests.retain(|mr| {
+        !mr.reviewer_states
+            .iter()
+            .any(|r| r.user.id == user.id && r.state == ReviewState::Approved)
+    });
+    Ok(merge_requests)
+}
+
+/// Fetch the full data (ie pipeline, reviews, diffs, reports) of merge requests. The bulk of it
+/// is fetched with a few GraphQL queries per project for every 100 merge requests, only data just
+/// some merge requests need is fetched per merge request, at most [`MAX_CONCURRENT_REQUESTS`] at
+/// a time. Merge requests whose data can not be fetched are returned as they are.
+pub async fn fetch_merge_requests_with_full_data(
+    instance: &Instance,
+    merge_requests: &[MergeRequest],
+) -> Result<Vec<MergeRequest>> {
+    const BATCH_SIZE: usize = 100;
+
+    let mut projects = Vec::<(&str, Vec<&MergeRequest>)>::new();
+    for merge_request in merge_requests {
+        let project_path = merge_request.project_path();
+        match projects.iter_mut().find(|(p, _)| *p == project_path) {
+            Some((_, project_merge_requests)) => project_merge_requests.push(merge_request),
+            None => projects.push((project_path, vec![merge_request])),
+        }
+    }
+    let futures = projects
+        .iter()
+        .flat_map(|(project_path, project_merge_requests)| {
+            project_merge_requests
+                .chunks(BATCH_SIZE)
+                .map(|batch| fetch_full_data_batch(instance, project_path, batch))
+        });
+    let fetched = join_all(futures).await.into_iter().flatten();
+    let mut fetched = stream::iter(fetched)
+        .map(|mut merge_request| async move {
+            fetch_follow_ups(instance, &mut merge_request).await;
+            (merge_request.id, merge_request)
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect::<HashMap<_, _>>()
+        .await;
+    Ok(merge_requests
+        .iter()
+        .map(|mr| fetched.remove(&mr.id).unwrap_or_else(|| mr.clone()))
+        .collect())
+}
+
+/// Fetch the full data of merge requests of the same project, but for the follow ups. Merge
+/// requests which can not be fetched are left out.
+async fn fetch_full_data_batch(
+    instance: &Instance,
+    project_path: &str,
+    merge_requests: &[&MergeRequest],
+) -> Vec<MergeRequest> {
+    let iids = merge_requests.iter().map(|mr| mr.iid).collect::<Vec<_>>();
+    let (fetched, reports, approvals, activity) = futures::join!(
+        fetch_merge_requests_graphql(
+            instance,
+            "project",
+            project_path,
+            json!({ "iids": iids.iter().map(i64::to_string).collect::<Vec<_>>() }),
+            iids.len(),
+            None,
+        ),
+        fetch_report_deltas(instance, project_path, &iids),
+        fetch_approvals(instance, project_path, &iids),
+        fetch_activity(instance, project_path, &iids),
+    );
+    let fetched = fetched
+        .inspect_err(|e| error!("failed fetching merge requests of {project_path}: {e}"))
+        .unwrap_or_default();
+    let reports = reports
+        .inspect_err(|e| error!("failed fetching reports of {project_path}: {e}"))
+        .unwrap_or_default();
+    let mut approvals = approvals
+        .inspect_err(|e| error!("failed fetching approvals of {project_path}: {e}"))
+        .unwrap_or_default();
+    let mut activity = activity
+        .inspect_err(|e| error!("failed fetching activity of {project_path}: {e}"))
+        .unwrap_or_default();
+
+    let mut target_branches = fetched
+        .iter()
+        .filter(|mr| {
+            mr.head_pipeline
+                .as_ref()
+                .is_some_and(|p| p.coverage.is_some())
+        })
+        .map(|mr| mr.target_branch.as_str())
+        .collect::<Vec<_>>();
+    target_branches.sort_unstable();
+    target_branches.dedup();
+    let target_coverage = fetch_branch_coverage(instance, project_path, &target_branches).await;
+
+    merge_requests
+        .iter()
+        .filter_map(|listed| {
+            let mut merge_request = fetched.iter().find(|mr| mr.id == listed.id).cloned()?;
+            merge_request.keep_listed(listed);
+            merge_request.target_coverage =
+                target_coverage.get(&merge_request.target_branch).cloned();
+            if let Some((security_report, code_quality_report)) = reports.get(&merge_request.iid) {
+                merge_request.security_report = security_report.clone();
+                merge_request.code_quality_report = code_quality_report.clone();
+            }
+            if let Some(approvals) = approvals.remove(&merge_request.iid) {
+                approvals.apply(&mut merge_request);
+            }
+            if let Some(activity) = activity.remove(&merge_request.iid) {
+                activity.apply(&mut merge_request);
+            }
+            Some(merge_request)
+        })
+        .collect()
+}
+
+async fn fetch_merge_requests_helper(
+    instance: &Instance,
+    query: &MergeRequestsQuery,
+    domain: &MergeRequestsDomain,
+) -> Result<Vec<MergeRequest>> {
+    info!("fetching merge requests with query {:?}", query);
+    info!("domain {:?}", domain);
+
+    if query.backend == Backend::Graphql {
+        let namespace = match domain {
+            MergeRequestsDomain::ProjectPath(project_path) => Some(("project", project_path)),
+            MergeRequestsDomain::GroupPath(group_path) => Some(("group", group_path)),
+            _ => None,
+        };
+        if let Some((namespace, full_path)) = namespace {
+            return fetch_merge_requests_graphql(
+                instance,
+                namespace,
+                full_path,
+                graphql_filters(query, namespace)?,
+                query.per_page.or(query.max_results).unwrap_or(usize::MAX),
+                query.max_results,
+            )
+            .await;
+        }
+    }
+
+    let gitlab_url = &instance.url;
     let request = client();
 
     let request = match domain {
         MergeRequestsDomain::AuthorUsername(author_username) => request
             .get(format!("{gitlab_url}/merge_requests"))
             .query(&[("author_username", author_username)]),
+        MergeRequestsDomain::AssigneeUsername(assignee_username) => request
+            .get(format!("{gitlab_url}/merge_requests"))
+            .query(&[("assignee_username", assignee_username)]),
+        MergeRequestsDomain::ReviewerUsername(reviewer_username) => request
+            .get(format!("{gitlab_url}/merge_requests"))
+            .query(&[("reviewer_username", reviewer_username)]),
         MergeRequestsDomain::ProjectPath(project_path) => {
             let project_path =
                 percent_encoding::utf8_percent_encode(project_path, NON_ALPHANUMERIC);
-            request.get(format!(
-                "{gitlab_url}/projects/{project_path}/merge_requests",
-            ))
+            let iids = query
+                .iids
+                .iter()
+                .map(|iid| ("iids[]", iid))
+                .collect::<Vec<_>>();
+            request
+                .get(format!(
+                    "{gitlab_url}/projects/{project_path}/merge_requests",
+                ))
+                .query(&iids)
+        }
+        MergeRequestsDomain::Label(label) => request
+            .get(format!("{gitlab_url}/merge_requests"))
+            .query(&[("labels", label)]),
+        MergeRequestsDomain::GroupPath(group_path) => {
+            let group_path = encode_path(group_path);
+            request
+                .get(format!("{gitlab_url}/groups/{group_path}/merge_requests"))
+                .query(&[("non_archived", query.non_archived)])
         }
     };
 
-    let response = request
-        .header("PRIVATE-TOKEN", private_token)
-        .query(&query)
-        .send()
-        .await?;
-    let merge_requests = if response.status().is_success() {
-        response.json::<Vec<MergeRequest>>().await?
-    } else {
+    let request = match &query.sudo {
+        Some(sudo) => request.header("Sudo", sudo),
+        None => request,
+    };
+    let request = match query.simple_view {
+        true => request.query(&[("view", "simple")]),
+        false => request.query(&[("with_labels_details", "true")]),
+    };
+    let merge_requests: Vec<serde_json::Value> = send_paginated(
+        request.query(&query),
+        instance,
+        "merge requests",
+        query.pagination,
+        query.per_page,
+        query.max_results,
+    )
+    .await?;
+    let mut merge_requests = merge_requests
+        .into_iter()
+        .map(MergeRequest::from_raw)
+        .collect::<Result<Vec<_>>>()?;
+    if query.simple_view {
+        for merge_request in &mut merge_requests {
+            merge_request.references = References::from_web_url(&merge_request.web_url);
+        }
+    }
+    info!("fetched {} merge requests", merge_requests.len());
+    Ok(merge_requests)
+}
+
+/// The merge requests of a project or group, `NAMESPACE` is replaced by which one and
+/// `SUBGROUPS` by the argument including the subgroups of groups
+const MERGE_REQUESTS_GRAPHQL: &str = r#"
+query($fullPath: ID!, $after: String, $first: Int, $state: MergeRequestState,
+      $sourceBranches: [String!], $iids: [String!], $draft: Boolean,
+      $createdAfter: Time, $createdBefore: Time, $updatedAfter: Time, $updatedBefore: Time,
+      $environmentName: String, $deployedAfter: Time, $deployedBefore: Time,
+      $sort: MergeRequestSort, $not: MergeRequestsResolverNegatedParams) {
+  NAMESPACE(fullPath: $fullPath) {
+    mergeRequests(after: $after, first: $first, state: $state, sourceBranches: $sourceBranches,
+                  iids: $iids, draft: $draft, createdAfter: $createdAfter,
+                  createdBefore: $createdBefore, updatedAfter: $updatedAfter,
+                  updatedBefore: $updatedBefore, environmentName: $environmentName,
+                  deployedAfter: $deployedAfter, deployedBefore: $deployedBefore, sort: $sort,
+                  not: $not SUBGROUPS) {
+      pageInfo { hasNextPage endCursor }
+      nodes {
+        id iid projectId title description webUrl state draft createdAt updatedAt mergedAt
+        sourceBranch targetBranch diffHeadSha detailedMergeStatus conflicts autoMergeEnabled
+        userNotesCount timeEstimate totalTimeSpent resolvableDiscussionsCount resolvedDiscussionsCount
+        reference(full: true)
+        author { ...user }
+        assignees { nodes { ...user } }
+        milestone { id title state dueDate webPath }
+        reviewers { nodes { ...user mergeRequestInteraction { reviewState } } }
+        labels { nodes { title color textColor description } }
+        headPipeline { id sha status path duration queuedDuration coverage }
+        diffStats { path additions deletions }
+      }
+    }
+  }
+}
+
+fragment user on User { id username name avatarUrl webUrl state }
+"#;
+
+#[derive(Deserialize)]
+struct NamespaceData {
+    #[serde(alias = "group")]
+    project: Option<Namespace>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Namespace {
+    merge_requests: Connection<GraphqlMergeRequest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Connection<T> {
+    #[serde(default)]
+    page_info: PageInfo,
+    nodes: Vec<T>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    #[serde(default)]
+    has_next_page: bool,
+    end_cursor: Option<String>,
+    #[serde(default)]
+    has_previous_page: bool,
+    start_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlMergeRequest {
+    id: String,
+    iid: String,
+    project_id: i64,
+    title: String,
+    description: Option<String>,
+    #[serde(default)]
+    time_estimate: i64,
+    #[serde(default)]
+    total_time_spent: i64,
+    web_url: String,
+    state: State,
+    draft: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    merged_at: Option<DateTime<Utc>>,
+    source_branch: String,
+    target_branch: String,
+    diff_head_sha: Option<String>,
+    detailed_merge_status: Option<String>,
+    conflicts: bool,
+    auto_merge_enabled: bool,
+    user_notes_count: Option<i64>,
+    resolvable_discussions_count: Option<i64>,
+    resolved_discussions_count: Option<i64>,
+    reference: String,
+    author: Option<GraphqlUser>,
+    #[serde(default)]
+    assignees: Option<Connection<GraphqlUser>>,
+    milestone: Option<GraphqlMilestone>,
+    reviewers: Connection<GraphqlReviewer>,
+    labels: Connection<GraphqlLabel>,
+    head_pipeline: Option<GraphqlPipeline>,
+    #[serde(default)]
+    diff_stats: Vec<GraphqlDiffStats>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlUser {
+    id: String,
+    username: String,
+    name: String,
+    avatar_url: Option<String>,
+    web_url: String,
+    state: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlReviewer {
+    #[serde(flatten)]
+    user: GraphqlUser,
+    merge_request_interaction: Option<GraphqlInteraction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlInteraction {
+    review_state: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlMilestone {
+    id: String,
+    title: String,
+    state: String,
+    due_date: Option<NaiveDate>,
+    web_path: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlLabel {
+    title: String,
+    color: String,
+    text_color: String,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlPipeline {
+    id: String,
+    sha: String,
+    status: String,
+    path: Option<String>,
+    duration: Option<i64>,
+    queued_duration: Option<f64>,
+    coverage: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlDiffStats {
+    path: String,
+    additions: usize,
+    deletions: usize,
+}
+
+impl From<GraphqlDiffStats> for Diff {
+    fn from(stats: GraphqlDiffStats) -> Self {
+        Diff {
+            old_path: stats.path.clone(),
+            new_path: stats.path,
+            summary: DiffSummary {
+                additions: stats.additions,
+                deletions: stats.deletions,
+                // None of these are in the stats, the full data fetches the diffs over REST when
+                // the stats leave them open, see [`Diff::needs_details`]
+                binary: false,
+            },
+            generated_file: false,
+            too_large: false,
+        }
+    }
+}
+
+impl From<GraphqlUser> for User {
+    fn from(user: GraphqlUser) -> Self {
+        User {
+            avatar_url: user.avatar_url.unwrap_or_default(),
+            id: graphql_id(&user.id),
+            name: user.name,
+            username: user.username,
+            state: user.state,
+            web_url: user.web_url,
+        }
+    }
+}
+
+impl GraphqlMergeRequest {
+    fn into_merge_request(self, web_base_url: &str) -> MergeRequest {
+        let reviewer_states = self
+            .reviewers
+            .nodes
+            .into_iter()
+            .map(|reviewer| Reviewer {
+                state: reviewer
+                    .merge_request_interaction
+                    .and_then(|i| i.review_state)
+                    .map_or(ReviewState::Unreviewed, |s| {
+                        graphql_enum(&s, ReviewState::Unknown)
+                    }),
+                user: reviewer.user.into(),
+                // Not available over GraphQL
+                created_at: self.created_at,
+            })
+            .collect::<Vec<_>>();
+        let short = format!("!{}", self.iid);
+        MergeRequest {
+            assignees: self
+                .assignees
+                .map(|a| a.nodes.into_iter().map(User::from).collect())
+                .unwrap_or_default(),
+            author: self.author.map(User::from).unwrap_or_default(),
+            blocking_discussions_resolved: self.resolvable_discussions_count
+                == self.resolved_discussions_count,
+            unresolved_threads: self.resolvable_discussions_count.unwrap_or_default()
+                - self.resolved_discussions_count.unwrap_or_default(),
+            created_at: self.created_at,
+            description: self.description,
+            detailed_merge_status: self
+                .detailed_merge_status
+                .map_or(MergeStatus::Unknown, |s| {
+                    graphql_enum(&s, MergeStatus::Unknown)
+                }),
+            draft: self.draft,
+            has_conflicts: self.conflicts,
+            head_pipeline: self.head_pipeline.map(|pipeline| Pipeline {
+                id: graphql_id(&pipeline.id),
+                sha: pipeline.sha,
+                status: graphql_enum(&pipeline.status, PipelineStatus::Unknown),
+                web_url: pipeline
+                    .path
+                    .map(|path| format!("{web_base_url}{path}"))
+                    .unwrap_or_default(),
+                duration: TimeDelta::seconds(pipeline.duration.unwrap_or_default()),
+                queued_duration: TimeDelta::seconds(
+                    pipeline.queued_duration.unwrap_or_default() as i64
+                ),
+                coverage: pipeline.coverage.map(|c| format!("{c:.2}")),
+            }),
+            id: graphql_id(&self.id),
+            iid: self.iid.parse().unwrap_or_default(),
+            labels: self
+                .labels
+                .nodes
+                .into_iter()
+                .map(|label| Label {
+                    name: label.title,
+                    color: label.color,
+                    text_color: label.text_color,
+                    description: label.description,
+                })
+                .collect(),
+            merge_when_pipeline_succeeds: self.auto_merge_enabled,
+            milestone: self.milestone.map(|milestone| Milestone {
+                id: graphql_id(&milestone.id),
+                title: milestone.title,
+                state: milestone.state,
+                due_date: milestone.due_date,
+                web_url: format!("{web_base_url}{}", milestone.web_path),
+            }),
+            merged_at: self.merged_at,
+            project_id: self.project_id,
+            references: References {
+                full: self.reference,
+                relative: short.clone(),
+                short,
+            },
+            reviewers: reviewer_states.iter().map(|r| r.user.clone()).collect(),
+            reviewer_states,
+            sha: self.diff_head_sha,
+            source_branch: self.source_branch,
+            state: self.state,
+            target_branch: self.target_branch,
+            time_stats: TimeStats {
+                time_estimate: self.time_estimate,
+                total_time_spent: self.total_time_spent,
+            },
+            title: self.title,
+            updated_at: self.updated_at,
+            user_notes_count: self.user_notes_count.unwrap_or_default(),
+            web_url: self.web_url,
+            diffs: self.diff_stats.into_iter().map(Diff::from).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The numeric id of a GraphQL global id such as `gid://gitlab/MergeRequest/123`
+fn graphql_id(gid: &str) -> i64 {
+    gid.rsplit('/')
+        .next()
+        .and_then(|id| id.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Decode a GraphQL enum value, which are the upper case versions of the REST ones
+fn graphql_enum<T: DeserializeOwned>(value: &str, unknown: T) -> T {
+    serde_json::from_value(json!(value.to_lowercase())).unwrap_or(unknown)
+}
+
+/// The GraphQL filter variables equivalent to a merge requests query, an error for the parts of
+/// the query GraphQL has no equivalent of rather than leaving them out
+fn graphql_filters(query: &MergeRequestsQuery, namespace: &str) -> Result<serde_json::Value> {
+    let unsupported = [
+        (query.scope != Scope::All, "scopes other than all"),
+        (!query.non_archived, "including archived projects"),
+        (query.sudo.is_some(), "sudo"),
+    ];
+    if let Some((_, what)) = unsupported.iter().find(|(set, _)| *set) {
         return Err(anyhow!(
-            "fetching merge requests failed with status {}",
-            response.status()
+            "the graphql backend does not support {what}, use the rest backend"
         ));
+    }
+    let sort = match (&query.order_by, &query.sort) {
+        (OrderBy::CreatedAt, Sort::Asc) => "CREATED_ASC",
+        (OrderBy::CreatedAt, Sort::Desc) => "CREATED_DESC",
+        (OrderBy::UpdatedAt, Sort::Asc) => "UPDATED_ASC",
+        (OrderBy::UpdatedAt, Sort::Desc) => "UPDATED_DESC",
+        (OrderBy::Title, Sort::Asc) => "TITLE_ASC",
+        (OrderBy::Title, Sort::Desc) => "TITLE_DESC",
     };
+    let not_labels = query.not_labels.as_ref().map(|labels| {
+        labels
+            .split(',')
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+    });
+    let iids = query.iids.iter().map(i64::to_string).collect::<Vec<_>>();
+    Ok(json!({
+        "state": query.state.filter(|s| *s != State::Unknown),
+        "sourceBranches": query.source_branch.as_ref().map(|b| [b]),
+        "iids": (namespace == "project" && !iids.is_empty()).then_some(iids),
+        "draft": query.wip.as_ref().map(|w| *w == Wip::Yes),
+        "createdAfter": query.created_after,
+        "createdBefore": query.created_before,
+        "updatedAfter": query.updated_after,
+        "updatedBefore": query.updated_before,
+        "environmentName": query.environment,
+        "deployedAfter": query.deployed_after,
+        "deployedBefore": query.deployed_before,
+        "sort": sort,
+        "not": {
+            "labels": not_labels,
+            "authorUsername": query.not_author_username,
+            "milestoneTitle": query.not_milestone,
+        },
+    }))
+}
+
+/// Fetch the merge requests of a project or group with a single GraphQL query per page
+async fn fetch_merge_requests_graphql(
+    instance: &Instance,
+    namespace: &str,
+    full_path: &str,
+    filters: serde_json::Value,
+    first: usize,
+    max_results: Option<usize>,
+) -> Result<Vec<MergeRequest>> {
+    const MAX_PER_PAGE: usize = 100;
+
+    info!("fetching merge requests of {namespace} {full_path} over graphql");
+    let web_base_url = instance
+        .url
+        .trim_end_matches('/')
+        .trim_end_matches("/api/v4");
+    let subgroups = match namespace {
+        "group" => ", includeSubgroups: true",
+        _ => "",
+    };
+    let graphql_query = MERGE_REQUESTS_GRAPHQL
+        .replace("NAMESPACE", namespace)
+        .replace("SUBGROUPS", subgroups);
+    let first = first.clamp(1, MAX_PER_PAGE);
+
+    let mut merge_requests = Vec::new();
+    let mut after = None::<String>;
+    loop {
+        let mut variables = json!({
+            "fullPath": full_path,
+            "after": after,
+            "first": first,
+        });
+        if let (Some(variables), Some(filters)) = (variables.as_object_mut(), filters.as_object()) {
+            variables.extend(filters.clone());
+        }
+        let data: NamespaceData = send_graphql(instance, &graphql_query, variables).await?;
+        let connection = data
+            .project
+            .ok_or_else(|| anyhow!("{namespace} {full_path} not found"))?
+            .merge_requests;
+        merge_requests.extend(
+            connection
+                .nodes
+                .into_iter()
+                .map(|mr| mr.into_merge_request(web_base_url)),
+        );
+        if let Some(max_results) = max_results {
+            if merge_requests.len() >= max_results {
+                merge_requests.truncate(max_results);
+                break;
+            }
+        }
+        match connection.page_info {
+            PageInfo {
+                has_next_page: true,
+                end_cursor: Some(end_cursor),
+                ..
+            } => after = Some(end_cursor),
+            _ => break,
+        }
+    }
     info!("fetched {} merge requests", merge_requests.len());
     Ok(merge_requests)
 }
 
-/// If fetching a single merge request fails just swallow the error and return a copy of the
-/// supplied merge request
-async fn fetch_merge_request_no_fail(
-    gitlab_url: &str,
-    private_token: &str,
-    merge_request: &MergeRequest,
-) -> MergeRequest {
-    fetch_merge_request(gitlab_url, private_token, merge_request)
+/// Fetch the data only some merge requests need and GraphQL does not have, one request each
+async fn fetch_follow_ups(instance: &Instance, merge_request: &mut MergeRequest) {
+    let full = merge_request.references.full.clone();
+
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+
+    // Projects using external CI report to the commit statuses API instead of running pipelines
+    if let (None, Some(sha)) = (&merge_request.head_pipeline, &merge_request.sha) {
+        let request = client().get(format!(
+            "{gitlab_url}/projects/{project_id}/repository/commits/{sha}/statuses"
+        ));
+        let statuses: Vec<CommitStatus> = send_json(request, instance, "commit statuses")
+            .await
+            .inspect_err(|e| error!("failed fetching commit statuses of {full}: {e}"))
+            .unwrap_or_default();
+        merge_request.head_pipeline = Pipeline::from_commit_statuses(&statuses);
+    }
+
+    if merge_request.detailed_merge_status == MergeStatus::ExternalStatusChecks {
+        let request = client().get(format!(
+            "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/status_checks"
+        ));
+        merge_request.status_checks = send_json(request, instance, "status checks")
+            .await
+            .inspect_err(|e| error!("failed fetching status checks of {full}: {e}"))
+            .unwrap_or_default();
+    }
+
+    if merge_request.diffs.iter().any(Diff::needs_details) {
+        match fetch_diffs(instance, merge_request).await {
+            Ok(diffs) => merge_request.diffs = diffs,
+            Err(e) => error!("failed fetching diffs of {full}: {e}"),
+        }
+    }
+
+    // GraphQL has no diff versions, they are only needed to compare pushes with reviews
+    let review_submitted = merge_request.reviewer_states.iter().any(|r| {
+        matches!(
+            r.state,
+            ReviewState::RequestedChanges | ReviewState::Approved
+        )
+    });
+    if review_submitted {
+        let versions = fetch_versions(instance, project_id, merge_request_iid)
+            .await
+            .inspect_err(|e| error!("failed fetching versions of {full}: {e}"))
+            .unwrap_or_default();
+        merge_request.last_pushed_at = versions.iter().map(|v| v.created_at).max();
+        // The head of the diff at approval time is the last version pushed before it
+        merge_request.approved_sha = merge_request.approved_at.and_then(|approved_at| {
+            versions
+                .iter()
+                .filter(|v| v.created_at <= approved_at)
+                .max_by_key(|v| v.created_at)
+                .map(|v| v.head_commit_sha.clone())
+        });
+    }
+}
+
+/// Fetch the diff versions of a merge request, newest first
+async fn fetch_versions(
+    instance: &Instance,
+    project_id: i64,
+    merge_request_iid: i64,
+) -> Result<Vec<MergeRequestVersion>> {
+    let gitlab_url = &instance.url;
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/versions"
+    ));
+    send_json(request, instance, "versions").await
+}
+
+const APPROVALS_GRAPHQL: &str = r#"
+query($fullPath: ID!, $iids: [String!], $first: Int) {
+  project(fullPath: $fullPath) {
+    mergeRequests(iids: $iids, first: $first) {
+      nodes {
+        iid approvalsRequired approvalsLeft
+        approvedBy { nodes { ...user } }
+        approvalState {
+          rules {
+            name type approved approvalsRequired
+            approvedBy { nodes { ...user } }
+            eligibleApprovers { ...user }
+          }
+        }
+      }
+    }
+  }
+}
+
+fragment user on User { id username name avatarUrl webUrl state }
+"#;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlApprovals {
+    iid: String,
+    approvals_required: Option<i64>,
+    approvals_left: Option<i64>,
+    approved_by: Option<Connection<GraphqlUser>>,
+    approval_state: Option<GraphqlApprovalState>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlApprovalState {
+    #[serde(default)]
+    rules: Vec<GraphqlApprovalRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlApprovalRule {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    rule_type: Option<String>,
+    #[serde(default)]
+    approved: bool,
+    approvals_required: Option<i64>,
+    approved_by: Option<Connection<GraphqlUser>>,
+    #[serde(default)]
+    eligible_approvers: Vec<GraphqlUser>,
+}
+
+impl GraphqlApprovals {
+    fn apply(self, merge_request: &mut MergeRequest) {
+        let users = |connection: Option<Connection<GraphqlUser>>| {
+            connection
+                .map(|c| c.nodes.into_iter().map(User::from).collect())
+                .unwrap_or_default()
+        };
+        merge_request.approvals_required = self.approvals_required.unwrap_or_default();
+        merge_request.approvals_left = self.approvals_left.unwrap_or_default();
+        merge_request.approved_by = users(self.approved_by);
+        merge_request.approval_rules = self
+            .approval_state
+            .map(|state| state.rules)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|rule| ApprovalRule {
+                name: rule.name.unwrap_or_default(),
+                rule_type: rule.rule_type.map_or(ApprovalRuleType::Unknown, |t| {
+                    graphql_enum(&t, ApprovalRuleType::Unknown)
+                }),
+                approved: rule.approved,
+                approvals_required: rule.approvals_required.unwrap_or_default(),
+                approved_by: users(rule.approved_by),
+                eligible_approvers: rule
+                    .eligible_approvers
+                    .into_iter()
+                    .map(User::from)
+                    .collect(),
+            })
+            .collect();
+    }
+}
+
+/// Fetch the approvals and approval rules of merge requests of a project keyed by iid. These are
+/// in a query of their own as GitLab Free does not have all of the fields.
+async fn fetch_approvals(
+    instance: &Instance,
+    project_path: &str,
+    iids: &[i64],
+) -> Result<HashMap<i64, GraphqlApprovals>> {
+    #[derive(Deserialize)]
+    struct Data {
+        project: Option<Project>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Project {
+        merge_requests: Connection<GraphqlApprovals>,
+    }
+
+    let variables = json!({
+        "fullPath": project_path,
+        "iids": iids.iter().map(i64::to_string).collect::<Vec<_>>(),
+        "first": iids.len(),
+    });
+    let data: Data = send_graphql(instance, APPROVALS_GRAPHQL, variables).await?;
+    Ok(data
+        .project
+        .map(|p| p.merge_requests.nodes)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|approvals| (approvals.iid.parse().unwrap_or_default(), approvals))
+        .collect())
+}
+
+const ACTIVITY_GRAPHQL: &str = r#"
+query($fullPath: ID!, $iids: [String!], $first: Int) {
+  project(fullPath: $fullPath) {
+    mergeRequests(iids: $iids, first: $first) {
+      nodes {
+        iid userNotesCount
+        reviewers { nodes { username mergeRequestInteraction { reviewState } } }
+        notes(last: 20) {
+          pageInfo { hasPreviousPage startCursor }
+          nodes { id body system createdAt author { ...user } }
+        }
+        headPipeline {
+          jobs(statuses: [FAILED], retried: false) { nodes { name allowFailure } }
+          testReportSummary { total { count success failed skipped error } }
+        }
+      }
+    }
+  }
+}
+
+fragment user on User { id username name avatarUrl webUrl state }
+"#;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlActivity {
+    iid: String,
+    user_notes_count: Option<i64>,
+    #[serde(default)]
+    reviewers: Option<Connection<GraphqlReviewState>>,
+    notes: Connection<GraphqlNote>,
+    head_pipeline: Option<GraphqlPipelineFailures>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlReviewState {
+    username: String,
+    merge_request_interaction: Option<GraphqlInteraction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlNote {
+    id: String,
+    body: String,
+    system: bool,
+    created_at: DateTime<Utc>,
+    author: Option<GraphqlUser>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlPipelineFailures {
+    jobs: Option<Connection<GraphqlJob>>,
+    test_report_summary: Option<TestReportSummary>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphqlJob {
+    name: String,
+    #[serde(default)]
+    allow_failure: bool,
+}
+
+impl From<GraphqlNote> for Note {
+    fn from(note: GraphqlNote) -> Self {
+        Note {
+            id: graphql_id(&note.id),
+            body: note.body,
+            author: note.author.map(User::from).unwrap_or_default(),
+            created_at: note.created_at,
+            system: note.system,
+        }
+    }
+}
+
+impl GraphqlActivity {
+    // GitLab adds these system notes when a review is submitted
+    const APPROVED_NOTE: &'static str = "approved this merge request";
+    const REQUESTED_CHANGES_NOTE: &'static str = "requested changes";
+
+    /// Whether earlier notes than the fetched ones are needed, for a comment when the fetched
+    /// notes are all system notes or for the note of a review a reviewer submitted
+    fn misses_notes(&self) -> bool {
+        self.notes.page_info.has_previous_page && (self.misses_comment() || self.misses_review())
+    }
+
+    fn misses_comment(&self) -> bool {
+        self.user_notes_count.unwrap_or_default() > 0 && self.notes.nodes.iter().all(|n| n.system)
+    }
+
+    fn misses_review(&self) -> bool {
+        let noted = |username: &str, prefix: &str| {
+            self.notes.nodes.iter().any(|n| {
+                n.system
+                    && n.body.starts_with(prefix)
+                    && n.author.as_ref().is_some_and(|a| a.username == username)
+            })
+        };
+        self.reviewers
+            .iter()
+            .flat_map(|reviewers| &reviewers.nodes)
+            .any(|reviewer| {
+                let state = reviewer
+                    .merge_request_interaction
+                    .as_ref()
+                    .and_then(|i| i.review_state.as_deref())
+                    .map_or(ReviewState::Unreviewed, |s| {
+                        graphql_enum(s, ReviewState::Unknown)
+                    });
+                match state {
+                    ReviewState::Approved => !noted(&reviewer.username, Self::APPROVED_NOTE),
+                    ReviewState::RequestedChanges => {
+                        !noted(&reviewer.username, Self::REQUESTED_CHANGES_NOTE)
+                    }
+                    _ => false,
+                }
+            })
+    }
+
+    /// Put the page of notes before the ones already fetched, oldest first like GraphQL has them
+    fn prepend(&mut self, earlier: Connection<GraphqlNote>) {
+        let later = std::mem::replace(&mut self.notes, earlier);
+        self.notes.nodes.extend(later.nodes);
+    }
+
+    fn apply(self, merge_request: &mut MergeRequest) {
+        // Newest first
+        let notes = self
+            .notes
+            .nodes
+            .into_iter()
+            .rev()
+            .map(Note::from)
+            .collect::<Vec<_>>();
+        merge_request.latest_comment = notes.iter().find(|n| !n.system).cloned();
+        let latest_note_at = |prefix: &str| {
+            notes
+                .iter()
+                .filter(|n| n.system && n.body.starts_with(prefix))
+                .map(|n| n.created_at)
+                .max()
+        };
+        merge_request.changes_requested_at = latest_note_at(Self::REQUESTED_CHANGES_NOTE);
+        merge_request.approved_at = latest_note_at(Self::APPROVED_NOTE);
+
+        let failed = merge_request
+            .head_pipeline
+            .as_ref()
+            .is_some_and(|p| p.status == PipelineStatus::Failed && p.has_jobs());
+        if let (true, Some(pipeline)) = (failed, self.head_pipeline) {
+            merge_request.failed_jobs = pipeline
+                .jobs
+                .map(|jobs| jobs.nodes)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|j| !j.allow_failure)
+                .map(|j| j.name)
+                .collect();
+            // Pipelines without test reports have all zero totals
+            merge_request.test_totals = pipeline
+                .test_report_summary
+                .map(|summary| summary.total)
+                .filter(|t| t.count > 0);
+        }
+    }
+}
+
+/// Fetch the latest notes and the failures of the head pipelines of merge requests of a project
+/// keyed by iid
+async fn fetch_activity(
+    instance: &Instance,
+    project_path: &str,
+    iids: &[i64],
+) -> Result<HashMap<i64, GraphqlActivity>> {
+    #[derive(Deserialize)]
+    struct Data {
+        project: Option<Project>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Project {
+        merge_requests: Connection<GraphqlActivity>,
+    }
+
+    let variables = json!({
+        "fullPath": project_path,
+        "iids": iids.iter().map(i64::to_string).collect::<Vec<_>>(),
+        "first": iids.len(),
+    });
+    let data: Data = send_graphql(instance, ACTIVITY_GRAPHQL, variables).await?;
+    let activity = data
+        .project
+        .map(|p| p.merge_requests.nodes)
+        .unwrap_or_default();
+    Ok(stream::iter(activity)
+        .map(|mut activity| async move {
+            if activity.misses_notes() {
+                fetch_earlier_notes(instance, project_path, &mut activity).await;
+            }
+            (activity.iid.parse().unwrap_or_default(), activity)
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await)
+}
+
+/// Page back through the notes of a merge request until the latest comment and the notes of the
+/// submitted reviews turn up, for merge requests whose latest notes are system notes such as
+/// pushes and label changes
+async fn fetch_earlier_notes(
+    instance: &Instance,
+    project_path: &str,
+    activity: &mut GraphqlActivity,
+) {
+    const QUERY: &str = r#"
+        query($fullPath: ID!, $iid: String!, $before: String) {
+            project(fullPath: $fullPath) {
+                mergeRequest(iid: $iid) {
+                    notes(last: 100, before: $before) {
+                        pageInfo { hasPreviousPage startCursor }
+                        nodes { id body system createdAt author { ...user } }
+                    }
+                }
+            }
+        }
+
+        fragment user on User { id username name avatarUrl webUrl state }
+    "#;
+    /// Merge requests with more notes than this since the last comment or review go without it
+    const MAX_PAGES: usize = 5;
+    #[derive(Deserialize)]
+    struct Data {
+        project: Option<Project>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Project {
+        merge_request: Option<Notes>,
+    }
+    #[derive(Deserialize)]
+    struct Notes {
+        notes: Connection<GraphqlNote>,
+    }
+
+    for _ in 0..MAX_PAGES {
+        if !activity.misses_notes() {
+            return;
+        }
+        let variables = json!({
+            "fullPath": project_path,
+            "iid": activity.iid,
+            "before": activity.notes.page_info.start_cursor,
+        });
+        let data: Data = match send_graphql(instance, QUERY, variables).await {
+            Ok(data) => data,
+            Err(e) => {
+                error!(
+                    "failed fetching notes of {project_path}!{}: {e}",
+                    activity.iid
+                );
+                return;
+            }
+        };
+        let Some(Notes { notes }) = data.project.and_then(|p| p.merge_request) else {
+            return;
+        };
+        activity.prepend(notes);
+    }
+}
+
+/// Fetch the coverage of the latest successful pipeline of each branch of a project, keyed by
+/// branch. Branches whose pipelines report no coverage or can not be fetched are left out.
+async fn fetch_branch_coverage(
+    instance: &Instance,
+    project_path: &str,
+    branches: &[&str],
+) -> HashMap<String, String> {
+    const QUERY: &str = r#"
+        query($fullPath: ID!, $ref: String!) {
+            project(fullPath: $fullPath) {
+                pipelines(ref: $ref, status: SUCCESS, first: 1) { nodes { coverage } }
+            }
+        }
+    "#;
+    #[derive(Deserialize)]
+    struct Data {
+        project: Option<Project>,
+    }
+    #[derive(Deserialize)]
+    struct Project {
+        pipelines: Connection<Coverage>,
+    }
+    #[derive(Deserialize)]
+    struct Coverage {
+        coverage: Option<f64>,
+    }
+
+    let futures = branches.iter().map(|branch| async move {
+        let variables = json!({ "fullPath": project_path, "ref": branch });
+        let data: Data = send_graphql(instance, QUERY, variables)
+            .await
+            .inspect_err(|e| error!("failed fetching coverage of {project_path} {branch}: {e}"))
+            .ok()?;
+        let coverage = data.project?.pipelines.nodes.into_iter().next()?.coverage?;
+        Some((branch.to_string(), format!("{coverage:.2}")))
+    });
+    join_all(futures).await.into_iter().flatten().collect()
+}
+
+/// Compare the security (SAST and dependency scanning) and code quality reports of merge requests
+/// of a project against their target branches, keyed by iid. A report is `None` if the project
+/// does not produce it or the comparison is not ready.
+async fn fetch_report_deltas(
+    instance: &Instance,
+    project_path: &str,
+    iids: &[i64],
+) -> Result<HashMap<i64, (Option<ReportDelta>, Option<ReportDelta>)>> {
+    const QUERY: &str = r#"
+        query($fullPath: ID!, $iids: [String!], $first: Int) {
+            project(fullPath: $fullPath) {
+                mergeRequests(iids: $iids, first: $first) { nodes {
+                    iid
+                    sast: findingReportsComparer(reportType: SAST) {
+                        status
+                        report { added { uuid } fixed { uuid } }
+                    }
+                    dependencyScanning: findingReportsComparer(reportType: DEPENDENCY_SCANNING) {
+                        status
+                        report { added { uuid } fixed { uuid } }
+                    }
+                    codequalityReportsComparer {
+                        status
+                        report { newErrors { fingerprint } resolvedErrors { fingerprint } }
+                    }
+                } }
+            }
+        }
+    "#;
+
+    #[derive(Deserialize)]
+    struct Data {
+        project: Option<Project>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Project {
+        merge_requests: Connection<Comparers>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Comparers {
+        iid: String,
+        sast: Option<Comparer>,
+        dependency_scanning: Option<Comparer>,
+        codequality_reports_comparer: Option<CodeQualityComparer>,
+    }
+    #[derive(Deserialize)]
+    struct Comparer {
+        status: String,
+        report: Option<Report>,
+    }
+    #[derive(Deserialize)]
+    struct Report {
+        #[serde(default)]
+        added: Vec<serde_json::Value>,
+        #[serde(default)]
+        fixed: Vec<serde_json::Value>,
+    }
+    #[derive(Deserialize)]
+    struct CodeQualityComparer {
+        status: String,
+        report: Option<CodeQualityReport>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct CodeQualityReport {
+        #[serde(default)]
+        new_errors: Vec<serde_json::Value>,
+        #[serde(default)]
+        resolved_errors: Vec<serde_json::Value>,
+    }
+
+    let variables = json!({
+        "fullPath": project_path,
+        "iids": iids.iter().map(i64::to_string).collect::<Vec<_>>(),
+        "first": iids.len(),
+    });
+    let data: Data = send_graphql(instance, QUERY, variables).await?;
+    let Some(project) = data.project else {
+        return Ok(HashMap::new());
+    };
+
+    Ok(project
+        .merge_requests
+        .nodes
+        .into_iter()
+        .map(|comparers| {
+            let security_reports = [comparers.sast, comparers.dependency_scanning]
+                .into_iter()
+                .flatten()
+                .filter(|c| c.status == "PARSED")
+                .filter_map(|c| c.report)
+                .collect::<Vec<_>>();
+            let security_report = (!security_reports.is_empty()).then(|| ReportDelta {
+                added: security_reports.iter().map(|r| r.added.len()).sum(),
+                fixed: security_reports.iter().map(|r| r.fixed.len()).sum(),
+            });
+
+            let code_quality_report = comparers
+                .codequality_reports_comparer
+                .filter(|c| c.status == "PARSED")
+                .and_then(|c| c.report)
+                .map(|r| ReportDelta {
+                    added: r.new_errors.len(),
+                    fixed: r.resolved_errors.len(),
+                });
+
+            (
+                comparers.iid.parse().unwrap_or_default(),
+                (security_report, code_quality_report),
+            )
+        })
+        .collect())
+}
+
+/// Fetch the deploy freeze periods of a project
+pub async fn fetch_freeze_periods(
+    instance: &Instance,
+    project_path: &str,
+) -> Result<Vec<FreezePeriod>> {
+    let gitlab_url = &instance.url;
+    info!("fetching freeze periods for {project_path}");
+    let project_path = encode_path(project_path);
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_path}/freeze_periods"
+    ));
+    send_json(request, instance, "freeze periods").await
+}
+
+/// Fetch the broadcast messages of the instance, including inactive ones
+pub async fn fetch_broadcast_messages(instance: &Instance) -> Result<Vec<BroadcastMessage>> {
+    let gitlab_url = &instance.url;
+    info!("fetching broadcast messages");
+    let request = client().get(format!("{gitlab_url}/broadcast_messages"));
+    send_json(request, instance, "broadcast messages").await
+}
+
+/// Fetch the most recent releases of a project, newest first
+pub async fn fetch_releases(instance: &Instance, project_path: &str) -> Result<Vec<Release>> {
+    let gitlab_url = &instance.url;
+    info!("fetching releases for {project_path}");
+    let project_path = encode_path(project_path);
+    let request = client()
+        .get(format!("{gitlab_url}/projects/{project_path}/releases"))
+        .query(&[("order_by", "released_at"), ("sort", "desc")]);
+    send_json(request, instance, "releases").await
+}
+
+/// Fetch the commits reachable from `to` but not from `from`, two refs (tags, branches, or SHAs)
+/// of a project
+pub async fn fetch_compare(
+    instance: &Instance,
+    project_path: &str,
+    from: &str,
+    to: &str,
+) -> Result<Compare> {
+    let gitlab_url = &instance.url;
+    info!("comparing {project_path} {from}...{to}");
+    let encoded_project_path = encode_path(project_path);
+    let request = client()
+        .get(format!(
+            "{gitlab_url}/projects/{encoded_project_path}/repository/compare"
+        ))
+        .query(&[("from", from), ("to", to)]);
+    let compare: Compare = send_json(request, instance, "compare").await?;
+    if compare.compare_timeout {
+        return Err(anyhow!("comparing {from}...{to} timed out"));
+    }
+    Ok(compare)
+}
+
+/// Fetch the merge requests each commit of a project is part of, in the order of the commits. A
+/// request per commit, at most [`MAX_CONCURRENT_REQUESTS`] at once. The commits whose merge
+/// requests fail to fetch are logged and get none.
+pub async fn fetch_merge_requests_of_commits(
+    instance: &Instance,
+    project_path: &str,
+    commits: &[Commit],
+) -> Vec<Vec<MergeRequest>> {
+    let gitlab_url = &instance.url;
+    let encoded_project_path = encode_path(project_path);
+    stream::iter(commits)
+        .map(|commit| {
+            let commit_id = &commit.id;
+            let request = client().get(format!(
+                "{gitlab_url}/projects/{encoded_project_path}/repository/commits/{commit_id}/merge_requests"
+            ));
+            async move {
+                send_json::<Vec<MergeRequest>>(request, instance, "commit merge requests")
+                    .await
+                    .inspect_err(|e| error!("failed fetching merge requests of {commit_id}: {e}"))
+                    .unwrap_or_default()
+            }
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect()
         .await
-        .ok()
-        .unwrap_or_else(|| merge_request.clone())
 }
 
-async fn fetch_merge_request(
-    gitlab_url: &str,
-    private_token: &str,
-    merge_request: &MergeRequest,
+/// Fetch the merged merge requests that brought in the commits between two refs (tags, branches,
+/// or SHAs) of a project
+pub async fn fetch_merge_requests_between_refs(
+    instance: &Instance,
+    project_path: &str,
+    from: &str,
+    to: &str,
+) -> Result<Vec<MergeRequest>> {
+    let compare = fetch_compare(instance, project_path, from, to).await?;
+    let mut merge_requests = Vec::<MergeRequest>::new();
+    for merge_request in fetch_merge_requests_of_commits(instance, project_path, &compare.commits)
+        .await
+        .into_iter()
+        .flatten()
+    {
+        // A merge request is associated with every commit it contains so only keep the first
+        // occurrence
+        if merge_request.state == State::Merged
+            && !merge_requests.iter().any(|mr| mr.id == merge_request.id)
+        {
+            merge_requests.push(merge_request);
+        }
+    }
+    merge_requests.sort_by_key(|mr| std::cmp::Reverse(mr.merged_at));
+    info!("found {} merge requests between refs", merge_requests.len());
+    Ok(merge_requests)
+}
+
+/// Fetch all the branches of a project
+pub async fn fetch_branches(instance: &Instance, project_path: &str) -> Result<Vec<Branch>> {
+    let gitlab_url = &instance.url;
+    info!("fetching branches for {project_path}");
+    let encoded_project_path = encode_path(project_path);
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{encoded_project_path}/repository/branches"
+    ));
+    send_paginated(
+        request,
+        instance,
+        "branches",
+        Pagination::Offset,
+        None,
+        None,
+    )
+    .await
+}
+
+/// Count how many commits each branch is ahead and behind of the default branch, yielding the
+/// branches by name as they are counted, at most [`MAX_CONCURRENT_REQUESTS`] at once. Merged
+/// branches have nothing ahead so only take a single compare.
+pub fn fetch_branch_divergences<'a>(
+    instance: &'a Instance,
+    project_path: &'a str,
+    default_branch: &'a str,
+    branches: &'a [Branch],
+) -> impl Stream<Item = (String, Result<BranchDivergence>)> + 'a {
+    stream::iter(branches)
+        .map(move |branch| async move {
+            let ahead = async {
+                match branch.merged || branch.default {
+                    true => Ok(0),
+                    false => {
+                        count_commits_between(instance, project_path, default_branch, &branch.name)
+                            .await
+                    }
+                }
+            };
+            let behind = async {
+                match branch.default {
+                    true => Ok(0),
+                    false => {
+                        count_commits_between(instance, project_path, &branch.name, default_branch)
+                            .await
+                    }
+                }
+            };
+            let divergence = futures::try_join!(ahead, behind)
+                .map(|(ahead, behind)| BranchDivergence { ahead, behind });
+            (branch.name.clone(), divergence)
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+}
+
+/// Fetch a single branch of a project
+pub async fn fetch_branch(instance: &Instance, project_id: i64, branch: &str) -> Result<Branch> {
+    let gitlab_url = &instance.url;
+    let branch = encode_path(branch);
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/repository/branches/{branch}"
+    ));
+    send_json(request, instance, "branch").await
+}
+
+/// Delete a branch of a project
+pub async fn delete_branch(instance: &Instance, project_path: &str, branch: &str) -> Result<()> {
+    let gitlab_url = &instance.url;
+    info!("deleting branch {branch} of {project_path}");
+    let project_path = encode_path(project_path);
+    let branch = encode_path(branch);
+    let request = client().delete(format!(
+        "{gitlab_url}/projects/{project_path}/repository/branches/{branch}"
+    ));
+    send(request, instance, "branch deletion").await?;
+    Ok(())
+}
+
+async fn count_commits_between(
+    instance: &Instance,
+    project_path: &str,
+    from: &str,
+    to: &str,
+) -> Result<usize> {
+    let gitlab_url = &instance.url;
+    let project_path = encode_path(project_path);
+    let request = client()
+        .get(format!(
+            "{gitlab_url}/projects/{project_path}/repository/compare"
+        ))
+        .query(&[("from", from), ("to", to), ("straight", "false")]);
+    let compare: Compare = send_json(request, instance, "compare").await?;
+    Ok(compare.commits.len())
+}
+
+/// Fetch a single merge request without the extra data of the full pass
+pub async fn fetch_merge_request_by_iid(
+    instance: &Instance,
+    project_id: i64,
+    merge_request_iid: i64,
 ) -> Result<MergeRequest> {
-    let full = &merge_request.references.full;
+    let gitlab_url = &instance.url;
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}",
+    ));
+    MergeRequest::from_raw(send_json(request, instance, "merge request").await?)
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+struct Block {
+    blocking_merge_request: BlockingMergeRequest,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+struct BlockingMergeRequest {
+    id: i64,
+}
+
+/// Fetch the ids of the merge requests which have to be merged before this one. Blocking merge
+/// requests are a premium feature, other instances respond with an error.
+pub async fn fetch_blocking_merge_request_ids(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+) -> Result<Vec<i64>> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/blocks"
+    ));
+    let blocks: Vec<Block> = send_json(request, instance, "blocking merge requests").await?;
+    Ok(blocks
+        .into_iter()
+        .map(|b| b.blocking_merge_request.id)
+        .collect())
+}
 
+/// Merge a merge request right away
+pub async fn merge_merge_request(instance: &Instance, merge_request: &MergeRequest) -> Result<()> {
+    let gitlab_url = &instance.url;
     let project_id = merge_request.project_id;
     let merge_request_iid = merge_request.iid;
+    info!("merging {}", merge_request.references.full);
+    let request = client().put(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/merge"
+    ));
+    send(request, instance, "merge").await?;
+    Ok(())
+}
 
-    let response = client()
+/// A file changed by a merge request
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Diff {
+    pub old_path: String,
+    pub new_path: String,
+    /// Summary of the unified diff, which is not kept as it can be huge
+    #[serde(default, rename = "diff")]
+    pub summary: DiffSummary,
+    /// Only reported by GitLab 16.9 and later over REST. Merge requests whose GraphQL diff stats
+    /// don't need the REST diffs only get the file name checks of
+    /// [`crate::files::change_warnings`].
+    #[serde(default)]
+    pub generated_file: bool,
+    /// Too large for GitLab to show the diff at all
+    #[serde(default)]
+    pub too_large: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(from = "String")]
+pub struct DiffSummary {
+    pub additions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
+/// Files with more changed lines than this might be too large for GitLab to show
+const MAYBE_TOO_LARGE_LINES: usize = 2000;
+
+impl Diff {
+    /// Whether the line counts of the GraphQL diff stats can't tell if the file is binary or too
+    /// large to show. Binary files have no changed lines, but neither do renames, mode changes,
+    /// and empty files.
+    fn needs_details(&self) -> bool {
+        let changed_lines = self.summary.changed_lines();
+        changed_lines == 0 || changed_lines > MAYBE_TOO_LARGE_LINES
+    }
+}
+
+impl DiffSummary {
+    pub fn changed_lines(&self) -> usize {
+        self.additions + self.deletions
+    }
+}
+
+impl From<String> for DiffSummary {
+    fn from(diff: String) -> Self {
+        DiffSummary {
+            // GitLab leaves out the file headers, every line starting with + or - is a change
+            additions: diff.lines().filter(|l| l.starts_with('+')).count(),
+            deletions: diff.lines().filter(|l| l.starts_with('-')).count(),
+            binary: diff.starts_with("Binary files "),
+        }
+    }
+}
+
+/// Fetch the files a merge request changes
+pub async fn fetch_diffs(instance: &Instance, merge_request: &MergeRequest) -> Result<Vec<Diff>> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/diffs"
+    ));
+    send_paginated(request, instance, "diffs", Pagination::Offset, None, None).await
+}
+
+/// Fetch the latest jobs of a pipeline, only the ones with the given status if any
+pub async fn fetch_pipeline_jobs(
+    instance: &Instance,
+    project_id: i64,
+    pipeline_id: i64,
+    scope: Option<PipelineStatus>,
+) -> Result<Vec<Job>> {
+    let gitlab_url = &instance.url;
+    info!("fetching jobs of pipeline {pipeline_id}");
+    let mut request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/pipelines/{pipeline_id}/jobs"
+    ));
+    if let Some(scope) = scope {
+        request = request.query(&[("scope[]", scope.to_string())]);
+    }
+    send_paginated(request, instance, "jobs", Pagination::Offset, None, None).await
+}
+
+/// Fetch the test reports of a pipeline with every test case, which can be large
+pub async fn fetch_test_report(
+    instance: &Instance,
+    project_id: i64,
+    pipeline_id: i64,
+) -> Result<TestReport> {
+    let gitlab_url = &instance.url;
+    info!("fetching the test report of pipeline {pipeline_id}");
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/pipelines/{pipeline_id}/test_report"
+    ));
+    send_json(request, instance, "test report").await
+}
+
+/// Fetch the commits of a merge request, newest first
+pub async fn fetch_merge_request_commits(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+) -> Result<Vec<Commit>> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!("fetching commits of {}", merge_request.references.full);
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/commits"
+    ));
+    send_paginated(request, instance, "commits", Pagination::Offset, None, None).await
+}
+
+/// Fetch the users who took part in a merge request, by commenting, reviewing or authoring it
+pub async fn fetch_participants(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+) -> Result<Vec<User>> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!("fetching participants of {}", merge_request.references.full);
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/participants"
+    ));
+    send_paginated(
+        request,
+        instance,
+        "participants",
+        Pagination::Offset,
+        None,
+        None,
+    )
+    .await
+}
+
+/// Fetch the issues a merge request closes when it is merged
+pub async fn fetch_closes_issues(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+) -> Result<Vec<Issue>> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!(
+        "fetching issues closed by {}",
+        merge_request.references.full
+    );
+    let request = client().get(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/closes_issues"
+    ));
+    send_paginated(
+        request,
+        instance,
+        "closed issues",
+        Pagination::Offset,
+        None,
+        None,
+    )
+    .await
+}
+
+/// Where GitLab looks for the code owners file, in order
+const CODEOWNERS_PATHS: [&str; 3] = ["CODEOWNERS", "docs/CODEOWNERS", ".gitlab/CODEOWNERS"];
+
+/// Fetch the code owners file of the default branch of a project, if it has one
+pub async fn fetch_codeowners(instance: &Instance, project_id: i64) -> Result<Option<String>> {
+    let gitlab_url = &instance.url;
+    for path in CODEOWNERS_PATHS {
+        let file_path = encode_path(path);
+        let request = client()
+            .get(format!(
+                "{gitlab_url}/projects/{project_id}/repository/files/{file_path}/raw"
+            ))
+            .query(&[("ref", "HEAD")]);
+        let response = send_any_status(request, instance).await?;
+        if response.status() != StatusCode::NOT_FOUND {
+            return Ok(Some(successful(response, "code owners")?.text().await?));
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Group {
+    pub full_path: String,
+}
+
+/// Fetch the groups the authenticated user is a member of
+pub async fn fetch_user_groups(instance: &Instance) -> Result<Vec<Group>> {
+    let gitlab_url = &instance.url;
+    let request = client()
+        .get(format!("{gitlab_url}/groups"))
+        .query(&[("min_access_level", "10")]);
+    send_paginated(request, instance, "groups", Pagination::Offset, None, None).await
+}
+
+/// Whether GitLab is still rebasing a merge request and why the last rebase failed
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct RebaseStatus {
+    #[serde(default)]
+    pub rebase_in_progress: bool,
+    pub merge_error: Option<String>,
+}
+
+/// Rebase the source branch of a merge request onto its target branch. The rebase runs in the
+/// background, follow it with [`fetch_rebase_status`].
+pub async fn rebase_merge_request(instance: &Instance, merge_request: &MergeRequest) -> Result<()> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!("rebasing {}", merge_request.references.full);
+    let request = client().put(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/rebase"
+    ));
+    send(request, instance, "rebase").await?;
+    Ok(())
+}
+
+pub async fn fetch_rebase_status(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+) -> Result<RebaseStatus> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    let request = client()
         .get(format!(
-            "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}",
+            "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}"
+        ))
+        .query(&[("include_rebase_in_progress", "true")]);
+    send_json(request, instance, "rebase status").await
+}
+
+/// Which fields of a merge request a search matches
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, EnumIter)]
+pub enum SearchIn {
+    #[default]
+    #[strum(serialize = "title,description")]
+    TitleAndDescription,
+    #[strum(serialize = "title")]
+    Title,
+    #[strum(serialize = "description")]
+    Description,
+}
+
+/// Search all the merge requests visible to the user
+pub async fn search_merge_requests(
+    instance: &Instance,
+    search: &str,
+    search_in: SearchIn,
+) -> Result<Vec<MergeRequest>> {
+    let gitlab_url = &instance.url;
+    let search_in = search_in.to_string();
+    let request = client()
+        .get(format!("{gitlab_url}/merge_requests"))
+        .query(&[
+            ("search", search),
+            ("in", &search_in),
+            ("scope", "all"),
+            ("per_page", "20"),
+            ("with_labels_details", "true"),
+        ]);
+    send_json(request, instance, "merge request search").await
+}
+
+/// Fetch the projects the user starred
+pub async fn fetch_starred_projects(instance: &Instance) -> Result<Vec<Project>> {
+    let gitlab_url = &instance.url;
+    let request = client().get(format!("{gitlab_url}/projects")).query(&[
+        ("starred", "true"),
+        ("simple", "true"),
+        ("order_by", "id"),
+        ("sort", "asc"),
+    ]);
+    send_paginated(
+        request,
+        instance,
+        "starred projects",
+        Pagination::Keyset,
+        None,
+        None,
+    )
+    .await
+}
+
+/// Check whether the browser has a GitLab session the API accepts, returning its user
+pub async fn detect_session(instance: &Instance) -> Result<User> {
+    let instance = Instance {
+        auth: Auth::Session,
+        ..instance.clone()
+    };
+    fetch_current_user(&instance).await
+}
+
+/// Fetch the user the private token belongs to
+pub async fn fetch_current_user(instance: &Instance) -> Result<User> {
+    let gitlab_url = &instance.url;
+    let request = client().get(format!("{gitlab_url}/user"));
+    send_json(request, instance, "current user").await
+}
+
+/// Approve a merge request as the authenticated user
+pub async fn approve_merge_request(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+) -> Result<()> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!("approving {}", merge_request.references.full);
+    let request = client().post(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/approve"
+    ));
+    send(request, instance, "approve").await?;
+    Ok(())
+}
+
+/// Replace the assignees of a merge request
+pub async fn assign_merge_request(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+    assignee_ids: &[i64],
+) -> Result<()> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!(
+        "assigning {} to {assignee_ids:?}",
+        merge_request.references.full
+    );
+    let request = client()
+        .put(format!(
+            "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}"
         ))
-        .header("PRIVATE-TOKEN", private_token)
+        .json(&json!({ "assignee_ids": assignee_ids }));
+    send(request, instance, "assign").await?;
+    Ok(())
+}
+
+/// Replace the reviewers of a merge request
+pub async fn set_reviewers(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+    reviewer_ids: &[i64],
+) -> Result<()> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!(
+        "setting reviewers of {} to {reviewer_ids:?}",
+        merge_request.references.full
+    );
+    let request = client()
+        .put(format!(
+            "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}"
+        ))
+        .json(&json!({ "reviewer_ids": reviewer_ids }));
+    send(request, instance, "set reviewers").await?;
+    Ok(())
+}
+
+/// Add a merge request to the authenticated user's todo list
+pub async fn create_todo(instance: &Instance, merge_request: &MergeRequest) -> Result<()> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!("creating a todo for {}", merge_request.references.full);
+    let request = client().post(format!(
+        "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/todo"
+    ));
+    send(request, instance, "todo").await?;
+    Ok(())
+}
+
+/// Comment on a merge request
+pub async fn post_note(
+    instance: &Instance,
+    merge_request: &MergeRequest,
+    body: &str,
+) -> Result<()> {
+    let gitlab_url = &instance.url;
+    let project_id = merge_request.project_id;
+    let merge_request_iid = merge_request.iid;
+    info!("commenting on {}", merge_request.references.full);
+    let request = client()
+        .post(format!(
+            "{gitlab_url}/projects/{project_id}/merge_requests/{merge_request_iid}/notes"
+        ))
+        .json(&json!({ "body": body }));
+    send(request, instance, "note").await?;
+    Ok(())
+}
+
+/// Fetch the content of the authenticated user's snippet with the given title
+pub async fn fetch_snippet_content(instance: &Instance, title: &str) -> Result<Option<String>> {
+    let gitlab_url = &instance.url;
+    let Some(snippet) = find_snippet(instance, title).await? else {
+        return Ok(None);
+    };
+    let snippet_id = snippet.id;
+    let request = client().get(format!("{gitlab_url}/snippets/{snippet_id}/raw"));
+    Ok(Some(
+        send(request, instance, "snippet").await?.text().await?,
+    ))
+}
+
+/// Fetch an iCalendar feed, such as an on-call schedule, which is not on the GitLab instance so
+/// none of its authentication applies
+pub async fn fetch_calendar(url: &str) -> Result<String> {
+    info!("fetching calendar {url}");
+    let response = client()
+        .get(url)
+        .header(USER_AGENT_HEADER, USER_AGENT)
         .send()
         .await?;
-    let merge_request = if response.status().is_success() {
-        response
-            .json::<MergeRequest>()
-            .await
-            .inspect_err(|e| error!("failed fetching merge request {full}: {e}"))?
-    } else {
+    if !response.status().is_success() {
         return Err(anyhow!(
-            "fetching merge requests failed with status {}",
+            "calendar request failed with status {}",
             response.status()
         ));
+    }
+    Ok(response.text().await?)
+}
+
+/// Create or update the authenticated user's private snippet with the given title so it contains
+/// a single file
+pub async fn save_private_snippet(
+    instance: &Instance,
+    title: &str,
+    file_path: &str,
+    content: &str,
+) -> Result<Snippet> {
+    let gitlab_url = &instance.url;
+    let request = match find_snippet(instance, title).await? {
+        Some(snippet) => {
+            let snippet_id = snippet.id;
+            client()
+                .put(format!("{gitlab_url}/snippets/{snippet_id}"))
+                .json(&json!({
+                    "files": [{ "action": "update", "file_path": file_path, "content": content }],
+                }))
+        }
+        None => client()
+            .post(format!("{gitlab_url}/snippets"))
+            .json(&json!({
+                "title": title,
+                "visibility": "private",
+                "files": [{ "file_path": file_path, "content": content }],
+            })),
+    };
+    info!("saving snippet {title}");
+    send_json(request, instance, "snippet").await
+}
+
+async fn find_snippet(instance: &Instance, title: &str) -> Result<Option<Snippet>> {
+    let gitlab_url = &instance.url;
+    let request = client()
+        .get(format!("{gitlab_url}/snippets"))
+        .query(&[("per_page", "100")]);
+    let snippets: Vec<Snippet> = send_json(request, instance, "snippets").await?;
+    Ok(snippets.into_iter().find(|s| s.title == title))
+}
+
+/// Send a request and deserialize the successful response body
+async fn send_json<T: DeserializeOwned>(
+    request: RequestBuilder,
+    instance: &Instance,
+    what: &str,
+) -> Result<T> {
+    decode(&send(request, instance, what).await?.text().await?, what)
+}
+
+/// A response which could not be decoded, with where in the payload decoding failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub what: String,
+    /// JSONPath of the value decoding failed at, like `$[3].head_pipeline.status`
+    pub path: String,
+    pub message: String,
+    /// The item of the payload decoding failed in, pretty printed with every string but the
+    /// failing value redacted. Missing when the payload is not JSON.
+    pub payload: Option<String>,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed decoding {} at {}: {}",
+            self.what, self.path, self.message
+        )
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl DecodeError {
+    fn new(text: &str, what: &str, error: &serde_json::Error) -> Self {
+        let segments = json_path_at(text, error.line(), error.column());
+        let path = segments
+            .iter()
+            .fold("$".to_string(), |path, segment| match segment {
+                PathSegment::Key(key) => format!("{path}.{key}"),
+                PathSegment::Index(i) => format!("{path}[{i}]"),
+            });
+        let payload = serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .map(|payload| {
+                // Only the item of a list which failed is of interest
+                let (item, segments) = match (&payload, segments.split_first()) {
+                    (serde_json::Value::Array(items), Some((PathSegment::Index(i), rest))) => {
+                        (items.get(*i).unwrap_or(&payload), rest)
+                    }
+                    _ => (&payload, segments.as_slice()),
+                };
+                serde_json::to_string_pretty(&redact(item, Some(segments))).unwrap_or_default()
+            });
+        DecodeError {
+            what: what.to_string(),
+            path,
+            message: error.to_string(),
+            payload,
+        }
+    }
+}
+
+/// Hook told about every response which fails to decode, see [`on_decode_error`]
+static DECODE_ERROR_HOOK: OnceLock<fn(&DecodeError)> = OnceLock::new();
+
+/// Be told about every response which fails to decode, e.g. to offer reporting it
+pub fn on_decode_error(hook: fn(&DecodeError)) {
+    let _ = DECODE_ERROR_HOOK.set(hook);
+}
+
+fn decode<T: DeserializeOwned>(text: &str, what: &str) -> Result<T> {
+    serde_json::from_str(text).map_err(|e| {
+        let error = DecodeError::new(text, what, &e);
+        error!("{error}");
+        if let Some(hook) = DECODE_ERROR_HOOK.get() {
+            hook(&error);
+        }
+        error.into()
+    })
+}
+
+/// The path to the value at the 1-based line and column of the JSON text, which is where
+/// `serde_json` reports decoding errors: at the end of the value which failed
+fn json_path_at(text: &str, line: usize, column: usize) -> Vec<PathSegment> {
+    enum Frame {
+        Object {
+            key: Option<String>,
+            expecting_key: bool,
+        },
+        Array {
+            index: usize,
+        },
+    }
+
+    let offset = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>()
+        + column;
+    let mut stack = Vec::<Frame>::new();
+    let mut string = None::<String>;
+    let mut escaped = false;
+    for c in text[..offset.min(text.len())].chars() {
+        if let Some(s) = &mut string {
+            match c {
+                _ if escaped => {
+                    escaped = false;
+                    s.push(c);
+                }
+                '\\' => escaped = true,
+                '"' => {
+                    if let Some(Frame::Object {
+                        key,
+                        expecting_key: expecting_key @ true,
+                    }) = stack.last_mut()
+                    {
+                        *key = string.take();
+                        *expecting_key = false;
+                    }
+                    string = None;
+                }
+                _ => s.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => string = Some(String::new()),
+            '{' => stack.push(Frame::Object {
+                key: None,
+                expecting_key: true,
+            }),
+            '[' => stack.push(Frame::Array { index: 0 }),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Object { expecting_key, .. }) => *expecting_key = true,
+                Some(Frame::Array { index }) => *index += 1,
+                None => {}
+            },
+            _ => {}
+        }
+    }
+    stack
+        .into_iter()
+        .filter_map(|frame| match frame {
+            Frame::Object { key, .. } => key.map(PathSegment::Key),
+            Frame::Array { index } => Some(PathSegment::Index(index)),
+        })
+        .collect()
+}
+
+/// Replace every string by `"redacted"`, except the value at the path which failed to decode
+fn redact(value: &serde_json::Value, path: Option<&[PathSegment]>) -> serde_json::Value {
+    use serde_json::Value;
+
+    let rest = |segment: PathSegment| match path?.split_first() {
+        Some((first, rest)) if *first == segment => Some(rest),
+        _ => None,
+    };
+    match value {
+        Value::String(_) if path.is_some_and(|p| p.is_empty()) => value.clone(),
+        Value::String(_) => json!("redacted"),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| redact(item, rest(PathSegment::Index(i))))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, field)| {
+                    (
+                        key.clone(),
+                        redact(field, rest(PathSegment::Key(key.clone()))),
+                    )
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Send a request following the pagination headers until there are no more pages or
+/// `max_results` items have been fetched. Pages are as large as GitLab allows unless `per_page`
+/// is given.
+async fn send_paginated<T: DeserializeOwned>(
+    request: RequestBuilder,
+    instance: &Instance,
+    what: &str,
+    pagination: Pagination,
+    per_page: Option<usize>,
+    max_results: Option<usize>,
+) -> Result<Vec<T>> {
+    const MAX_PER_PAGE: usize = 100;
+
+    let per_page = per_page
+        .or(max_results)
+        .unwrap_or(MAX_PER_PAGE)
+        .clamp(1, MAX_PER_PAGE)
+        .to_string();
+    let request = request.query(&[("per_page", &per_page)]);
+    let request = match pagination {
+        Pagination::Offset => request,
+        Pagination::Keyset => request.query(&[("pagination", "keyset")]),
     };
+    let mut items = Vec::new();
+    let mut page_request = repeat(&request, what)?;
+    loop {
+        let response = send(page_request, instance, what).await?;
+        let next_page_request = next_page_request(&request, &response, pagination, what)?;
+        let page_items: Vec<T> = decode(&response.text().await?, what)?;
+        items.extend(page_items);
+        if let Some(max_results) = max_results {
+            if items.len() >= max_results {
+                items.truncate(max_results);
+                break;
+            }
+        }
+        match next_page_request {
+            Some(next_page_request) => page_request = next_page_request,
+            None => break,
+        }
+    }
+    Ok(items)
+}
+
+fn repeat(request: &RequestBuilder, what: &str) -> Result<RequestBuilder> {
+    request
+        .try_clone()
+        .ok_or_else(|| anyhow!("{what} request can not be repeated"))
+}
+
+/// The request for the page after the response, if there is one
+fn next_page_request(
+    request: &RequestBuilder,
+    response: &Response,
+    pagination: Pagination,
+    what: &str,
+) -> Result<Option<RequestBuilder>> {
+    match pagination {
+        Pagination::Offset => match next_page(response) {
+            Some(page) => Ok(Some(repeat(request, what)?.query(&[("page", page)]))),
+            None => Ok(None),
+        },
+        // The cursor is opaque so the whole next url has to be followed, keeping the headers
+        Pagination::Keyset => {
+            let Some(next_link) = next_link(response) else {
+                return Ok(None);
+            };
+            let template = repeat(request, what)?.build()?;
+            Ok(Some(
+                client()
+                    .request(template.method().clone(), next_link)
+                    .headers(template.headers().clone()),
+            ))
+        }
+    }
+}
 
-    Ok(merge_request)
+/// The next page number from the `x-next-page` header, falling back to the `Link` header which
+/// is the only one set when GitLab skips counting large result sets
+fn next_page(response: &Response) -> Option<String> {
+    if let Some(next_page) = response
+        .headers()
+        .get("x-next-page")
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+    {
+        return Some(next_page.to_string());
+    }
+    next_link(response)?
+        .query_pairs()
+        .find(|(name, _)| name == "page")
+        .map(|(_, page)| page.to_string())
+}
+
+/// The `rel="next"` url of the `Link` header
+fn next_link(response: &Response) -> Option<reqwest::Url> {
+    let link = response.headers().get("link")?.to_str().ok()?;
+    let next_url = link.split(',').find_map(|link| {
+        let (url, rel) = link.split_once(';')?;
+        rel.contains("rel=\"next\"")
+            .then(|| url.trim().trim_start_matches('<').trim_end_matches('>'))
+    })?;
+    reqwest::Url::parse(next_url).ok()
+}
+
+/// How many requests per merge request, commit, or branch are in flight at once, so long lists do
+/// not run into rate limits
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Identifies the app to instance admins auditing API consumers
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Send a request failing if the response does not have a successful status
+async fn send(request: RequestBuilder, instance: &Instance, what: &str) -> Result<Response> {
+    successful(send_any_status(request, instance).await?, what)
+}
+
+/// Send a request with the credentials and headers of the instance, whatever the status of the
+/// response
+async fn send_any_status(request: RequestBuilder, instance: &Instance) -> Result<Response> {
+    let mut request = match instance.auth {
+        Auth::Token => request.header("PRIVATE-TOKEN", &instance.private_token),
+        Auth::Session => with_session_cookies(request),
+    }
+    .header(USER_AGENT_HEADER, USER_AGENT);
+    if let Some(request_tag) = &instance.request_tag {
+        request = request.header("X-Request-Id", format!("{request_tag}-{}", request_id()?));
+    }
+    for (name, value) in &instance.headers {
+        request = request.header(name, value);
+    }
+    Ok(request.send().await?)
+}
+
+fn successful(response: Response, what: &str) -> Result<Response> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(anyhow!(
+            "{what} request failed with status {}",
+            response.status()
+        ))
+    }
+}
+
+/// Run a query against the GraphQL API which lives next to the REST API
+async fn send_graphql<T: DeserializeOwned>(
+    instance: &Instance,
+    query: &str,
+    variables: serde_json::Value,
+) -> Result<T> {
+    #[derive(Deserialize)]
+    struct GraphqlResponse<T> {
+        data: Option<T>,
+        #[serde(default)]
+        errors: Vec<GraphqlError>,
+    }
+    #[derive(Deserialize)]
+    struct GraphqlError {
+        message: String,
+    }
+
+    let graphql_url = instance
+        .url
+        .trim_end_matches('/')
+        .trim_end_matches("/v4")
+        .to_string()
+        + "/graphql";
+    let request = client()
+        .post(graphql_url)
+        .json(&json!({ "query": query, "variables": variables }));
+    let response: GraphqlResponse<T> = send_json(request, instance, "graphql").await?;
+    match (response.data, response.errors.into_iter().next()) {
+        (_, Some(error)) => Err(anyhow!("graphql query failed: {}", error.message)),
+        (Some(data), None) => Ok(data),
+        (None, None) => Err(anyhow!("graphql query returned no data")),
+    }
+}
+
+fn encode_path(path: &str) -> String {
+    percent_encoding::utf8_percent_encode(path, NON_ALPHANUMERIC).to_string()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn with_session_cookies(request: RequestBuilder) -> RequestBuilder {
+    request.fetch_credentials_include()
+}
+
+/// Outside the browser there are no session cookies to send
+#[cfg(not(target_arch = "wasm32"))]
+fn with_session_cookies(request: RequestBuilder) -> RequestBuilder {
+    request
+}
+
+/// A random id distinguishing requests with the same tag
+fn request_id() -> Result<String> {
+    let mut id = [0; 8];
+    getrandom::getrandom(&mut id)?;
+    Ok(id.iter().map(|b| format!("{b:02x}")).collect())
 }
 
 fn client() -> &'static Client {
     static CLIENT: OnceLock<Client> = OnceLock::new();
-    CLIENT.get_or_init(|| Client::new())
+    CLIENT.get_or_init(Client::new)
 }
 
 fn deserialize_time_delta_from_seconds_with_default<'de, D>(
@@ -326,3 +3679,418 @@ where
     let seconds: Option<i64> = Deserialize::deserialize(deserializer)?;
     Ok(TimeDelta::seconds(seconds.unwrap_or_default()))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Sanitized payloads captured from each GitLab version in `fixtures/gitlab/<version>`
+    fn fixtures(name: &str) -> Vec<(String, String)> {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/gitlab");
+        let mut fixtures = fs::read_dir(&root)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path().join(name);
+                let payload = fs::read_to_string(&path)
+                    .unwrap_or_else(|e| panic!("failed reading {}: {e}", path.display()));
+                (path.display().to_string(), payload)
+            })
+            .collect::<Vec<_>>();
+        fixtures.sort();
+        assert!(
+            !fixtures.is_empty(),
+            "no {name} fixtures in {}",
+            root.display()
+        );
+        fixtures
+    }
+
+    #[test]
+    fn merge_request_fixtures_deserialize() {
+        for (path, payload) in fixtures("merge_request.json") {
+            let merge_request: MergeRequest = serde_json::from_str(&payload)
+                .unwrap_or_else(|e| panic!("failed decoding {path}: {e}"));
+            assert_ne!(merge_request.state, State::Unknown, "{path}");
+            assert_ne!(
+                merge_request.detailed_merge_status,
+                MergeStatus::Unknown,
+                "{path}"
+            );
+            assert_eq!(merge_request.project_path(), "platform/uploader", "{path}");
+            let head_pipeline = merge_request.head_pipeline.expect(&path);
+            assert_ne!(head_pipeline.status, PipelineStatus::Unknown, "{path}");
+        }
+    }
+
+    #[test]
+    fn pipeline_fixtures_deserialize() {
+        for (path, payload) in fixtures("pipeline.json") {
+            let pipeline: Pipeline = serde_json::from_str(&payload)
+                .unwrap_or_else(|e| panic!("failed decoding {path}: {e}"));
+            assert_ne!(pipeline.status, PipelineStatus::Unknown, "{path}");
+        }
+    }
+
+    #[test]
+    fn user_fixtures_deserialize() {
+        for (path, payload) in fixtures("user.json") {
+            let user: User = serde_json::from_str(&payload)
+                .unwrap_or_else(|e| panic!("failed decoding {path}: {e}"));
+            assert_eq!(user.username, "dana", "{path}");
+        }
+    }
+
+    #[test]
+    fn unknown_statuses_do_not_fail_decoding() {
+        let (path, payload) = fixtures("merge_request.json").remove(0);
+        let mut payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        payload["detailed_merge_status"] = json!("some_future_status");
+        payload["head_pipeline"]["status"] = json!("some_future_status");
+        let merge_request: MergeRequest = serde_json::from_value(payload)
+            .unwrap_or_else(|e| panic!("failed decoding {path}: {e}"));
+        assert_eq!(merge_request.detailed_merge_status, MergeStatus::Unknown);
+        assert_eq!(
+            merge_request.head_pipeline.unwrap().status,
+            PipelineStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn simple_view_payloads_decode() {
+        let payload = json!({
+            "id": 84,
+            "iid": 14,
+            "project_id": 4,
+            "title": "Impedit et ut et dolores vero provident ullam est",
+            "description": "Repellendus impedit et vel velit dignissimos.",
+            "state": "opened",
+            "created_at": "2024-04-02T18:57:24.912Z",
+            "updated_at": "2024-04-11T10:21:10.438Z",
+            "web_url": "https://gitlab.example.com/my-group/my-project/-/merge_requests/14"
+        });
+        let merge_request: MergeRequest = serde_json::from_value(payload).unwrap();
+        assert_eq!(merge_request.detailed_merge_status, MergeStatus::Unknown);
+        assert_eq!(
+            References::from_web_url(&merge_request.web_url),
+            References {
+                full: "my-group/my-project!14".to_string(),
+                short: "!14".to_string(),
+                relative: "!14".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn graphql_merge_requests_decode() {
+        let payload = include_str!("../fixtures/gitlab/17.5/graphql_merge_requests.json");
+        let mut payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        let data: NamespaceData = serde_json::from_value(payload["data"].take()).unwrap();
+        let node = data.project.unwrap().merge_requests.nodes;
+        let merge_request = node
+            .into_iter()
+            .next()
+            .unwrap()
+            .into_merge_request("https://gitlab.example.com");
+        assert_eq!(merge_request.id, 84);
+        assert_eq!(merge_request.iid, 14);
+        assert_eq!(merge_request.references.short, "!14");
+        assert_eq!(merge_request.detailed_merge_status, MergeStatus::NeedRebase);
+        assert!(!merge_request.blocking_discussions_resolved);
+        assert_eq!(merge_request.unresolved_threads, 1);
+        assert_eq!(
+            merge_request.reviewer_states[0].state,
+            ReviewState::Approved
+        );
+        assert_eq!(merge_request.diffs.len(), 2);
+        assert_eq!(merge_request.diffs[0].summary.additions, 42);
+        assert_eq!(merge_request.diffs[0].summary.deletions, 7);
+        assert!(!merge_request.diffs[0].needs_details());
+        // Could be binary, only the diffs over REST tell
+        assert!(!merge_request.diffs[1].summary.binary);
+        assert!(merge_request.diffs[1].needs_details());
+        let pipeline = merge_request.head_pipeline.unwrap();
+        assert_eq!(pipeline.status, PipelineStatus::WaitingForResource);
+        assert_eq!(pipeline.coverage.as_deref(), Some("87.50"));
+        assert_eq!(
+            pipeline.web_url,
+            "https://gitlab.example.com/my-group/my-project/-/pipelines/501"
+        );
+        let milestone = merge_request.milestone.unwrap();
+        assert_eq!(milestone.id, 31);
+        assert_eq!(
+            milestone.web_url,
+            "https://gitlab.example.com/my-group/my-project/-/milestones/6"
+        );
+    }
+
+    #[test]
+    fn full_data_keeps_the_listed_json() {
+        let (_, payload) = fixtures("merge_request.json").pop().unwrap();
+        let listed = MergeRequest::from_raw(serde_json::from_str(&payload).unwrap()).unwrap();
+        let mut merge_request = MergeRequest::default();
+        merge_request.keep_listed(&listed);
+        assert_eq!(merge_request.merge_commit_sha, listed.merge_commit_sha);
+        let raw = merge_request.raw.expect("the listed JSON");
+        assert_eq!(
+            crate::custom_field::render("{source_branch} ({changes_count} files)", &raw),
+            "retry-uploads (4 files)"
+        );
+    }
+
+    #[test]
+    fn graphql_approvals_decode() {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Project {
+            merge_requests: Connection<GraphqlApprovals>,
+        }
+
+        let payload = include_str!("../fixtures/gitlab/17.5/graphql_approvals.json");
+        let mut payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        let project: Project = serde_json::from_value(payload["data"]["project"].take()).unwrap();
+        let mut merge_request = MergeRequest::default();
+        let approvals = project.merge_requests.nodes.into_iter().next().unwrap();
+        assert_eq!(approvals.iid, "14");
+        approvals.apply(&mut merge_request);
+        assert_eq!(merge_request.approvals_required, 2);
+        assert_eq!(merge_request.approvals_left, 1);
+        assert_eq!(merge_request.approved_by[0].username, "sam");
+        let rule = &merge_request.approval_rules[1];
+        assert_eq!(rule.rule_type, ApprovalRuleType::CodeOwner);
+        assert!(!rule.approved);
+        assert_eq!(rule.eligible_approvers[1].username, "jordan");
+    }
+
+    #[test]
+    fn graphql_activity_decodes() {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Project {
+            merge_requests: Connection<GraphqlActivity>,
+        }
+
+        let payload = include_str!("../fixtures/gitlab/17.5/graphql_activity.json");
+        let mut payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        let project: Project = serde_json::from_value(payload["data"]["project"].take()).unwrap();
+        let mut merge_request = MergeRequest {
+            head_pipeline: Some(Pipeline {
+                status: PipelineStatus::Failed,
+                web_url: "https://gitlab.example.com/my-group/my-project/-/pipelines/501"
+                    .to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let activity = project.merge_requests.nodes.into_iter().next().unwrap();
+        assert_eq!(activity.iid, "14");
+        assert!(!activity.misses_notes());
+        activity.apply(&mut merge_request);
+        let comment = merge_request.latest_comment.unwrap();
+        assert_eq!(comment.id, 1203);
+        assert_eq!(comment.author.username, "sam");
+        assert_eq!(
+            merge_request.approved_at,
+            Some("2024-10-02T09:30:00Z".parse().unwrap())
+        );
+        assert_eq!(merge_request.changes_requested_at, None);
+        assert_eq!(merge_request.failed_jobs, ["rspec 1/2"]);
+        assert_eq!(merge_request.test_totals.unwrap().failed, 3);
+    }
+
+    #[test]
+    fn graphql_activity_of_only_system_notes_misses_comment() {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Project {
+            merge_requests: Connection<GraphqlActivity>,
+        }
+
+        let payload = include_str!("../fixtures/gitlab/17.5/graphql_activity.json");
+        let mut payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        let project: Project = serde_json::from_value(payload["data"]["project"].take()).unwrap();
+        let mut activity = project.merge_requests.nodes.into_iter().next().unwrap();
+        activity.notes.nodes.retain(|n| n.system);
+        assert!(activity.misses_notes());
+        activity.notes.page_info.has_previous_page = false;
+        assert!(!activity.misses_notes());
+    }
+
+    #[test]
+    fn graphql_activity_pages_back_to_the_approval() {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Project {
+            merge_requests: Connection<GraphqlActivity>,
+        }
+
+        let payload = include_str!("../fixtures/gitlab/17.5/graphql_activity.json");
+        let mut payload: serde_json::Value = serde_json::from_str(payload).unwrap();
+        let project: Project = serde_json::from_value(payload["data"]["project"].take()).unwrap();
+        let mut activity = project.merge_requests.nodes.into_iter().next().unwrap();
+        // The approval is older than the first page, which has the comments
+        let approval = activity.notes.nodes.pop().unwrap();
+        assert!(activity.misses_notes());
+        activity.prepend(Connection {
+            page_info: PageInfo::default(),
+            nodes: vec![approval],
+        });
+        assert!(!activity.misses_notes());
+        let mut merge_request = MergeRequest::default();
+        activity.apply(&mut merge_request);
+        assert_eq!(
+            merge_request.approved_at,
+            Some("2024-10-02T09:30:00Z".parse().unwrap())
+        );
+        assert_eq!(merge_request.latest_comment.unwrap().id, 1203);
+    }
+
+    #[test]
+    fn graphql_filters_map_the_query() {
+        let query = MergeRequestsQuery {
+            order_by: OrderBy::UpdatedAt,
+            sort: Sort::Asc,
+            environment: Some("production".to_string()),
+            not_labels: Some("wontfix, blocked".to_string()),
+            not_milestone: Some("17.6".to_string()),
+            ..MergeRequestsQuery::default()
+        };
+        let filters = graphql_filters(&query, "group").unwrap();
+        assert_eq!(filters["sort"], "UPDATED_ASC");
+        assert_eq!(filters["environmentName"], "production");
+        assert_eq!(filters["not"]["labels"], json!(["wontfix", "blocked"]));
+        assert_eq!(filters["not"]["milestoneTitle"], "17.6");
+
+        let query = MergeRequestsQuery {
+            scope: Scope::CreatedByMe,
+            ..MergeRequestsQuery::default()
+        };
+        assert!(graphql_filters(&query, "project").is_err());
+    }
+
+    #[test]
+    fn broadcast_messages_decode() {
+        let payload = include_str!("../fixtures/gitlab/17.5/broadcast_messages.json");
+        let messages: Vec<BroadcastMessage> = serde_json::from_str(payload).unwrap();
+        let now = "2024-06-12T12:00:00Z".parse().unwrap();
+        let active = messages
+            .iter()
+            .filter(|m| m.is_active(now))
+            .collect::<Vec<_>>();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].broadcast_type, "banner");
+        assert!(active[0].message.starts_with("Maintenance"));
+    }
+
+    #[test]
+    fn decode_errors_tell_where_decoding_failed() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Item {
+            name: String,
+            pipeline: Option<Pipeline>,
+        }
+
+        let payload = r#"[
+            {"name": "first", "pipeline": null},
+            {"name": "second", "pipeline": {"id": 1, "sha": "abc", "status": 7, "web_url": "url"}}
+        ]"#;
+        let error = decode::<Vec<Item>>(payload, "items")
+            .unwrap_err()
+            .downcast::<DecodeError>()
+            .unwrap();
+        assert_eq!(error.path, "$[1].pipeline.status");
+        let redacted: serde_json::Value =
+            serde_json::from_str(error.payload.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            redacted,
+            json!({
+                "name": "redacted",
+                "pipeline": {"id": 1, "sha": "redacted", "status": 7, "web_url": "redacted"},
+            })
+        );
+
+        let error = decode::<Item>(r#"{"name": "x", "pipeline": {"id": 1}}"#, "item")
+            .unwrap_err()
+            .downcast::<DecodeError>()
+            .unwrap();
+        assert_eq!(error.path, "$.pipeline");
+        assert!(error.message.starts_with("missing field `sha`"));
+
+        let error = decode::<Item>("<html>", "item")
+            .unwrap_err()
+            .downcast::<DecodeError>()
+            .unwrap();
+        assert_eq!(error.path, "$");
+        assert_eq!(error.payload, None);
+    }
+
+    #[test]
+    fn tracked_time_is_humanized_like_gitlab() {
+        assert_eq!(human_time(0), "0m");
+        assert_eq!(human_time(30 * 60), "30m");
+        assert_eq!(
+            human_time((5 * 8 + 2 * 8 + 3) * 3600 + 30 * 60),
+            "1w 2d 3h 30m"
+        );
+        assert_eq!(human_time(9 * 3600), "1d 1h");
+        assert_eq!(human_time(-3600), "-1h");
+    }
+
+    #[test]
+    fn closes_issues_decode() {
+        let payload = include_str!("../fixtures/gitlab/17.5/closes_issues.json");
+        let issues: Vec<Issue> = serde_json::from_str(payload).unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].references.full, "group/project#14");
+        assert_eq!(issues[1].state, State::Closed);
+    }
+
+    #[test]
+    fn pull_requests_decode() {
+        let payload = include_str!("../fixtures/github/pulls.json");
+        let pull_requests: Vec<PullRequest> = serde_json::from_str(payload).unwrap();
+        let merge_request = MergeRequest::from(pull_requests.into_iter().next().unwrap());
+        assert_eq!(merge_request.iid, 42);
+        assert_eq!(merge_request.references.full, "octo-org/octo-repo#42");
+        assert_eq!(merge_request.project_path(), "octo-org/octo-repo");
+        assert_eq!(merge_request.state, State::Merged);
+        assert_eq!(merge_request.source_branch, "retry-uploads");
+        assert_eq!(merge_request.reviewers[0].username, "hubot");
+        assert_eq!(merge_request.labels[0].color, "#d73a4a");
+        assert_eq!(merge_request.labels[0].text_color, "#FFFFFF");
+        assert_eq!(merge_request.labels[1].text_color, "#000000");
+    }
+
+    #[test]
+    fn labels_decode_with_and_without_details() {
+        let (path, payload) = fixtures("merge_request.json").remove(0);
+        let mut payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        payload["labels"] = json!([
+            "backend",
+            {
+                "id": 1,
+                "name": "reliability",
+                "color": "#dc143c",
+                "text_color": "#FFFFFF",
+                "description": "Keeps the lights on",
+                "description_html": "Keeps the lights on"
+            }
+        ]);
+        let merge_request: MergeRequest = serde_json::from_value(payload)
+            .unwrap_or_else(|e| panic!("failed decoding {path}: {e}"));
+        assert_eq!(merge_request.labels[0].name, "backend");
+        assert_eq!(merge_request.labels[0].description, None);
+        assert_eq!(
+            merge_request.labels[1],
+            Label {
+                name: "reliability".to_string(),
+                color: "#dc143c".to_string(),
+                text_color: "#FFFFFF".to_string(),
+                description: Some("Keeps the lights on".to_string()),
+            }
+        );
+    }
+}
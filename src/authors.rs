@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use chrono::{TimeDelta, Utc};
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight};
+use dioxus_free_icons::Icon;
+
+use crate::api::{MergeRequest, PipelineStatus, State};
+use crate::chart::{Bars, Histogram, Sparkline};
+use crate::{time_ago, MergeRequestList};
+
+/// How many weeks of created merge requests the sparkline of each author covers
+const ACTIVITY_WEEKS: i64 = 8;
+
+/// Merge requests grouped by author with workload totals in each group header, after charts of
+/// the open merge requests by author and by age
+#[component]
+pub fn ByAuthor(merge_request_list: Vec<MergeRequest>) -> Element {
+    let now = Utc::now();
+    let ages = merge_request_list
+        .iter()
+        .filter(|mr| mr.state == State::Opened)
+        .map(|mr| (now - mr.created_at).num_days() as f64)
+        .collect::<Vec<_>>();
+
+    let mut groups = BTreeMap::<String, Vec<MergeRequest>>::new();
+    for merge_request in merge_request_list {
+        groups
+            .entry(merge_request.author.username.clone())
+            .or_default()
+            .push(merge_request);
+    }
+
+    let mut open_by_author = groups
+        .iter()
+        .map(|(username, merge_requests)| {
+            let open = merge_requests.iter().filter(|mr| mr.state == State::Opened);
+            (username.clone(), open.count() as f64)
+        })
+        .filter(|(_, open)| *open > 0.0)
+        .collect::<Vec<_>>();
+    open_by_author.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    rsx! {
+        if !ages.is_empty() {
+            div { class: "flex flex-row flex-wrap font-ariel text-xs py-1 border-b",
+                div { class: "flex flex-col mr-4",
+                    span { "open by author" }
+                    Bars { bars: open_by_author, width: 240 }
+                }
+                div { class: "flex flex-col",
+                    span { "open by age" }
+                    Histogram { values: ages, count: 5, unit: "d", width: 240 }
+                }
+            }
+        }
+        ul { class: "list-none",
+            for (username, merge_request_list) in groups {
+                li { key: "{username}", class: "flex flex-col py-1 border-b",
+                    AuthorGroup { username: username.clone(), merge_request_list }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AuthorGroup(username: String, merge_request_list: Vec<MergeRequest>) -> Element {
+    let mut expanded = use_signal(|| false);
+
+    let open = merge_request_list
+        .iter()
+        .filter(|mr| mr.state == State::Opened)
+        .collect::<Vec<_>>();
+    let oldest = open.iter().map(|mr| mr.created_at).min();
+    let failing = open
+        .iter()
+        .filter(|mr| {
+            mr.head_pipeline
+                .as_ref()
+                .is_some_and(|p| p.status == PipelineStatus::Failed)
+        })
+        .count();
+    // Merge requests created each week, oldest week first
+    let now = Utc::now();
+    let weekly = (0..ACTIVITY_WEEKS)
+        .rev()
+        .map(|weeks_ago| {
+            let end = now - TimeDelta::weeks(weeks_ago);
+            let start = end - TimeDelta::weeks(1);
+            merge_request_list
+                .iter()
+                .filter(|mr| start < mr.created_at && mr.created_at <= end)
+                .count() as f64
+        })
+        .collect::<Vec<_>>();
+
+    rsx! {
+        div { class: "flex flex-row items-center",
+            span {
+                class: "cursor-pointer",
+                onclick: move |_event| {
+                    *expanded.write() = !expanded();
+                },
+                if expanded() {
+                    Icon { width: 16, height: 16, icon: FaCaretDown }
+                } else {
+                    Icon { width: 16, height: 16, icon: FaCaretRight }
+                }
+            }
+            span { class: "font-ariel text-sm mr-2", "{username}" }
+            span { class: "mr-2", title: "merge requests created each week over the last {ACTIVITY_WEEKS} weeks",
+                Sparkline { values: weekly, width: 48, height: 12 }
+            }
+            span { class: "font-ariel text-xs mr-2", "{open.len()} open" }
+            if let Some(oldest) = oldest {
+                span { class: "font-ariel text-xs mr-2", title: oldest.to_string(),
+                    "oldest {time_ago(oldest)}"
+                }
+            }
+            if failing > 0 {
+                span { class: "font-ariel text-xs text-red-700", "{failing} failing" }
+            }
+        }
+        if expanded() {
+            div { class: "ml-4",
+                MergeRequestList { merge_request_list }
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use dioxus::prelude::*;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::api::{MergeRequest, MergeStatus, PipelineStatus, State};
+use crate::{storage, time_ago};
+
+/// Local storage key of the work in progress limit of each column keyed by column name
+const WIP_LIMITS_STORAGE_KEY: &str = "lab-bench-wip-limits";
+
+/// The columns of the board, in display order
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, EnumIter)]
+pub enum Column {
+    Draft,
+    #[strum(serialize = "In review")]
+    InReview,
+    Blocked,
+    Mergeable,
+    Merged,
+}
+
+impl Column {
+    /// The column a merge request belongs in. Closed merge requests are not on the board.
+    pub fn of(merge_request: &MergeRequest) -> Option<Self> {
+        use MergeStatus::*;
+
+        let pipeline_failed = merge_request
+            .head_pipeline
+            .as_ref()
+            .is_some_and(|p| p.status == PipelineStatus::Failed);
+        match merge_request.state {
+            State::Merged => Some(Column::Merged),
+            State::Closed | State::Locked => None,
+            State::Opened | State::Unknown => Some(match merge_request.detailed_merge_status {
+                _ if merge_request.draft => Column::Draft,
+                DraftStatus => Column::Draft,
+                _ if pipeline_failed => Column::Blocked,
+                BlockedStatus
+                | Conflict
+                | NeedRebase
+                | DiscussionsNotResolved
+                | JiraAssociationMissing
+                | RequestedChanges
+                | ExternalStatusChecks
+                | CiMustPass
+                | NotOpen => Column::Blocked,
+                Mergeable => Column::Mergeable,
+                Checking | Unchecked | CiStillRunning | NotApproved | Unknown => Column::InReview,
+            }),
+        }
+    }
+}
+
+/// Kanban style layout of merge requests with a column per status. Each column can be given a
+/// work in progress limit and its header is highlighted once the limit is exceeded.
+#[component]
+pub fn Board(merge_request_list: Vec<MergeRequest>) -> Element {
+    let mut wip_limits = use_signal(|| {
+        storage::load::<HashMap<String, usize>>(WIP_LIMITS_STORAGE_KEY).unwrap_or_default()
+    });
+
+    rsx! {
+        div { class: "grid grid-cols-5 gap-2",
+            for column in Column::iter() {
+                {
+                    let merge_requests = merge_request_list
+                        .iter()
+                        .filter(|mr| Column::of(mr) == Some(column))
+                        .collect::<Vec<_>>();
+                    let limit = wip_limits.read().get(&column.to_string()).copied();
+                    let over_limit = limit.is_some_and(|limit| merge_requests.len() > limit);
+                    rsx! {
+                        div { class: "flex flex-col",
+                            div {
+                                class: if over_limit { "flex flex-row items-center justify-between border-b bg-red-100" } else { "flex flex-row items-center justify-between border-b" },
+                                title: if over_limit { "over the work in progress limit" } else { "" },
+                                h2 { class: "font-ariel text-sm", "{column} ({merge_requests.len()})" }
+                                input {
+                                    r#type: "number",
+                                    min: "0",
+                                    class: "w-12 p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                    placeholder: "limit",
+                                    title: "work in progress limit",
+                                    value: limit.map(|limit| limit.to_string()).unwrap_or_default(),
+                                    oninput: move |event| {
+                                        let mut wip_limits = wip_limits.write();
+                                        match event.value().trim().parse::<usize>() {
+                                            Ok(limit) => wip_limits.insert(column.to_string(), limit),
+                                            Err(_) => wip_limits.remove(&column.to_string()),
+                                        };
+                                        storage::save(WIP_LIMITS_STORAGE_KEY, &*wip_limits);
+                                    }
+                                }
+                            }
+                            for merge_request in merge_requests {
+                                BoardCard { key: "{merge_request.references.full}", merge_request: merge_request.clone() }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn BoardCard(merge_request: MergeRequest) -> Element {
+    let MergeRequest {
+        author,
+        references,
+        title,
+        updated_at,
+        web_url,
+        ..
+    } = merge_request;
+
+    rsx! {
+        div { class: "flex flex-col p-1 my-1 border rounded-sm border-gray-300",
+            a { class: "font-ariel text-xs", href: web_url, "{title}" }
+            span { class: "font-ariel text-xs", "{references.full}" }
+            span { class: "font-ariel text-xs", title: updated_at.to_string(),
+                "{author.username}, updated {time_ago(updated_at)}"
+            }
+        }
+    }
+}
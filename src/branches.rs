@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use chrono::{TimeDelta, Utc};
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCodeBranch, FaTrash};
+use dioxus_free_icons::Icon;
+
+use futures::StreamExt;
+
+use crate::api::{Instance, delete_branch, fetch_branch_divergences, fetch_branches, Branch, BranchDivergence};
+use crate::time_ago;
+
+/// Branches without a commit for this long are considered stale
+const STALE_AFTER: TimeDelta = TimeDelta::days(90);
+
+#[component]
+pub fn Branches(instance: Instance) -> Element {
+    let mut project_path = use_signal(String::new);
+    let mut branches_result = use_signal(|| Ok::<_, String>(Vec::<Branch>::new()));
+    // Counted after the branches are listed, by branch name
+    let mut divergences = use_signal(HashMap::<String, Result<BranchDivergence, String>>::new);
+
+    let load_instance = instance.clone();
+
+    rsx! {
+        div { class: "flex flex-row items-center my-1",
+            label { class: "block", "Repo" }
+            input {
+                r#type: "text",
+                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                oninput: move |event| {
+                    *project_path.write() = event.value().trim().to_string();
+                }
+            }
+            button {
+                class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
+                onclick: move |_event| {
+                    let instance = load_instance.clone();
+                    spawn(async move {
+                        divergences.write().clear();
+                        let project_path = project_path();
+                        let mut branches = match fetch_branches(&instance, &project_path).await {
+                            Ok(branches) => branches,
+                            Err(e) => {
+                                *branches_result.write() = Err(e.to_string());
+                                return;
+                            }
+                        };
+                        // Oldest first so the cleanup candidates are at the top
+                        branches.sort_by_key(|b| b.commit.created_at);
+                        *branches_result.write() = Ok(branches.clone());
+                        let Some(default_branch) = branches.iter().find(|b| b.default).map(|b| b.name.clone()) else {
+                            *branches_result.write() = Err(format!("{project_path} has no default branch"));
+                            return;
+                        };
+                        let mut counted = fetch_branch_divergences(&instance, &project_path, &default_branch, &branches);
+                        while let Some((name, divergence)) = counted.next().await {
+                            divergences.write().insert(name, divergence.map_err(|e| e.to_string()));
+                        }
+                    });
+                },
+                "Load"
+            }
+        }
+        match branches_result.read().clone() {
+            Ok(branches) => rsx!(
+                ul { class: "list-none",
+                    for branch in branches {
+                        li { key: "{branch.name}", class: "flex flex-row justify-between py-1 border-b",
+                            BranchItem {
+                                instance: instance.clone(),
+                                project_path: project_path(),
+                                divergence: divergences.read().get(&branch.name).cloned(),
+                                branch,
+                                on_delete: move |name: String| {
+                                    if let Ok(branches) = branches_result.write().as_mut() {
+                                        branches.retain(|b| b.name != name);
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            ),
+            Err(e) => rsx!(span {"{e}"}),
+        }
+    }
+}
+
+#[component]
+fn BranchItem(
+    instance: Instance,
+    project_path: String,
+    branch: Branch,
+    /// `None` until it is counted
+    divergence: Option<Result<BranchDivergence, String>>,
+    on_delete: EventHandler<String>,
+) -> Element {
+    let mut delete_error = use_signal(|| None::<String>);
+
+    let last_commit_at = branch.commit.created_at;
+    let stale = Utc::now() - last_commit_at > STALE_AFTER;
+    // Only allow deleting branches which cannot lose work or break anything
+    let deletable = (branch.merged || stale) && !branch.protected && !branch.default;
+    let name = branch.name.clone();
+
+    rsx! {
+        div { class: "flex flex-row items-center",
+            Icon { width: 12, height: 12, fill: "#626168", icon: FaCodeBranch }
+            a { class: "font-ariel text-sm mx-1", href: branch.web_url, "{branch.name}" }
+            if branch.default {
+                span { class: "font-ariel text-xs mr-1", "default" }
+            }
+            if branch.protected {
+                span { class: "font-ariel text-xs mr-1", "protected" }
+            }
+            if branch.merged {
+                span { class: "font-ariel text-xs mr-1 text-green-700", "merged" }
+            }
+            if stale {
+                span { class: "font-ariel text-xs mr-1 text-red-700", "stale" }
+            }
+            if let Some(e) = delete_error() {
+                span { class: "font-ariel text-xs text-red-700", "{e}" }
+            }
+        }
+        div { class: "flex flex-row items-center",
+            match divergence {
+                Some(Ok(BranchDivergence { ahead, behind })) => rsx!(
+                    span {
+                        class: "font-ariel text-xs mr-1",
+                        title: "ahead/behind the default branch",
+                        "+{ahead} -{behind}"
+                    }
+                ),
+                Some(Err(e)) => rsx!(
+                    span { class: "font-ariel text-xs mr-1 text-red-700", title: "{e}", "+? -?" }
+                ),
+                None => rsx!(
+                    span { class: "font-ariel text-xs mr-1 text-gray-400", title: "counting commits ahead/behind the default branch", "+… -…" }
+                ),
+            }
+            span {
+                class: "font-ariel text-xs mr-1",
+                title: last_commit_at.to_string(),
+                "last commit {time_ago(last_commit_at)}"
+            }
+            if deletable {
+                span {
+                    class: "cursor-pointer",
+                    title: "delete branch",
+                    onclick: move |_event| {
+                        if !confirm(&format!("Delete branch {name}?")) {
+                            return;
+                        }
+                        let instance = instance.clone();
+                        let project_path = project_path.clone();
+                        let name = name.clone();
+                        spawn(async move {
+                            match delete_branch(&instance, &project_path, &name).await {
+                                Ok(()) => on_delete.call(name),
+                                Err(e) => *delete_error.write() = Some(e.to_string()),
+                            }
+                        });
+                    },
+                    Icon { width: 12, height: 12, fill: "#dd2b0e", icon: FaTrash }
+                }
+            }
+        }
+    }
+}
+
+fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .expect("window to exist")
+        .confirm_with_message(message)
+        .unwrap_or(false)
+}
@@ -0,0 +1,87 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Utc};
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretLeft, FaCaretRight, FaCodeMerge, FaPlus};
+use dioxus_free_icons::Icon;
+
+use crate::api::MergeRequest;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Month calendar placing merge requests on the day they were created and the day they were merged
+#[component]
+pub fn Calendar(merge_request_list: Vec<MergeRequest>) -> Element {
+    let mut month = use_signal(|| first_of_month(Utc::now().date_naive()));
+
+    let first = month();
+    // Start the grid on the Monday on or before the first of the month
+    let start = first - Days::new(first.weekday().num_days_from_monday().into());
+    let next_month = first + Months::new(1);
+    let days = start
+        .iter_days()
+        .take_while(|day| *day < next_month || day.weekday().num_days_from_monday() != 0)
+        .collect::<Vec<_>>();
+
+    rsx! {
+        div { class: "flex flex-row items-center my-1",
+            span {
+                class: "cursor-pointer",
+                onclick: move |_event| {
+                    *month.write() = first - Months::new(1);
+                },
+                Icon { width: 16, height: 16, icon: FaCaretLeft }
+            }
+            span { class: "font-ariel text-sm mx-1", "{first.format(\"%B %Y\")}" }
+            span {
+                class: "cursor-pointer",
+                onclick: move |_event| {
+                    *month.write() = next_month;
+                },
+                Icon { width: 16, height: 16, icon: FaCaretRight }
+            }
+        }
+        div { class: "grid grid-cols-7 gap-1",
+            for weekday in WEEKDAYS {
+                span { class: "font-ariel text-xs border-b", "{weekday}" }
+            }
+            for day in days {
+                div { class: if day.month() == first.month() { "flex flex-col p-1 border rounded-sm border-gray-300" } else { "flex flex-col p-1 border rounded-sm border-gray-300 text-gray-400" },
+                    span { class: "font-ariel text-xs", "{day.day()}" }
+                    for merge_request in merge_request_list.iter().filter(|mr| mr.created_at.date_naive() == day) {
+                        CalendarEntry { merge_request: merge_request.clone(), merged: false }
+                    }
+                    for merge_request in merge_request_list.iter().filter(|mr| mr.merged_at.is_some_and(|at| at.date_naive() == day)) {
+                        CalendarEntry { merge_request: merge_request.clone(), merged: true }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CalendarEntry(merge_request: MergeRequest, merged: bool) -> Element {
+    let MergeRequest {
+        references,
+        title,
+        web_url,
+        ..
+    } = merge_request;
+
+    rsx! {
+        a {
+            class: "flex flex-row items-center font-ariel text-xs truncate",
+            href: web_url,
+            title: "{references.full} {title}",
+            if merged {
+                Icon { width: 10, height: 10, fill: "#108548", icon: FaCodeMerge }
+            } else {
+                Icon { width: 10, height: 10, icon: FaPlus }
+            }
+            span { class: "ml-1 truncate", "{title}" }
+        }
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::FaCopy;
+use dioxus_free_icons::Icon;
+
+use crate::api::{Instance, fetch_merge_requests_between_refs, MergeRequest};
+use crate::set_clipboard;
+
+/// Heading for merge requests without any labels
+const UNLABELED: &str = "Other";
+
+#[component]
+pub fn Changelog(instance: Instance) -> Element {
+    let mut project_path = use_signal(String::new);
+    let mut from = use_signal(String::new);
+    let mut to = use_signal(String::new);
+    let mut changelog_result = use_signal(|| Ok::<_, String>(String::new()));
+
+    rsx! {
+        div { class: "flex flex-row items-center my-1",
+            label { class: "block", "Repo" }
+            input {
+                r#type: "text",
+                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                oninput: move |event| {
+                    *project_path.write() = event.value().trim().to_string();
+                }
+            }
+            label { class: "block", "From" }
+            input {
+                r#type: "text",
+                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                oninput: move |event| {
+                    *from.write() = event.value().trim().to_string();
+                }
+            }
+            label { class: "block", "To" }
+            input {
+                r#type: "text",
+                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                oninput: move |event| {
+                    *to.write() = event.value().trim().to_string();
+                }
+            }
+            button {
+                class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
+                onclick: move |_event| {
+                    let instance = instance.clone();
+                    spawn(async move {
+                        *changelog_result
+                            .write() = fetch_merge_requests_between_refs(
+                                &instance,
+                                &project_path(),
+                                &from(),
+                                &to(),
+                            )
+                            .await
+                            .map(|merge_requests| changelog_markdown(&from(), &to(), &merge_requests))
+                            .map_err(|e| e.to_string());
+                    });
+                },
+                "Generate"
+            }
+        }
+        match changelog_result() {
+            Ok(changelog) if changelog.is_empty() => rsx!(),
+            Ok(changelog) => rsx!(
+                div { class: "flex flex-row items-start",
+                    pre { class: "flex-grow p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs whitespace-pre-wrap",
+                        "{changelog}"
+                    }
+                    span {
+                        class: "cursor-pointer ml-1",
+                        title: "copy",
+                        onclick: move |_event| set_clipboard(&changelog),
+                        Icon { width: 16, height: 16, icon: FaCopy }
+                    }
+                }
+            ),
+            Err(e) => rsx!(span {"{e}"}),
+        }
+    }
+}
+
+/// Render merge requests as a markdown changelog with a section per label. A merge request with
+/// several labels is listed in each of their sections.
+fn changelog_markdown(from: &str, to: &str, merge_requests: &[MergeRequest]) -> String {
+    let mut sections = BTreeMap::<&str, Vec<&MergeRequest>>::new();
+    let mut unlabeled = Vec::new();
+    for merge_request in merge_requests {
+        if merge_request.labels.is_empty() {
+            unlabeled.push(merge_request);
+        }
+        for label in &merge_request.labels {
+            sections.entry(&label.name).or_default().push(merge_request);
+        }
+    }
+
+    let mut markdown = format!("# Changes from {from} to {to}\n");
+    let sections = sections
+        .into_iter()
+        .chain((!unlabeled.is_empty()).then_some((UNLABELED, unlabeled)));
+    for (label, merge_requests) in sections {
+        markdown.push_str(&format!("\n## {label}\n\n"));
+        for merge_request in merge_requests {
+            markdown.push_str(&format!(
+                "- {} ([{}]({})) by @{}\n",
+                merge_request.title,
+                merge_request.references.short,
+                merge_request.web_url,
+                merge_request.author.username
+            ));
+        }
+    }
+    markdown
+}
@@ -0,0 +1,159 @@
+//! Small charts drawn as inline SVG, which needs no chart library and renders the same in the web
+//! and desktop builds
+
+use dioxus::prelude::*;
+
+const BAR_COLOR: &str = "#1f75cb";
+const TEXT_COLOR: &str = "#626168";
+/// Height of a bar and its label in horizontal bar charts
+const ROW_HEIGHT: usize = 12;
+/// Width of the bar labels in horizontal bar charts
+const LABEL_WIDTH: usize = 80;
+/// Room for the value after the longest bar
+const VALUE_WIDTH: usize = 24;
+
+/// A line through the values from left to right, scaled to the box
+#[component]
+pub fn Sparkline(values: Vec<f64>, width: usize, height: usize) -> Element {
+    let points = sparkline_points(&values, width, height);
+    rsx! {
+        svg {
+            width: "{width}",
+            height: "{height}",
+            view_box: "0 0 {width} {height}",
+            polyline {
+                points,
+                fill: "none",
+                stroke: BAR_COLOR,
+                stroke_width: "1.5",
+            }
+        }
+    }
+}
+
+/// A horizontal bar per labeled value, the longest filling the width
+#[component]
+pub fn Bars(bars: Vec<(String, f64)>, width: usize) -> Element {
+    let max = bars.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let bar_space = width.saturating_sub(LABEL_WIDTH + VALUE_WIDTH) as f64;
+    let height = bars.len() * ROW_HEIGHT;
+    rsx! {
+        svg {
+            width: "{width}",
+            height: "{height}",
+            view_box: "0 0 {width} {height}",
+            font_size: "9",
+            for (i, (label, value)) in bars.into_iter().enumerate() {
+                {
+                    let y = i * ROW_HEIGHT;
+                    let text_y = y + ROW_HEIGHT - 3;
+                    let bar_width = if max > 0.0 { value / max * bar_space } else { 0.0 };
+                    let value_x = LABEL_WIDTH as f64 + bar_width + 2.0;
+                    rsx! {
+                        g { key: "{i}",
+                            text {
+                                x: "{LABEL_WIDTH - 2}",
+                                y: "{text_y}",
+                                text_anchor: "end",
+                                fill: TEXT_COLOR,
+                                "{label}"
+                            }
+                            rect {
+                                x: "{LABEL_WIDTH}",
+                                y: "{y + 2}",
+                                width: "{bar_width}",
+                                height: "{ROW_HEIGHT - 4}",
+                                fill: BAR_COLOR,
+                            }
+                            text { x: "{value_x}", y: "{text_y}", fill: TEXT_COLOR, "{value}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How many values fall in each of `count` equally wide buckets, as bars labeled with the range of
+/// the bucket followed by the unit
+#[component]
+pub fn Histogram(values: Vec<f64>, count: usize, unit: String, width: usize) -> Element {
+    let bars = buckets(&values, count)
+        .into_iter()
+        .map(|(start, end, n)| (format!("{start:.0}-{end:.0}{unit}"), n as f64))
+        .collect::<Vec<_>>();
+    rsx!(Bars { bars, width })
+}
+
+/// Split the range of the values into `count` equally wide buckets, as the start and end of each
+/// with how many values it holds. The last bucket includes its end.
+fn buckets(values: &[f64], count: usize) -> Vec<(f64, f64, usize)> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if values.is_empty() || count == 0 {
+        return Vec::new();
+    }
+    let count = if max > min { count } else { 1 };
+    let bucket_width = (max - min) / count as f64;
+    let mut buckets = (0..count)
+        .map(|i| {
+            let start = min + i as f64 * bucket_width;
+            (start, start + bucket_width, 0)
+        })
+        .collect::<Vec<_>>();
+    for value in values {
+        let i = if bucket_width > 0.0 {
+            (((value - min) / bucket_width) as usize).min(count - 1)
+        } else {
+            0
+        };
+        buckets[i].2 += 1;
+    }
+    buckets
+}
+
+/// The points of a polyline through the values, the lowest at the bottom and the highest at the
+/// top with a pixel of room for the stroke
+fn sparkline_points(values: &[f64], width: usize, height: usize) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let inner_height = height.saturating_sub(2) as f64;
+    let step = width as f64 / values.len().saturating_sub(1).max(1) as f64;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let y = if max > min {
+                1.0 + (max - value) / (max - min) * inner_height
+            } else {
+                height as f64 / 2.0
+            };
+            format!("{:.1},{y:.1}", i as f64 * step)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_counted_in_equal_buckets() {
+        assert_eq!(
+            buckets(&[0.0, 1.0, 4.0, 9.0, 10.0], 2),
+            [(0.0, 5.0, 3), (5.0, 10.0, 2)]
+        );
+        assert_eq!(buckets(&[3.0, 3.0], 4), [(3.0, 3.0, 2)]);
+        assert!(buckets(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn sparklines_span_the_box() {
+        assert_eq!(
+            sparkline_points(&[0.0, 5.0, 10.0], 20, 12),
+            "0.0,11.0 10.0,6.0 20.0,1.0"
+        );
+        assert_eq!(sparkline_points(&[2.0, 2.0], 20, 12), "0.0,6.0 20.0,6.0");
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use dioxus::prelude::*;
+use futures::stream::{self, StreamExt};
+use tracing::error;
+
+use crate::api::{
+    fetch_codeowners, fetch_current_user, fetch_diffs, fetch_user_groups, Instance, MergeRequest,
+    MAX_CONCURRENT_REQUESTS,
+};
+use crate::MergeRequestList;
+
+/// The rules of a CODEOWNERS file, grouped by section. Within a section the last matching rule
+/// decides the owners of a path, and the owners of all sections apply.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Codeowners {
+    sections: Vec<Vec<Rule>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Rule {
+    pattern: String,
+    /// Usernames and group paths with their `@`, or emails
+    owners: Vec<String>,
+}
+
+impl Codeowners {
+    pub fn parse(codeowners: &str) -> Self {
+        let mut sections = vec![Vec::new()];
+        for line in codeowners.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // Section headers such as `[Docs]`, `^[Docs]`, or `[Docs][2] @docs-team`
+            if line.starts_with('[') || line.starts_with("^[") {
+                sections.push(Vec::new());
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            let rule = Rule {
+                pattern: pattern.replace("\\ ", " "),
+                owners: parts.map(str::to_string).collect(),
+            };
+            if let Some(section) = sections.last_mut() {
+                section.push(rule);
+            }
+        }
+        Self { sections }
+    }
+
+    /// The owners of a path
+    pub fn owners(&self, path: &str) -> Vec<&str> {
+        self.sections
+            .iter()
+            .filter_map(|rules| rules.iter().rev().find(|r| matches(&r.pattern, path)))
+            .flat_map(|r| r.owners.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+/// Whether a path matches a CODEOWNERS pattern. Patterns starting with `/` are anchored to the
+/// repository root, others match at any depth. Patterns ending with `/` match everything in the
+/// directory.
+fn matches(pattern: &str, path: &str) -> bool {
+    let pattern = match pattern.strip_suffix('/') {
+        Some(directory) => format!("{directory}/**"),
+        None => pattern.to_string(),
+    };
+    let pattern = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if pattern.contains('/') && !pattern.starts_with("**/") => pattern,
+        None => format!("**/{pattern}"),
+    };
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+    // A pattern naming a directory also matches everything in it
+    (1..=path.len()).any(|end| matches_segments(&pattern, &path[..end]))
+}
+
+fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            matches_segments(&pattern[1..], path)
+                || (!path.is_empty() && matches_segments(pattern, &path[1..]))
+        }
+        (Some(segment), Some(name)) => {
+            matches_segment(segment.as_bytes(), name.as_bytes())
+                && matches_segments(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+/// Match a single path segment against a pattern segment with `*` and `?` wildcards
+fn matches_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            matches_segment(&pattern[1..], name)
+                || (!name.is_empty() && matches_segment(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => matches_segment(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) => p == n && matches_segment(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// A merge request changing files the user owns
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Owned {
+    merge_request: MergeRequest,
+    owned_paths: usize,
+    reviewer: bool,
+}
+
+/// Find the fetched merge requests of others which change files the authenticated user or one
+/// of their groups owns
+async fn find_owned(
+    instance: &Instance,
+    merge_requests: &[MergeRequest],
+) -> anyhow::Result<Vec<Owned>> {
+    let user = fetch_current_user(instance).await?;
+    let mut me = vec![format!("@{}", user.username)];
+    me.extend(
+        fetch_user_groups(instance)
+            .await?
+            .into_iter()
+            .map(|g| format!("@{}", g.full_path)),
+    );
+
+    let mut codeowners = HashMap::new();
+    for project_id in merge_requests.iter().map(|mr| mr.project_id) {
+        if codeowners.contains_key(&project_id) {
+            continue;
+        }
+        let parsed = fetch_codeowners(instance, project_id)
+            .await?
+            .map(|c| Codeowners::parse(&c));
+        codeowners.insert(project_id, parsed);
+    }
+
+    let reviewable = merge_requests
+        .iter()
+        .filter(|mr| mr.author.id != user.id)
+        .filter_map(|mr| Some((mr, codeowners.get(&mr.project_id)?.as_ref()?)));
+    let owned = stream::iter(reviewable)
+        .map(|(merge_request, codeowners)| {
+            let me = &me;
+            async move {
+                let mut merge_request = merge_request.clone();
+                // Without the full data the diffs still have to be fetched
+                if merge_request.diffs.is_empty() {
+                    merge_request.diffs = fetch_diffs(instance, &merge_request)
+                        .await
+                        .inspect_err(|e| {
+                            error!(
+                                "failed fetching the changes of {}: {e}",
+                                merge_request.references.full
+                            )
+                        })
+                        .unwrap_or_default();
+                }
+                let owned_paths = merge_request
+                    .changed_paths()
+                    .iter()
+                    .filter(|path| {
+                        codeowners
+                            .owners(path)
+                            .iter()
+                            .any(|owner| me.iter().any(|m| m == owner))
+                    })
+                    .count();
+                let reviewer = merge_request.reviewers.iter().any(|r| r.id == user.id);
+                (owned_paths > 0).then_some(Owned {
+                    merge_request,
+                    owned_paths,
+                    reviewer,
+                })
+            }
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .filter_map(std::future::ready)
+        .collect()
+        .await;
+    Ok(owned)
+}
+
+/// The fetched merge requests touching files the user owns according to CODEOWNERS
+#[component]
+pub fn MyAreas(instance: Instance, merge_request_list: Vec<MergeRequest>) -> Element {
+    let mut owned_result = use_signal(|| None::<Result<Vec<Owned>, String>>);
+
+    rsx! {
+        div { class: "flex flex-row items-center my-1",
+            span { class: "font-ariel text-xs mr-2",
+                "Merge requests changing files you or your groups own, out of the fetched ones"
+            }
+            button {
+                class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
+                onclick: move |_event| {
+                    let instance = instance.clone();
+                    let merge_request_list = merge_request_list.clone();
+                    spawn(async move {
+                        *owned_result.write() = Some(
+                            find_owned(&instance, &merge_request_list)
+                                .await
+                                .map_err(|e| e.to_string()),
+                        );
+                    });
+                },
+                "Find"
+            }
+        }
+        match owned_result() {
+            Some(Ok(owned)) => {
+                let not_reviewing = owned
+                    .iter()
+                    .filter(|o| !o.reviewer)
+                    .map(|o| format!("{} ({} files)", o.merge_request.references.short, o.owned_paths))
+                    .collect::<Vec<_>>();
+                rsx! {
+                    if !not_reviewing.is_empty() {
+                        span { class: "font-ariel text-xs", "Not a reviewer yet: {not_reviewing.join(\", \")}" }
+                    }
+                    MergeRequestList {
+                        merge_request_list: owned.into_iter().map(|o| o.merge_request).collect::<Vec<_>>(),
+                    }
+                }
+            }
+            Some(Err(e)) => rsx!(span {"{e}"}),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unanchored_patterns_match_at_any_depth() {
+        assert!(matches("*.rs", "src/main.rs"));
+        assert!(matches("README.md", "docs/README.md"));
+        assert!(!matches("*.rs", "src/main.ts"));
+    }
+
+    #[test]
+    fn anchored_patterns_match_from_the_root() {
+        assert!(matches("/src/main.rs", "src/main.rs"));
+        assert!(!matches("/main.rs", "src/main.rs"));
+    }
+
+    #[test]
+    fn directory_patterns_match_everything_inside() {
+        assert!(matches("services/auth/", "services/auth/src/lib.rs"));
+        assert!(matches("/docs/", "docs/index.md"));
+        assert!(matches(
+            "/services/*/Cargo.toml",
+            "services/auth/Cargo.toml"
+        ));
+        assert!(matches("/services/**/lib.rs", "services/auth/src/lib.rs"));
+        assert!(!matches("services/auth/", "services/billing/src/lib.rs"));
+    }
+
+    #[test]
+    fn last_matching_rule_of_each_section_wins() {
+        let codeowners = Codeowners::parse(
+            "# Default owners\n\
+             * @everyone\n\
+             /services/auth/ @auth-team\n\
+             \n\
+             [Docs]\n\
+             *.md @docs-team\n",
+        );
+        assert_eq!(codeowners.owners("services/auth/lib.rs"), ["@auth-team"]);
+        assert_eq!(
+            codeowners.owners("services/auth/README.md"),
+            ["@auth-team", "@docs-team"]
+        );
+        assert_eq!(codeowners.owners("Cargo.toml"), ["@everyone"]);
+    }
+}
@@ -0,0 +1,72 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight};
+use dioxus_free_icons::Icon;
+
+use crate::api::{fetch_merge_request_commits, Commit, Instance, MergeRequest};
+use crate::time_ago;
+
+/// Commits squashed into an earlier one by `git rebase --autosquash` start with these
+const AUTOSQUASH_PREFIXES: [&str; 3] = ["fixup! ", "squash! ", "amend! "];
+
+fn is_autosquash(commit: &Commit) -> bool {
+    AUTOSQUASH_PREFIXES
+        .iter()
+        .any(|prefix| commit.title.starts_with(prefix))
+}
+
+/// The commits of a merge request, fetched the first time the section is expanded
+#[component]
+pub fn Commits(merge_request: MergeRequest) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut expanded = use_signal(|| false);
+    let mut commits = use_signal(|| None::<Result<Vec<Commit>, String>>);
+
+    let toggle = move |_event| {
+        *expanded.write() = !expanded();
+        if commits.peek().is_some() {
+            return;
+        }
+        let merge_request = merge_request.clone();
+        spawn(async move {
+            *commits.write() = Some(
+                fetch_merge_request_commits(&instance(), &merge_request)
+                    .await
+                    .map_err(|e| e.to_string()),
+            );
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col",
+            span { class: "flex flex-row items-center font-ariel text-xs cursor-pointer", onclick: toggle,
+                if expanded() {
+                    Icon { width: 12, height: 12, icon: FaCaretDown }
+                } else {
+                    Icon { width: 12, height: 12, icon: FaCaretRight }
+                }
+                "commits"
+            }
+            if expanded() {
+                match commits() {
+                    Some(Ok(commits)) => rsx! {
+                        for commit in commits {
+                            div { key: "{commit.id}", class: "flex flex-row items-center font-ariel text-xs ml-3 min-w-0",
+                                a { class: "font-mono mr-1", href: commit.web_url.clone(), "{commit.short_id}" }
+                                span {
+                                    class: if is_autosquash(&commit) { "truncate mr-1 text-amber-800" } else { "truncate mr-1" },
+                                    title: commit.title.clone(),
+                                    "{commit.title}"
+                                }
+                                span { class: "whitespace-nowrap", title: commit.created_at.to_string(),
+                                    "{commit.author_name}, {time_ago(commit.created_at)}"
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx!(span { class: "font-ariel text-xs ml-3", "{e}" }),
+                    None => rsx!(span { class: "font-ariel text-xs ml-3", "loading" }),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use dioxus::prelude::*;
+use serde_json::Value;
+
+use crate::api::MergeRequest;
+
+/// Template of an extra field shown on the rows, rendered over the JSON of each merge request so
+/// fields the app doesn't model can be shown too. Shared through context.
+#[derive(Clone, Copy)]
+pub struct CustomField(pub Signal<String>);
+
+/// Replace each `{path}` of the template with the value at the path in the JSON. A path is dot
+/// separated keys and array indexes like `milestone.title` or `labels.0`, optionally starting
+/// with `$.`, and a key applied to an array is applied to each of its elements like
+/// `assignees.username`. Missing values are left empty.
+pub fn render(template: &str, json: &Value) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let path = rest[start + 1..end].trim();
+        let path = path.strip_prefix("$.").unwrap_or(path);
+        let segments = path
+            .split('.')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        let values = lookup(json, &segments)
+            .into_iter()
+            .filter_map(|value| match value {
+                Value::Null => None,
+                Value::String(s) => Some(s.clone()),
+                value => Some(value.to_string()),
+            })
+            .collect::<Vec<_>>();
+        rendered.push_str(&values.join(", "));
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn lookup<'a>(value: &'a Value, path: &[&str]) -> Vec<&'a Value> {
+    let Some((key, rest)) = path.split_first() else {
+        return vec![value];
+    };
+    match value {
+        Value::Array(items) => match key.parse::<usize>() {
+            Ok(i) => items.get(i).map(|v| lookup(v, rest)).unwrap_or_default(),
+            Err(_) => items.iter().flat_map(|v| lookup(v, path)).collect(),
+        },
+        Value::Object(fields) => fields
+            .get(*key)
+            .map(|v| lookup(v, rest))
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// The custom field of a row, left out when no template is set or it renders empty
+#[component]
+pub fn CustomFieldValue(merge_request: MergeRequest) -> Element {
+    let CustomField(template) = use_context::<CustomField>();
+    let raw = merge_request.raw?;
+    let template = template.read();
+    if template.trim().is_empty() {
+        return None;
+    }
+    let value = render(&template, &raw);
+    if value.trim().is_empty() {
+        return None;
+    }
+    rsx! {
+        span { class: "font-ariel text-xs mr-1", title: template.clone(), "{value}" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn templates_render_paths_into_the_json() {
+        let json = json!({
+            "milestone": { "title": "17.5", "due_date": null },
+            "assignees": [{ "username": "alice" }, { "username": "bob" }],
+            "labels": ["backend", "bug"],
+            "user_notes_count": 4,
+        });
+        assert_eq!(render("{milestone.title}", &json), "17.5");
+        assert_eq!(render("due {milestone.due_date}", &json), "due ");
+        assert_eq!(render("{$.assignees.username}", &json), "alice, bob");
+        assert_eq!(
+            render("{labels.1} ({user_notes_count} notes)", &json),
+            "bug (4 notes)"
+        );
+        assert_eq!(render("{missing.field}", &json), "");
+        assert_eq!(render("unclosed {labels", &json), "unclosed {labels");
+    }
+}
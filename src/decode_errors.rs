@@ -0,0 +1,76 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::FaTriangleExclamation;
+use dioxus_free_icons::Icon;
+
+use crate::api::DecodeError;
+use crate::set_clipboard;
+
+/// How many of the most recent decoding failures are shown
+const MAX_FAILURES: usize = 3;
+
+/// Responses which failed to decode since the app started, most recent last
+static FAILURES: GlobalSignal<Vec<DecodeError>> = Signal::global(Vec::new);
+
+/// Remember a decoding failure so it can be reported, given to [`crate::api::on_decode_error`]
+pub fn record(error: &DecodeError) {
+    // Only responses fetched by the app are shown, not the ones decoded outside of it
+    if Runtime::current().is_none() {
+        return;
+    }
+    let mut failures = FAILURES.write();
+    failures.retain(|f| f.what != error.what || f.path != error.path);
+    failures.push(error.clone());
+    let excess = failures.len().saturating_sub(MAX_FAILURES);
+    failures.drain(..excess);
+}
+
+/// A bug report of the failure, with the redacted payload to reproduce it
+fn report(error: &DecodeError) -> String {
+    let mut report = format!(
+        "Decoding {} failed at `{}` with lab-bench {}\n\n{}\n",
+        error.what,
+        error.path,
+        env!("CARGO_PKG_VERSION"),
+        error.message
+    );
+    if let Some(payload) = &error.payload {
+        report.push_str(&format!("\n```json\n{payload}\n```\n"));
+    }
+    report
+}
+
+/// Banners for the responses which failed to decode, usually GitLab changing its API, with the
+/// report to file about them
+#[component]
+pub fn DecodeFailures() -> Element {
+    let failures = FAILURES.read();
+    if failures.is_empty() {
+        return None;
+    }
+    rsx! {
+        for failure in failures.iter().cloned() {
+            div {
+                key: "{failure.what}{failure.path}",
+                class: "flex flex-row items-center font-ariel text-xs rounded-sm px-1 mb-1 bg-red-100",
+                Icon { width: 12, height: 12, class: "mr-1", icon: FaTriangleExclamation }
+                span { class: "truncate mr-2", title: failure.message.clone(),
+                    "{failure.what} could not be decoded at {failure.path}"
+                }
+                span {
+                    class: "underline cursor-pointer whitespace-nowrap mr-2",
+                    title: "copy a bug report with the payload, every string but the failing value is redacted",
+                    onclick: {
+                        let failure = failure.clone();
+                        move |_event| set_clipboard(&report(&failure))
+                    },
+                    "copy report"
+                }
+                span {
+                    class: "underline cursor-pointer",
+                    onclick: move |_event| FAILURES.write().retain(|f| *f != failure),
+                    "dismiss"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Badges and links added to the merge request rows from data sources outside GitLab, like an
+//! internal ticket system or a deployment tracker. Company specific decorators implement
+//! [`Decorator`] and are registered in [`decorators`] behind a feature flag, so they are compiled
+//! in without forking the rows:
+//!
+//! ```ignore
+//! pub fn decorators() -> Vec<Box<dyn Decorator>> {
+//!     vec![
+//!         #[cfg(feature = "deployments")]
+//!         Box::new(deployments::Deployments),
+//!     ]
+//! }
+//! ```
+
+use anyhow::Result;
+use dioxus::prelude::*;
+use futures::future::{join_all, LocalBoxFuture};
+
+use crate::api::{Instance, MergeRequest};
+
+/// A badge on a row, linking somewhere when it has a URL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoration {
+    pub text: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+}
+
+pub trait Decorator {
+    /// Names the decorator in its errors
+    fn name(&self) -> &'static str;
+
+    /// The badges of the merge request, which may come from requests to other services
+    fn decorate<'a>(
+        &'a self,
+        instance: &'a Instance,
+        merge_request: &'a MergeRequest,
+    ) -> LocalBoxFuture<'a, Result<Vec<Decoration>>>;
+}
+
+/// The decorators compiled in
+pub fn decorators() -> Vec<Box<dyn Decorator>> {
+    vec![]
+}
+
+/// The badges of every decorator in order, a failing decorator is shown as a badge with its error
+async fn decorate(
+    decorators: &[Box<dyn Decorator>],
+    instance: &Instance,
+    merge_request: &MergeRequest,
+) -> Vec<Decoration> {
+    join_all(decorators.iter().map(|d| async move {
+        d.decorate(instance, merge_request)
+            .await
+            .unwrap_or_else(|e| {
+                vec![Decoration {
+                    text: format!("{} failed", d.name()),
+                    title: Some(e.to_string()),
+                    url: None,
+                }]
+            })
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// The badges the compiled in decorators add to a row, fetched once when the row is shown
+#[component]
+pub fn Decorations(merge_request: MergeRequest) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut decorations = use_signal(Vec::<Decoration>::new);
+
+    use_hook(move || {
+        let decorators = decorators();
+        if decorators.is_empty() {
+            return;
+        }
+        spawn(async move {
+            *decorations.write() = decorate(&decorators, &instance(), &merge_request).await;
+        });
+    });
+
+    if decorations.read().is_empty() {
+        return None;
+    }
+    rsx! {
+        div { class: "flex flex-row flex-wrap items-center",
+            for (i, decoration) in decorations().into_iter().enumerate() {
+                if let Some(url) = decoration.url {
+                    a {
+                        key: "{i}",
+                        class: "font-ariel text-xs rounded-full px-2 mr-1 bg-gray-100",
+                        href: url,
+                        title: decoration.title.unwrap_or_default(),
+                        "{decoration.text}"
+                    }
+                } else {
+                    span {
+                        key: "{i}",
+                        class: "font-ariel text-xs rounded-full px-2 mr-1 bg-gray-100",
+                        title: decoration.title.unwrap_or_default(),
+                        "{decoration.text}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use futures::executor::block_on;
+    use futures::FutureExt;
+
+    use super::*;
+
+    struct Deployments;
+
+    impl Decorator for Deployments {
+        fn name(&self) -> &'static str {
+            "deployments"
+        }
+
+        fn decorate<'a>(
+            &'a self,
+            _instance: &'a Instance,
+            merge_request: &'a MergeRequest,
+        ) -> LocalBoxFuture<'a, Result<Vec<Decoration>>> {
+            async move {
+                Ok(vec![Decoration {
+                    text: format!("deployed {}", merge_request.iid),
+                    title: None,
+                    url: Some("https://deploy.example.com".to_string()),
+                }])
+            }
+            .boxed_local()
+        }
+    }
+
+    struct Broken;
+
+    impl Decorator for Broken {
+        fn name(&self) -> &'static str {
+            "tickets"
+        }
+
+        fn decorate<'a>(
+            &'a self,
+            _instance: &'a Instance,
+            _merge_request: &'a MergeRequest,
+        ) -> LocalBoxFuture<'a, Result<Vec<Decoration>>> {
+            async { Err(anyhow!("unreachable")) }.boxed_local()
+        }
+    }
+
+    #[test]
+    fn failing_decorators_are_shown_with_their_error() {
+        let merge_request = MergeRequest {
+            iid: 14,
+            ..Default::default()
+        };
+        let decorators: Vec<Box<dyn Decorator>> = vec![Box::new(Deployments), Box::new(Broken)];
+        let decorations = block_on(decorate(&decorators, &Instance::default(), &merge_request));
+        assert_eq!(
+            decorations,
+            [
+                Decoration {
+                    text: "deployed 14".to_string(),
+                    title: None,
+                    url: Some("https://deploy.example.com".to_string()),
+                },
+                Decoration {
+                    text: "tickets failed".to_string(),
+                    title: Some("unreachable".to_string()),
+                    url: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::Utc;
+use dioxus::prelude::*;
+
+use crate::api::{MergeRequest, User};
+use crate::time_ago;
+use crate::working_hours::Team;
+
+/// A merge request waiting on the approval of a team member, with the rules they can satisfy
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Waiting {
+    merge_request: MergeRequest,
+    rules: Vec<String>,
+}
+
+/// The team members who are eligible approvers of unsatisfied approval rules and haven't
+/// approved, with the merge requests waiting on them. The busiest come first.
+fn waiting_on(
+    team: &HashSet<String>,
+    merge_requests: &[MergeRequest],
+) -> Vec<(User, Vec<Waiting>)> {
+    let mut by_username = BTreeMap::<String, (User, Vec<Waiting>)>::new();
+    for merge_request in merge_requests {
+        for rule in merge_request.approval_rules.iter().filter(|r| !r.approved) {
+            let approvers = rule.eligible_approvers.iter().filter(|u| {
+                team.contains(&u.username) && !rule.approved_by.iter().any(|a| a.id == u.id)
+            });
+            for approver in approvers {
+                let (_, waiting) = by_username
+                    .entry(approver.username.clone())
+                    .or_insert_with(|| (approver.clone(), Vec::new()));
+                match waiting
+                    .iter_mut()
+                    .find(|w| w.merge_request.id == merge_request.id)
+                {
+                    Some(w) => w.rules.push(rule.name.clone()),
+                    None => waiting.push(Waiting {
+                        merge_request: merge_request.clone(),
+                        rules: vec![rule.name.clone()],
+                    }),
+                }
+            }
+        }
+    }
+    let mut waiting_on = by_username.into_values().collect::<Vec<_>>();
+    waiting_on.sort_by_key(|(_, waiting)| std::cmp::Reverse(waiting.len()));
+    waiting_on
+}
+
+/// The approvals the team members are holding up, per person, so they can be chased or handed
+/// to someone else. Needs the full data of the merge requests for their approval rules.
+#[component]
+pub fn TeamApprovals(merge_request_list: Vec<MergeRequest>) -> Element {
+    let members = use_context::<Team>().members;
+    let team = members.read();
+    if team.is_empty() {
+        return rsx! {
+            span { class: "font-ariel text-xs", "Add the team members to the Team setting to see the approvals waiting on them" }
+        };
+    }
+    let usernames = team.keys().cloned().collect::<HashSet<_>>();
+    let waiting_on = waiting_on(&usernames, &merge_request_list);
+
+    rsx! {
+        if waiting_on.is_empty() {
+            span { class: "font-ariel text-xs", "No approvals are waiting on the team" }
+        }
+        for (user, waiting) in waiting_on {
+            div { key: "{user.username}", class: "flex flex-col my-1",
+                div { class: "flex flex-row items-center border-b",
+                    if let Some(working_hours) = team.get(&user.username) {
+                        span {
+                            class: if working_hours.is_online(Utc::now()) { "w-2 h-2 mr-1 rounded-full bg-green-500" } else { "w-2 h-2 mr-1 rounded-full bg-gray-300" },
+                            title: working_hours.describe(Utc::now()),
+                        }
+                    }
+                    a { class: "font-ariel text-sm mr-1", href: user.web_url.clone(), "{user.username}" }
+                    span { class: "font-ariel text-xs",
+                        if waiting.len() == 1 { "1 approval" } else { "{waiting.len()} approvals" }
+                    }
+                }
+                for w in waiting {
+                    div { key: "{w.merge_request.id}", class: "flex flex-row items-center font-ariel text-xs ml-3 min-w-0",
+                        a { class: "mr-1 whitespace-nowrap", href: w.merge_request.web_url.clone(), "{w.merge_request.references.full}" }
+                        span { class: "truncate mr-1", title: w.merge_request.title.clone(), "{w.merge_request.title}" }
+                        span { class: "whitespace-nowrap mr-1", "needs {w.rules.join(\", \")}" }
+                        span { class: "whitespace-nowrap", title: w.merge_request.created_at.to_string(),
+                            "opened {time_ago(w.merge_request.created_at)}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::ApprovalRule;
+
+    use super::*;
+
+    fn user(id: i64, username: &str) -> User {
+        User {
+            id,
+            username: username.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn merge_request(id: i64, rules: Vec<ApprovalRule>) -> MergeRequest {
+        MergeRequest {
+            id,
+            approval_rules: rules,
+            ..Default::default()
+        }
+    }
+
+    fn rule(name: &str, approved_by: Vec<User>, eligible_approvers: Vec<User>) -> ApprovalRule {
+        ApprovalRule {
+            name: name.to_string(),
+            rule_type: Default::default(),
+            approved: false,
+            approvals_required: 1,
+            approved_by,
+            eligible_approvers,
+        }
+    }
+
+    #[test]
+    fn approvals_are_grouped_by_waiting_team_member() {
+        let (alice, bob, carol) = (user(1, "alice"), user(2, "bob"), user(3, "carol"));
+        let team = HashSet::from(["alice".to_string(), "bob".to_string()]);
+        let merge_requests = [
+            merge_request(
+                10,
+                vec![
+                    rule("backend", vec![], vec![alice.clone(), carol.clone()]),
+                    rule("security", vec![], vec![alice.clone()]),
+                ],
+            ),
+            merge_request(
+                11,
+                vec![rule("backend", vec![], vec![alice.clone(), bob.clone()])],
+            ),
+            merge_request(
+                12,
+                vec![rule("frontend", vec![bob.clone()], vec![bob.clone()])],
+            ),
+        ];
+        let waiting = waiting_on(&team, &merge_requests)
+            .into_iter()
+            .map(|(user, waiting)| {
+                let waiting = waiting
+                    .into_iter()
+                    .map(|w| (w.merge_request.id, w.rules))
+                    .collect::<Vec<_>>();
+                (user.username, waiting)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            waiting,
+            [
+                (
+                    "alice".to_string(),
+                    vec![
+                        (10, vec!["backend".to_string(), "security".to_string()]),
+                        (11, vec!["backend".to_string()]),
+                    ]
+                ),
+                ("bob".to_string(), vec![(11, vec!["backend".to_string()])]),
+            ]
+        );
+    }
+}
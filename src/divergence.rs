@@ -0,0 +1,387 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight, FaCodeBranch};
+use dioxus_free_icons::Icon;
+use tracing::error;
+
+use crate::api::{
+    fetch_compare, fetch_merge_requests_of_commits, Commit, Instance, MergeRequest, State,
+};
+use crate::{time_ago, toasts, MergeRequestList};
+
+/// A release branch and the branch it was cut from, which should only diverge by backports
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BranchPair {
+    pub project_path: String,
+    pub base: String,
+    pub release: String,
+}
+
+impl FromStr for BranchPair {
+    type Err = Error;
+
+    /// Parse `<project path> <base branch> <release branch>`, e.g. `group/app main release/1.2`
+    fn from_str(s: &str) -> Result<Self> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let [project_path, base, release] = fields[..] else {
+            return Err(anyhow!(
+                "expected `<project path> <base branch> <release branch>`"
+            ));
+        };
+        Ok(Self {
+            project_path: project_path.to_string(),
+            base: base.to_string(),
+            release: release.to_string(),
+        })
+    }
+}
+
+/// Parse the branch pairs, one per line. Blank lines and lines starting with `#` are ignored.
+pub fn parse(pairs: &str) -> Result<Vec<BranchPair>> {
+    pairs
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|e| anyhow!("release branch {}: {e}", i + 1))
+        })
+        .collect()
+}
+
+/// A commit on only one branch of a pair
+#[derive(Clone, Debug, PartialEq)]
+struct Diverged {
+    commit: Commit,
+    /// The merged merge requests which brought in the commit
+    merge_requests: Vec<MergeRequest>,
+    /// Whether the other branch has a cherry-pick of the commit
+    picked: bool,
+}
+
+/// What each branch of a pair has which the other doesn't
+#[derive(Clone, Debug, PartialEq)]
+struct Divergence {
+    only_in_base: Vec<Diverged>,
+    only_in_release: Vec<Diverged>,
+    /// Merge requests into the base branch labeled for backporting, none of whose commits made
+    /// it to the release branch
+    missing_backports: Vec<MergeRequest>,
+}
+
+/// Whether one of the other commits is a cherry-pick of the commit or the other way around,
+/// going by the `(cherry picked from commit ...)` trailer of `git cherry-pick -x` or else the
+/// same title
+fn is_picked(commit: &Commit, others: &[Commit]) -> bool {
+    let picked_from = |c: &Commit, id: &str| {
+        c.message
+            .contains(&format!("(cherry picked from commit {id}"))
+    };
+    others.iter().any(|other| {
+        picked_from(other, &commit.id)
+            || picked_from(commit, &other.id)
+            || other.title == commit.title
+    })
+}
+
+fn diverged(
+    commits: Vec<Commit>,
+    merge_requests: Vec<Vec<MergeRequest>>,
+    others: &[Commit],
+) -> Vec<Diverged> {
+    commits
+        .into_iter()
+        .zip(merge_requests)
+        .map(|(commit, merge_requests)| Diverged {
+            picked: is_picked(&commit, others),
+            merge_requests: merge_requests
+                .into_iter()
+                .filter(|mr| mr.state == State::Merged)
+                .collect(),
+            commit,
+        })
+        .collect()
+}
+
+/// The merge requests with the backport label which have commits only on the base branch, none
+/// of which were picked. Without a label nothing is missing.
+fn missing_backports(backport_label: &str, only_in_base: &[Diverged]) -> Vec<MergeRequest> {
+    if backport_label.is_empty() {
+        return Vec::new();
+    }
+    let mut missing = Vec::<MergeRequest>::new();
+    let labeled = only_in_base
+        .iter()
+        .flat_map(|d| &d.merge_requests)
+        .filter(|mr| mr.labels.iter().any(|l| l.name == backport_label));
+    for merge_request in labeled {
+        let picked = only_in_base
+            .iter()
+            .filter(|d| d.merge_requests.iter().any(|mr| mr.id == merge_request.id))
+            .any(|d| d.picked);
+        if !picked && !missing.iter().any(|mr| mr.id == merge_request.id) {
+            missing.push(merge_request.clone());
+        }
+    }
+    missing
+}
+
+async fn fetch_divergence(
+    instance: &Instance,
+    pair: &BranchPair,
+    backport_label: &str,
+) -> Result<Divergence> {
+    let BranchPair {
+        project_path,
+        base,
+        release,
+    } = pair;
+    let (only_in_base, only_in_release) = futures::try_join!(
+        fetch_compare(instance, project_path, release, base),
+        fetch_compare(instance, project_path, base, release),
+    )?;
+    let (only_in_base, only_in_release) = (only_in_base.commits, only_in_release.commits);
+    let (base_merge_requests, release_merge_requests) = futures::join!(
+        fetch_merge_requests_of_commits(instance, project_path, &only_in_base),
+        fetch_merge_requests_of_commits(instance, project_path, &only_in_release),
+    );
+    let diverged_base = diverged(only_in_base.clone(), base_merge_requests, &only_in_release);
+    let diverged_release = diverged(only_in_release, release_merge_requests, &only_in_base);
+    Ok(Divergence {
+        missing_backports: missing_backports(backport_label, &diverged_base),
+        only_in_base: diverged_base,
+        only_in_release: diverged_release,
+    })
+}
+
+/// The commits and merge requests on only one branch of each configured pair, raising a toast
+/// for the pairs missing backports
+#[component]
+pub fn ReleaseDivergence(
+    instance: Instance,
+    /// The branch pairs, see [`parse`]
+    pairs: String,
+    /// Merge requests into the base branch with this label should be on the release branch too
+    backport_label: String,
+    toasts: Signal<Vec<toasts::Toast>>,
+) -> Element {
+    let pairs = match parse(&pairs) {
+        Ok(pairs) if pairs.is_empty() => {
+            return rsx! {
+                span { class: "font-ariel text-xs", "Add release branches to the settings to compare them with the branches they were cut from" }
+            };
+        }
+        Ok(pairs) => pairs,
+        Err(e) => return rsx!(span { class: "font-ariel text-xs text-red-700", "{e}" }),
+    };
+    rsx! {
+        for pair in pairs {
+            PairDivergence {
+                key: "{pair.project_path} {pair.base} {pair.release}",
+                instance: instance.clone(),
+                pair: pair.clone(),
+                backport_label: backport_label.trim().to_string(),
+                toasts,
+            }
+        }
+    }
+}
+
+#[component]
+fn PairDivergence(
+    instance: Instance,
+    pair: BranchPair,
+    backport_label: String,
+    toasts: Signal<Vec<toasts::Toast>>,
+) -> Element {
+    let mut divergence = use_signal(|| None::<Result<Divergence, String>>);
+
+    let fetch_pair = pair.clone();
+    let fetch_backport_label = backport_label.clone();
+    use_hook(move || {
+        spawn(async move {
+            let fetched = fetch_divergence(&instance, &fetch_pair, &fetch_backport_label).await;
+            match &fetched {
+                Ok(fetched) if !fetched.missing_backports.is_empty() => {
+                    let BranchPair {
+                        project_path,
+                        release,
+                        ..
+                    } = &fetch_pair;
+                    toasts::show(
+                        toasts,
+                        vec![format!(
+                            "{} backports missing from {project_path} {release}",
+                            fetched.missing_backports.len()
+                        )],
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => error!(
+                    "failed comparing {} {}...{}: {e}",
+                    fetch_pair.project_path, fetch_pair.base, fetch_pair.release
+                ),
+            }
+            *divergence.write() = Some(fetched.map_err(|e| e.to_string()));
+        });
+    });
+
+    let BranchPair {
+        project_path,
+        base,
+        release,
+    } = pair;
+
+    rsx! {
+        div { class: "flex flex-col border rounded-sm border-gray-300 p-1 my-1",
+            div { class: "flex flex-row items-center",
+                Icon { width: 12, height: 12, fill: "#626168", icon: FaCodeBranch }
+                span { class: "font-ariel text-sm mx-1", "{project_path}" }
+                span { class: "font-ariel text-xs", "{base} and {release}" }
+            }
+            match divergence() {
+                Some(Ok(divergence)) => rsx! {
+                    if !divergence.missing_backports.is_empty() {
+                        div { class: "flex flex-col rounded-sm px-1 my-1 bg-red-100",
+                            span { class: "font-ariel text-xs text-red-700",
+                                "{divergence.missing_backports.len()} merge requests labeled {backport_label} are not on {release}"
+                            }
+                            MergeRequestList { merge_request_list: divergence.missing_backports }
+                        }
+                    }
+                    DivergedCommits { branch: base.clone(), other: release.clone(), diverged: divergence.only_in_base }
+                    DivergedCommits { branch: release.clone(), other: base.clone(), diverged: divergence.only_in_release }
+                },
+                Some(Err(e)) => rsx!(span { class: "font-ariel text-xs", "{e}" }),
+                None => rsx!(span { class: "font-ariel text-xs", "loading" }),
+            }
+        }
+    }
+}
+
+/// The commits only on `branch`, collapsed at first
+#[component]
+fn DivergedCommits(branch: String, other: String, diverged: Vec<Diverged>) -> Element {
+    let mut expanded = use_signal(|| false);
+    let picked = diverged.iter().filter(|d| d.picked).count();
+
+    rsx! {
+        div { class: "flex flex-col",
+            span {
+                class: "flex flex-row items-center font-ariel text-xs cursor-pointer",
+                onclick: move |_event| *expanded.write() = !expanded(),
+                if expanded() {
+                    Icon { width: 12, height: 12, icon: FaCaretDown }
+                } else {
+                    Icon { width: 12, height: 12, icon: FaCaretRight }
+                }
+                "{diverged.len()} commits only on {branch}, {picked} of them picked onto {other}"
+            }
+            if expanded() {
+                for d in diverged {
+                    div { key: "{d.commit.id}", class: "flex flex-row items-center font-ariel text-xs ml-3 min-w-0",
+                        a { class: "font-mono mr-1", href: d.commit.web_url.clone(), "{d.commit.short_id}" }
+                        span { class: "truncate mr-1", title: d.commit.title.clone(), "{d.commit.title}" }
+                        for merge_request in d.merge_requests {
+                            a { key: "{merge_request.id}", class: "mr-1 whitespace-nowrap", href: merge_request.web_url.clone(),
+                                "{merge_request.references.short}"
+                            }
+                        }
+                        if d.picked {
+                            span { class: "mr-1 whitespace-nowrap", "picked" }
+                        }
+                        span { class: "whitespace-nowrap", title: d.commit.created_at.to_string(),
+                            "{time_ago(d.commit.created_at)}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::Label;
+
+    use super::*;
+
+    fn commit(id: &str, title: &str, message: &str) -> Commit {
+        Commit {
+            id: id.to_string(),
+            short_id: id.to_string(),
+            title: title.to_string(),
+            message: message.to_string(),
+            author_name: String::new(),
+            created_at: Default::default(),
+            web_url: String::new(),
+        }
+    }
+
+    fn merge_request(id: i64, labels: &[&str]) -> MergeRequest {
+        MergeRequest {
+            id,
+            state: State::Merged,
+            labels: labels
+                .iter()
+                .map(|name| Label {
+                    name: name.to_string(),
+                    color: String::new(),
+                    text_color: String::new(),
+                    description: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn branch_pairs_are_parsed() {
+        assert_eq!(
+            parse("# backports\ngroup/app main release/1.2\n\n").unwrap(),
+            [BranchPair {
+                project_path: "group/app".to_string(),
+                base: "main".to_string(),
+                release: "release/1.2".to_string(),
+            }]
+        );
+        assert!(parse("group/app main").is_err());
+    }
+
+    #[test]
+    fn unpicked_backports_are_missing() {
+        let only_in_base = vec![
+            commit("a1", "Fix the export", ""),
+            commit("b2", "Fix the import", ""),
+            commit("c3", "Add a button", ""),
+            commit("d4", "Fix the login", ""),
+        ];
+        let only_in_release = [
+            commit("e5", "Fix the export", ""),
+            commit(
+                "f6",
+                "Fix login",
+                "Fix login\n\n(cherry picked from commit d4)",
+            ),
+        ];
+        let merge_requests = vec![
+            vec![merge_request(1, &["backport"])],
+            vec![merge_request(2, &["backport"])],
+            vec![merge_request(3, &[])],
+            vec![merge_request(4, &["backport"])],
+        ];
+        let diverged = diverged(only_in_base, merge_requests, &only_in_release);
+        assert_eq!(
+            diverged.iter().map(|d| d.picked).collect::<Vec<_>>(),
+            [true, false, false, true]
+        );
+        let missing = missing_backports("backport", &diverged)
+            .into_iter()
+            .map(|mr| mr.id)
+            .collect::<Vec<_>>();
+        assert_eq!(missing, [2]);
+        assert!(missing_backports("", &diverged).is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::api::MergeRequest;
+
+/// How many changed files there are of each type, most common first. The type is the extension,
+/// or the whole file name for files without one such as `Dockerfile`.
+pub fn file_types(merge_request: &MergeRequest) -> Vec<(String, usize)> {
+    let mut counts = HashMap::<String, usize>::new();
+    for diff in &merge_request.diffs {
+        let name = diff.new_path.rsplit('/').next().unwrap_or(&diff.new_path);
+        let file_type = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => extension,
+            _ => name,
+        };
+        *counts.entry(file_type.to_string()).or_default() += 1;
+    }
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|(a_type, a), (b_type, b)| b.cmp(a).then_with(|| a_type.cmp(b_type)));
+    counts
+}
+
+/// Added and deleted lines and changed files, `None` when the diffs were not fetched
+pub fn diff_size(merge_request: &MergeRequest) -> Option<(usize, usize, usize)> {
+    let diffs = &merge_request.diffs;
+    if diffs.is_empty() {
+        return None;
+    }
+    let additions = diffs.iter().map(|d| d.summary.additions).sum();
+    let deletions = diffs.iter().map(|d| d.summary.deletions).sum();
+    Some((additions, deletions, diffs.len()))
+}
+
+/// Merge requests changing more lines than this are flagged as large
+const LARGE_CHANGE_LINES: usize = 1000;
+
+/// File names which are generated even when GitLab does not say so
+const GENERATED_FILE_NAMES: [&str; 6] = [
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "go.sum",
+    "poetry.lock",
+];
+/// File name endings of generated files
+const GENERATED_FILE_SUFFIXES: [&str; 4] = [".min.js", ".pb.go", "_pb2.py", ".snap"];
+
+/// What a reviewer should know before opening a merge request: very large changes, binary
+/// files, generated files, and files too large for GitLab to show
+pub fn change_warnings(merge_request: &MergeRequest) -> Vec<String> {
+    let diffs = &merge_request.diffs;
+    let mut warnings = Vec::new();
+    let changed_lines = diffs.iter().map(|d| d.summary.changed_lines()).sum::<usize>();
+    if changed_lines > LARGE_CHANGE_LINES {
+        warnings.push(format!("{changed_lines} lines changed"));
+    }
+    let mut count = |what: &str, count: usize| {
+        if count > 0 {
+            warnings.push(format!("{count} {what}"));
+        }
+    };
+    count(
+        "binary files",
+        diffs.iter().filter(|d| d.summary.binary).count(),
+    );
+    count(
+        "generated files",
+        diffs
+            .iter()
+            .filter(|d| {
+                let name = d.new_path.rsplit('/').next().unwrap_or(&d.new_path);
+                d.generated_file
+                    || GENERATED_FILE_NAMES.contains(&name)
+                    || GENERATED_FILE_SUFFIXES.iter().any(|s| name.ends_with(s))
+            })
+            .count(),
+    );
+    count(
+        "files too large to show",
+        diffs.iter().filter(|d| d.too_large).count(),
+    );
+    warnings
+}
@@ -0,0 +1,238 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use dioxus::prelude::*;
+use futures::future::join_all;
+use tracing::error;
+
+use crate::api::{fetch_freeze_periods, FreezePeriod, Instance, MergeRequest};
+
+/// The configured freeze windows and the ones read from GitLab, shared through context so rows
+/// and the merge assistant can tell whether a merge request would merge into a frozen branch
+#[derive(Clone, Copy)]
+pub struct Freezes {
+    /// Written by the user, see [`parse`]
+    pub configured: Signal<String>,
+    /// Parsed from `configured` whenever it changes, so the rows don't parse it on every render.
+    /// Empty while it doesn't parse.
+    pub windows: Signal<Vec<FreezeWindow>>,
+    /// The freeze periods of the projects of the last fetched merge requests
+    pub gitlab: Signal<Vec<FreezeWindow>>,
+}
+
+impl Freezes {
+    /// When the latest freeze applying to a merge request ends, if it is frozen now
+    pub fn frozen_until(
+        &self,
+        merge_request: &MergeRequest,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let configured = self.windows.read();
+        let gitlab = self.gitlab.read();
+        configured
+            .iter()
+            .chain(gitlab.iter())
+            .filter(|w| w.applies_to(merge_request))
+            .filter_map(|w| w.frozen_until(now))
+            .max()
+    }
+}
+
+/// A recurring window during which merging is frozen, written one per line as
+/// `<start cron> .. <end cron> [in <time zone>] [on <target branch>]`, for example
+/// `0 18 * * 5 .. 0 8 * * 1 in Europe/Berlin on main`
+#[derive(Clone, Debug)]
+pub struct FreezeWindow {
+    start: Schedule,
+    end: Schedule,
+    timezone: Tz,
+    /// Only GitLab freeze periods are limited to a project
+    project: Option<String>,
+    branch: Option<String>,
+}
+
+impl FreezeWindow {
+    fn from_freeze_period(project_path: &str, freeze_period: &FreezePeriod) -> Result<Self> {
+        Ok(FreezeWindow {
+            start: schedule(&freeze_period.freeze_start)?,
+            end: schedule(&freeze_period.freeze_end)?,
+            timezone: freeze_period
+                .cron_timezone
+                .parse()
+                .map_err(|e| anyhow!("{e}"))?,
+            project: Some(project_path.to_string()),
+            branch: None,
+        })
+    }
+
+    fn applies_to(&self, merge_request: &MergeRequest) -> bool {
+        self.project
+            .as_deref()
+            .is_none_or(|p| p == merge_request.project_path())
+            && self
+                .branch
+                .as_ref()
+                .is_none_or(|b| *b == merge_request.target_branch)
+    }
+
+    /// Frozen when the window started more recently than it ended
+    fn frozen_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let now = now.with_timezone(&self.timezone);
+        let started = self.start.after(&now).next_back()?;
+        if self
+            .end
+            .after(&now)
+            .next_back()
+            .is_some_and(|ended| ended >= started)
+        {
+            return None;
+        }
+        self.end
+            .after(&now)
+            .next()
+            .map(|end| end.with_timezone(&Utc))
+    }
+}
+
+impl FromStr for FreezeWindow {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let (start, rest) = line
+            .split_once("..")
+            .ok_or_else(|| anyhow!("expected `<start cron> .. <end cron>`"))?;
+        let (rest, branch) = match rest.split_once(" on ") {
+            Some((rest, branch)) => (rest, Some(branch.trim().to_string())),
+            None => (rest, None),
+        };
+        let (end, timezone) = match rest.split_once(" in ") {
+            Some((end, timezone)) => (end, timezone.trim().parse().map_err(|e| anyhow!("{e}"))?),
+            None => (rest, Tz::UTC),
+        };
+        Ok(FreezeWindow {
+            start: schedule(start)?,
+            end: schedule(end)?,
+            timezone,
+            project: None,
+            branch,
+        })
+    }
+}
+
+/// Parse the freeze windows, one per line. Blank lines and lines starting with `#` are ignored.
+pub fn parse(windows: &str) -> Result<Vec<FreezeWindow>> {
+    windows
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|e| anyhow!("freeze window {}: {e}", i + 1))
+        })
+        .collect()
+}
+
+/// Fetch the freeze periods of projects, the ones which can not be fetched or parsed are skipped
+pub async fn fetch_windows(instance: &Instance, project_paths: &[&str]) -> Vec<FreezeWindow> {
+    let futures = project_paths.iter().map(|project_path| async move {
+        let freeze_periods = fetch_freeze_periods(instance, project_path)
+            .await
+            .inspect_err(|e| error!("failed fetching freeze periods of {project_path}: {e}"))
+            .unwrap_or_default();
+        freeze_periods
+            .iter()
+            .filter_map(|freeze_period| {
+                FreezeWindow::from_freeze_period(project_path, freeze_period)
+                    .inspect_err(|e| error!("invalid freeze period of {project_path}: {e}"))
+                    .ok()
+            })
+            .collect::<Vec<_>>()
+    });
+    join_all(futures).await.into_iter().flatten().collect()
+}
+
+/// Parse a standard five field cron expression. The cron crate also takes seconds and numbers the
+/// days of the week from 1, so they are passed by name instead.
+fn schedule(cron: &str) -> Result<Schedule> {
+    const DAYS: [&str; 8] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+    let fields = cron.split_whitespace().collect::<Vec<_>>();
+    let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+        return Err(anyhow!("expected five fields in `{}`", cron.trim()));
+    };
+    let mut days_of_week = String::new();
+    let mut after_step = false;
+    for c in day_of_week.chars() {
+        match c.to_digit(10) {
+            Some(day) if !after_step => {
+                days_of_week.push_str(DAYS.get(day as usize).unwrap_or(&""))
+            }
+            _ => days_of_week.push(c),
+        }
+        if !c.is_ascii_digit() {
+            after_step = c == '/';
+        }
+    }
+    Schedule::from_str(&format!(
+        "0 {minute} {hour} {day_of_month} {month} {days_of_week}"
+    ))
+    .map_err(|e| anyhow!("invalid cron `{}`: {e}", cron.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn window(line: &str) -> FreezeWindow {
+        line.parse().unwrap()
+    }
+
+    #[test]
+    fn frozen_between_start_and_end() {
+        let weekend = window("0 18 * * 5 .. 0 8 * * 1 on main");
+        // Saturday
+        let now = Utc.with_ymd_and_hms(2024, 6, 8, 12, 0, 0).unwrap();
+        assert_eq!(
+            weekend.frozen_until(now),
+            Some(Utc.with_ymd_and_hms(2024, 6, 10, 8, 0, 0).unwrap())
+        );
+        // Wednesday
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 12, 0, 0).unwrap();
+        assert_eq!(weekend.frozen_until(now), None);
+    }
+
+    #[test]
+    fn windows_are_in_their_time_zone() {
+        let evening = window("0 18 * * * .. 0 20 * * * in Europe/Berlin");
+        // 18:30 in Berlin during summer time
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 16, 30, 0).unwrap();
+        assert!(evening.frozen_until(now).is_some());
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 18, 30, 0).unwrap();
+        assert!(evening.frozen_until(now).is_none());
+    }
+
+    #[test]
+    fn windows_apply_to_their_branch() {
+        let weekend = window("0 18 * * 5 .. 0 8 * * 1 on main");
+        let mut merge_request = MergeRequest {
+            target_branch: "main".to_string(),
+            ..Default::default()
+        };
+        assert!(weekend.applies_to(&merge_request));
+        merge_request.target_branch = "develop".to_string();
+        assert!(!weekend.applies_to(&merge_request));
+    }
+
+    #[test]
+    fn invalid_windows_are_reported_by_line() {
+        assert!(parse("# weekends\n0 18 * * 5 .. 0 8 * * 1").is_ok());
+        let e = parse("\n0 18 * * 5").unwrap_err();
+        assert!(e.to_string().starts_with("freeze window 2"));
+        assert!(parse("0 18 * * .. 0 8 * * 1").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use dioxus::prelude::*;
+use gloo_timers::future::sleep;
+use tracing::error;
+
+use crate::api::{
+    fetch_merge_requests_staggered, fetch_merge_requests_with_full_data, Instance, MergeRequest,
+    MergeRequestsDomain, MergeRequestsQuery, PipelineStatus, State,
+};
+use crate::polling::{jittered, stagger, Jitter};
+use crate::{alerts, tabs, toasts, MergeRequestList};
+
+/// How often the hotfixes are refreshed while the view is open, much more often than anyone
+/// clicks query
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// How long a tab keeps refreshing for the other open tabs without renewing, see [`tabs::lead`]
+const LEADER_LEASE: Duration = Duration::from_secs(90);
+/// How often the tabs which don't lead refresh, so they stay current without each hitting the
+/// instance as often as the leading tab
+const FOLLOWER_REFRESH_INTERVAL: Duration = Duration::from_secs(120);
+/// Merge requests targeting branches starting with these are hotfixes
+const HOTFIX_BRANCH_PREFIXES: [&str; 3] = ["hotfix/", "release/", "releases/"];
+
+pub fn is_hotfix(merge_request: &MergeRequest) -> bool {
+    HOTFIX_BRANCH_PREFIXES
+        .iter()
+        .any(|prefix| merge_request.target_branch.starts_with(prefix))
+}
+
+/// Open merge requests of the domains targeting release and hotfix branches, refreshed every
+/// [`REFRESH_INTERVAL`] give or take the jitter while shown. The domains are fetched one after the
+/// other over the first half of the interval. When several tabs show hotfixes only one of them
+/// refreshes that often and alerts, the others refresh every [`FOLLOWER_REFRESH_INTERVAL`]. New
+/// hotfixes and failed pipelines always raise a toast and play the alert, whether or not the
+/// sound alert is enabled.
+#[component]
+pub fn Hotfixes(
+    instance: Instance,
+    /// The query and domains when the view was opened, only open merge requests are fetched
+    query: MergeRequestsQuery,
+    domains: Vec<MergeRequestsDomain>,
+    toasts: Signal<Vec<toasts::Toast>>,
+) -> Element {
+    let mut hotfixes = use_signal(|| None::<Result<Vec<MergeRequest>, String>>);
+    let mut refreshed_at = use_signal(|| None::<DateTime<Utc>>);
+    let mut refreshed_elsewhere = use_signal(|| false);
+    let Jitter(jitter) = use_context::<Jitter>();
+
+    // Tasks are dropped with the component, so this stops when switching views
+    use_drop(|| tabs::release("hotfixes"));
+    use_hook(move || {
+        spawn(async move {
+            let query = MergeRequestsQuery {
+                state: Some(State::Opened),
+                ..query
+            };
+            let delays = stagger(REFRESH_INTERVAL, domains.len());
+            let mut leading = false;
+            loop {
+                let was_leading = leading;
+                leading = tabs::lead("hotfixes", LEADER_LEASE);
+                *refreshed_elsewhere.write() = !leading;
+                // Followers keep checking for the lead every interval but refresh less often
+                let follower_refresh_due = refreshed_at.peek().is_none_or(|refreshed_at| {
+                    Utc::now() - refreshed_at
+                        >= TimeDelta::from_std(FOLLOWER_REFRESH_INTERVAL).unwrap_or_default()
+                });
+                if !leading && !follower_refresh_due {
+                    sleep(jittered(REFRESH_INTERVAL, *jitter.peek())).await;
+                    continue;
+                }
+                let fetched = match fetch_merge_requests_staggered(
+                    &instance, &query, &domains, &delays,
+                )
+                .await
+                {
+                    Ok(merge_requests) => {
+                        let merge_requests = merge_requests
+                            .into_iter()
+                            .filter(is_hotfix)
+                            .collect::<Vec<_>>();
+                        fetch_merge_requests_with_full_data(&instance, &merge_requests).await
+                    }
+                    Err(e) => Err(e),
+                };
+                match fetched {
+                    Ok(current) => {
+                        let previous = hotfixes
+                            .peek()
+                            .clone()
+                            .and_then(Result::ok)
+                            .unwrap_or_default();
+                        // The leading tab alerts for all tabs
+                        if leading {
+                            let messages = urgent_messages(&previous, &current, !was_leading);
+                            if !messages.is_empty() {
+                                alerts::play_alert();
+                            }
+                            toasts::show(toasts, messages);
+                        }
+                        *hotfixes.write() = Some(Ok(current));
+                    }
+                    Err(e) => {
+                        error!("failed refreshing hotfixes: {e}");
+                        *hotfixes.write() = Some(Err(e.to_string()));
+                    }
+                }
+                *refreshed_at.write() = Some(Utc::now());
+                sleep(jittered(REFRESH_INTERVAL, *jitter.peek())).await;
+            }
+        })
+    });
+
+    let branches = HOTFIX_BRANCH_PREFIXES.join(", ");
+    let interval = match jitter() {
+        0 => format!("{}s", REFRESH_INTERVAL.as_secs()),
+        jitter => format!("{}s ±{jitter}%", REFRESH_INTERVAL.as_secs()),
+    };
+    let refreshed_at = refreshed_at().map(|t| t.format("%H:%M:%S UTC").to_string());
+
+    rsx! {
+        div { class: "flex flex-col border-2 rounded-sm border-red-700 p-1 my-1",
+            div { class: "flex flex-row items-center font-ariel text-xs text-red-700",
+                span { class: "mr-2",
+                    "Open merge requests into {branches} branches, refreshed every {interval}"
+                }
+                if let Some(refreshed_at) = refreshed_at {
+                    span { class: "mr-2", "last refreshed {refreshed_at}" }
+                }
+                if refreshed_elsewhere() {
+                    span { "another open tab alerts, this one refreshes every {FOLLOWER_REFRESH_INTERVAL.as_secs()}s" }
+                }
+            }
+            match hotfixes() {
+                Some(Ok(merge_request_list)) if merge_request_list.is_empty() => rsx!(span { class: "font-ariel text-xs", "no open hotfixes" }),
+                Some(Ok(merge_request_list)) => rsx!(MergeRequestList { merge_request_list }),
+                Some(Err(e)) => rsx!(span { "{e}" }),
+                None => rsx!(span { class: "font-ariel text-xs", "loading" }),
+            }
+        }
+    }
+}
+
+/// The hotfixes which appeared or whose pipeline newly failed since the previous refresh. The
+/// first refresh has nothing to compare to, so only failures are reported.
+fn urgent_messages(
+    previous: &[MergeRequest],
+    current: &[MergeRequest],
+    first: bool,
+) -> Vec<String> {
+    let failed = |mr: &MergeRequest| {
+        mr.head_pipeline
+            .as_ref()
+            .is_some_and(|p| p.status == PipelineStatus::Failed)
+    };
+    let mut messages = Vec::new();
+    for merge_request in current {
+        let reference = &merge_request.references.full;
+        let before = previous.iter().find(|mr| mr.id == merge_request.id);
+        if before.is_none() && !first {
+            messages.push(format!(
+                "new hotfix {reference} into {}",
+                merge_request.target_branch
+            ));
+        }
+        if failed(merge_request) && !before.is_some_and(failed) {
+            messages.push(format!("hotfix {reference} pipeline failed"));
+        }
+    }
+    messages
+}
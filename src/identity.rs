@@ -0,0 +1,19 @@
+use dioxus::prelude::*;
+
+use crate::api::User;
+
+/// Who the instance is accessed as, or why the credentials do not work
+#[component]
+pub fn Identity(identity: Option<Result<User, String>>) -> Element {
+    match identity? {
+        Ok(user) => rsx! {
+            a { class: "flex flex-row items-center font-ariel text-xs", href: user.web_url,
+                img { class: "w-4 h-4 rounded-full mr-1", src: user.avatar_url }
+                "signed in as {user.username}"
+            }
+        },
+        Err(e) => rsx! {
+            span { class: "font-ariel text-xs text-red-700", "{e}" }
+        },
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use dioxus::prelude::*;
+
+use crate::api::MergeRequest;
+use crate::storage;
+
+/// Local storage key of the incident links attached to merge requests
+const INCIDENTS_STORAGE_KEY: &str = "lab-bench-incidents";
+/// Scoped labels with this prefix name the incident, e.g. `incident::INC-1234`
+const INCIDENT_LABEL_PREFIX: &str = "incident::";
+
+/// Incident or ticket links of merge requests, shared through context so rows can show them and
+/// the menu can attach them
+#[derive(Clone, Copy)]
+pub struct Incidents {
+    /// Full references of merge requests to the incident URL attached to them locally
+    pub links: Signal<HashMap<String, String>>,
+    /// URL of an incident where `{id}` is replaced by the value of an `incident::` label
+    pub url_template: Signal<String>,
+}
+
+impl Incidents {
+    pub fn load() -> Self {
+        Incidents {
+            links: Signal::new(storage::load(INCIDENTS_STORAGE_KEY).unwrap_or_default()),
+            url_template: Signal::new(String::new()),
+        }
+    }
+
+    /// The incident URL attached locally, falling back to the one named by a label
+    pub fn url(&self, merge_request: &MergeRequest) -> Option<String> {
+        if let Some(url) = self.links.read().get(&merge_request.references.full) {
+            return Some(url.clone());
+        }
+        let id = merge_request
+            .labels
+            .iter()
+            .find_map(|l| l.name.strip_prefix(INCIDENT_LABEL_PREFIX))?;
+        let url_template = self.url_template.read();
+        if id.starts_with("http") || url_template.is_empty() {
+            return Some(id.to_string());
+        }
+        Some(url_template.replace("{id}", id))
+    }
+
+    /// Attach an incident URL to a merge request, an empty URL removes it
+    pub fn attach(&mut self, merge_request: &MergeRequest, url: &str) {
+        let mut links = self.links.write();
+        let reference = merge_request.references.full.clone();
+        match url.trim() {
+            "" => links.remove(&reference),
+            url => links.insert(reference, url.to_string()),
+        };
+        storage::save(INCIDENTS_STORAGE_KEY, &*links);
+    }
+}
@@ -0,0 +1,61 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight};
+use dioxus_free_icons::Icon;
+
+use crate::api::{fetch_closes_issues, Instance, Issue, MergeRequest, State};
+
+/// The issues a merge request closes, fetched the first time the section is expanded
+#[component]
+pub fn ClosesIssues(merge_request: MergeRequest) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut expanded = use_signal(|| false);
+    let mut issues = use_signal(|| None::<Result<Vec<Issue>, String>>);
+
+    let toggle = move |_event| {
+        *expanded.write() = !expanded();
+        if issues.peek().is_some() {
+            return;
+        }
+        let merge_request = merge_request.clone();
+        spawn(async move {
+            *issues.write() = Some(
+                fetch_closes_issues(&instance(), &merge_request)
+                    .await
+                    .map_err(|e| e.to_string()),
+            );
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col",
+            span { class: "flex flex-row items-center font-ariel text-xs cursor-pointer", onclick: toggle,
+                if expanded() {
+                    Icon { width: 12, height: 12, icon: FaCaretDown }
+                } else {
+                    Icon { width: 12, height: 12, icon: FaCaretRight }
+                }
+                "closes issues"
+            }
+            if expanded() {
+                match issues() {
+                    Some(Ok(issues)) if issues.is_empty() => rsx!(span { class: "font-ariel text-xs ml-3", "none" }),
+                    Some(Ok(issues)) => rsx! {
+                        for issue in issues {
+                            div { key: "{issue.id}", class: "flex flex-row items-center font-ariel text-xs ml-3 min-w-0",
+                                a { class: "mr-1", href: issue.web_url.clone(), "{issue.references.full}" }
+                                span {
+                                    class: if issue.state == State::Closed { "truncate mr-1 line-through" } else { "truncate mr-1" },
+                                    title: issue.title.clone(),
+                                    "{issue.title}"
+                                }
+                                span { class: "whitespace-nowrap", "{issue.state}" }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx!(span { class: "font-ariel text-xs ml-3", "{e}" }),
+                    None => rsx!(span { class: "font-ariel text-xs ml-3", "loading" }),
+                }
+            }
+        }
+    }
+}
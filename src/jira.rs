@@ -0,0 +1,89 @@
+use dioxus::prelude::*;
+
+use crate::api::MergeRequest;
+
+/// Base URL of the Jira instance the issue keys in merge requests link to, shared through context
+#[derive(Clone, Copy)]
+pub struct Jira(pub Signal<String>);
+
+/// The Jira issue keys like `ABC-1234` in the text, in order without repeats. A key is a project
+/// key of an upper case letter followed by upper case letters, digits or underscores, a dash and
+/// the issue number, which is not part of a longer word.
+pub fn keys(text: &str) -> Vec<String> {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut keys = Vec::<String>::new();
+    let mut previous = None::<char>;
+    for (start, c) in text.char_indices() {
+        let starts_word = previous.is_none_or(|p| !is_word(p));
+        previous = Some(c);
+        if !starts_word || !c.is_ascii_uppercase() {
+            continue;
+        }
+        let rest = &text[start..];
+        let project_len = rest
+            .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+            .unwrap_or(rest.len());
+        let Some(number) = rest[project_len..].strip_prefix('-') else {
+            continue;
+        };
+        let number_len = number
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(number.len());
+        let ends_word = number[number_len..]
+            .chars()
+            .next()
+            .is_none_or(|c| !is_word(c));
+        if project_len < 2 || number_len == 0 || !ends_word {
+            continue;
+        }
+        let key = &rest[..project_len + 1 + number_len];
+        if !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    }
+    keys
+}
+
+/// Links to the Jira issues named in the title, description or source branch of a merge request,
+/// left out until the Jira URL is set
+#[component]
+pub fn JiraLinks(merge_request: MergeRequest) -> Element {
+    let Jira(jira_url) = use_context::<Jira>();
+    let jira_url = jira_url.read().trim().trim_end_matches('/').to_string();
+    if jira_url.is_empty() {
+        return None;
+    }
+    let text = [
+        merge_request.title.as_str(),
+        merge_request.description.as_deref().unwrap_or_default(),
+        merge_request.source_branch.as_str(),
+    ]
+    .join("\n");
+    let keys = keys(&text);
+    if keys.is_empty() {
+        return None;
+    }
+    rsx! {
+        div { class: "flex flex-row flex-wrap items-center font-ariel text-xs",
+            span { class: "mr-1", "jira" }
+            for key in keys {
+                a { key: "{key}", class: "underline mr-1", href: "{jira_url}/browse/{key}", "{key}" }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_keys_are_found_in_text() {
+        assert_eq!(
+            keys("ABC-1234: fix the export, see also PROJ_2-7 and ABC-1234"),
+            ["ABC-1234", "PROJ_2-7"]
+        );
+        assert_eq!(keys("feature/ABC-12-csv-export"), ["ABC-12"]);
+        assert!(keys("xABC-12 ABC-12b A-1 ABC- abc-12").is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight};
+use dioxus_free_icons::Icon;
+
+use crate::api::{fetch_pipeline_jobs, Instance, Job, MergeRequest, PipelineStatus};
+use crate::status;
+
+/// Group jobs by stage, in the order the stages run. GitLab lists the newest jobs first and
+/// creates the jobs of earlier stages first.
+fn stages(mut jobs: Vec<Job>) -> Vec<(String, Vec<Job>)> {
+    jobs.sort_by_key(|j| j.id);
+    let mut stages = Vec::<(String, Vec<Job>)>::new();
+    for job in jobs {
+        match stages.iter_mut().find(|(stage, _)| *stage == job.stage) {
+            Some((_, jobs)) => jobs.push(job),
+            None => stages.push((job.stage.clone(), vec![job])),
+        }
+    }
+    stages
+}
+
+/// The jobs of the head pipeline by stage, fetched the first time the section is expanded. Only
+/// shown for GitLab pipelines, external CI systems have no jobs to fetch.
+#[component]
+pub fn Jobs(merge_request: MergeRequest) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut expanded = use_signal(|| false);
+    let mut jobs = use_signal(|| None::<Result<Vec<(String, Vec<Job>)>, String>>);
+
+    let pipeline = merge_request
+        .head_pipeline
+        .clone()
+        .filter(|p| p.has_jobs())?;
+    let project_id = merge_request.project_id;
+    let toggle = move |_event| {
+        *expanded.write() = !expanded();
+        if jobs.peek().is_some() {
+            return;
+        }
+        spawn(async move {
+            *jobs.write() = Some(
+                fetch_pipeline_jobs(&instance(), project_id, pipeline.id, None)
+                    .await
+                    .map(stages)
+                    .map_err(|e| e.to_string()),
+            );
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col",
+            span { class: "flex flex-row items-center font-ariel text-xs cursor-pointer", onclick: toggle,
+                if expanded() {
+                    Icon { width: 12, height: 12, icon: FaCaretDown }
+                } else {
+                    Icon { width: 12, height: 12, icon: FaCaretRight }
+                }
+                "jobs"
+            }
+            if expanded() {
+                match jobs() {
+                    Some(Ok(stages)) => rsx! {
+                        div { class: "flex flex-row flex-wrap ml-3",
+                            for (stage, jobs) in stages {
+                                div { key: "{stage}", class: "flex flex-col font-ariel text-xs mr-3",
+                                    span { class: "border-b mb-1", "{stage}" }
+                                    for job in jobs {
+                                        a {
+                                            key: "{job.id}",
+                                            class: "flex flex-row items-center",
+                                            href: job.web_url.clone(),
+                                            title: if job.allow_failure { "{job.status}, allowed to fail" } else { "{job.status}" },
+                                            status::StatusIconView { icon: status::pipeline_status_icon(&job.status) }
+                                            span {
+                                                class: if job.status == PipelineStatus::Failed && !job.allow_failure { "ml-1 text-red-700" } else { "ml-1" },
+                                                "{job.name}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx!(span { class: "font-ariel text-xs ml-3", "{e}" }),
+                    None => rsx!(span { class: "font-ariel text-xs ml-3", "loading" }),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: i64, stage: &str) -> Job {
+        Job {
+            id,
+            name: format!("job {id}"),
+            stage: stage.to_string(),
+            status: PipelineStatus::Success,
+            web_url: String::new(),
+            allow_failure: false,
+        }
+    }
+
+    #[test]
+    fn jobs_are_grouped_by_stage_in_run_order() {
+        let stages = stages(vec![
+            job(4, "deploy"),
+            job(3, "test"),
+            job(2, "test"),
+            job(1, "build"),
+        ]);
+        let names = stages
+            .iter()
+            .map(|(stage, _)| stage.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["build", "test", "deploy"]);
+        assert_eq!(stages[1].1[0].id, 2);
+    }
+}
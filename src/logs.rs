@@ -0,0 +1,226 @@
+//! The tracing subscriber, filtered by the level and scope from the settings. Recent lines are
+//! kept in memory so they can be downloaded for bug reports.
+
+use std::collections::VecDeque;
+use std::fmt::{Debug, Write};
+use std::sync::Mutex;
+
+use chrono::Utc;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum::{Display, EnumIter};
+use tracing::field::{Field, Visit};
+use tracing::subscriber::Interest;
+use tracing::{error, Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::Registry;
+
+/// How many of the most recent lines are kept for downloading
+const MAX_LINES: usize = 2000;
+/// Target of the events logged by the GitLab and GitHub clients
+const API_TARGET: &str = "lab_bench::api";
+const CRATE_TARGET: &str = "lab_bench";
+/// Save the received text as a file, the browser has no other way to write one
+const DOWNLOAD_SCRIPT: &str = r#"
+const { name, text } = await dioxus.recv();
+const link = document.createElement("a");
+link.href = URL.createObjectURL(new Blob([text], { type: "text/plain" }));
+link.download = name;
+link.click();
+URL.revokeObjectURL(link.href);
+"#;
+
+static FILTER: Mutex<Filter> = Mutex::new(Filter {
+    level: LogLevel::Info,
+    scope: LogScope::All,
+});
+static LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, Copy, Debug, Default, Display, Deserialize, Serialize, PartialEq, Eq, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn level(self) -> Level {
+        match self {
+            LogLevel::Error => Level::ERROR,
+            LogLevel::Warn => Level::WARN,
+            LogLevel::Info => Level::INFO,
+            LogLevel::Debug => Level::DEBUG,
+            LogLevel::Trace => Level::TRACE,
+        }
+    }
+}
+
+/// Which part of the app to log, errors are logged from everywhere
+#[derive(Clone, Copy, Debug, Default, Display, Deserialize, Serialize, PartialEq, Eq, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum LogScope {
+    /// The app and its dependencies
+    #[default]
+    All,
+    /// Requests to GitLab and GitHub
+    Api,
+    /// The app besides its requests
+    Ui,
+}
+
+impl LogScope {
+    fn matches(self, target: &str) -> bool {
+        let in_module = |module: &str| {
+            target
+                .strip_prefix(module)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        };
+        match self {
+            LogScope::All => true,
+            LogScope::Api => in_module(API_TARGET),
+            LogScope::Ui => in_module(CRATE_TARGET) && !in_module(API_TARGET),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Filter {
+    level: LogLevel,
+    scope: LogScope,
+}
+
+impl Filter {
+    fn enables(&self, metadata: &Metadata<'_>) -> bool {
+        let level = *metadata.level();
+        level == Level::ERROR
+            || (level <= self.level.level() && self.scope.matches(metadata.target()))
+    }
+}
+
+/// Filters by the current settings, prints to the console and keeps the recent lines
+struct Capture;
+
+impl<S: Subscriber> Layer<S> for Capture {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // The filter changes with the settings, so it can't be cached per callsite
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        FILTER.lock().is_ok_and(|filter| filter.enables(metadata))
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let line = format!(
+            "{} {:>5} {}: {}{}",
+            Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            metadata.level(),
+            metadata.target(),
+            fields.message,
+            fields.rest
+        );
+        print(*metadata.level(), &line);
+        if let Ok(mut lines) = LINES.lock() {
+            if lines.len() == MAX_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+}
+
+/// The message of an event followed by its other fields as `name=value`
+#[derive(Default)]
+struct Fields {
+    message: String,
+    rest: String,
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.rest, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.rest, " {}={value}", field.name());
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn print(level: Level, line: &str) {
+    let line = line.into();
+    match level {
+        Level::ERROR => web_sys::console::error_1(&line),
+        Level::WARN => web_sys::console::warn_1(&line),
+        Level::INFO => web_sys::console::info_1(&line),
+        _ => web_sys::console::debug_1(&line),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn print(_level: Level, line: &str) {
+    eprintln!("{line}");
+}
+
+pub fn init() {
+    console_error_panic_hook::set_once();
+    tracing::subscriber::set_global_default(Registry::default().with(Capture))
+        .expect("failed to init logger");
+}
+
+/// Change what is logged from now on
+pub fn configure(level: LogLevel, scope: LogScope) {
+    if let Ok(mut filter) = FILTER.lock() {
+        *filter = Filter { level, scope };
+    }
+}
+
+/// Save the recent lines as a text file to attach to bug reports
+pub fn download() {
+    let mut text = format!("lab-bench {}\n", env!("CARGO_PKG_VERSION"));
+    if let Ok(lines) = LINES.lock() {
+        for line in lines.iter() {
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    let name = format!("lab-bench-{}.log", Utc::now().format("%Y%m%d-%H%M%S"));
+    if let Err(e) = eval(DOWNLOAD_SCRIPT).send(json!({ "name": name, "text": text })) {
+        error!("failed downloading logs: {e:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_split_requests_from_the_rest_of_the_app() {
+        assert!(LogScope::Api.matches("lab_bench::api"));
+        assert!(!LogScope::Api.matches("lab_bench::outbox"));
+        assert!(!LogScope::Api.matches("lab_bench::apis"));
+        assert!(LogScope::Ui.matches("lab_bench"));
+        assert!(LogScope::Ui.matches("lab_bench::outbox"));
+        assert!(!LogScope::Ui.matches("lab_bench::api"));
+        assert!(!LogScope::Ui.matches("dioxus_core::virtual_dom"));
+        assert!(LogScope::All.matches("dioxus_core::virtual_dom"));
+    }
+}
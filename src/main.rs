@@ -1,25 +1,139 @@
+// The app itself is unused when building the component preview
+#![cfg_attr(feature = "preview", allow(dead_code))]
+
+use std::collections::{HashMap, HashSet};
 use std::sync::OnceLock;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use dioxus::prelude::*;
 use dioxus_free_icons::icons::fa_solid_icons::{
-    FaBan, FaCaretDown, FaCaretRight, FaCircleCheck, FaCircleExclamation, FaCircleQuestion,
-    FaCodeBranch, FaCodeMerge, FaComment, FaListCheck, FaSpinner,
+    FaCaretDown, FaCaretRight, FaCodeBranch, FaComment, FaEllipsisVertical, FaFire, FaGauge,
+    FaShieldHalved, FaSnowflake, FaTriangleExclamation,
 };
 use dioxus_free_icons::Icon;
 use timeago::Formatter;
-use tracing::{info, Level};
-use strum::IntoEnumIterator;
+use strum::{Display, EnumIter, IntoEnumIterator};
+use tracing::{error, info};
 
 use crate::api::{
-    fetch_merge_requests, fetch_merge_requests_with_full_data, MergeRequest, MergeRequestsDomain,
-    MergeRequestsQuery, OrderBy, Scope, Sort,
+    detect_session, fetch_current_user, fetch_merge_requests_to_review,
+    fetch_broadcast_messages, fetch_merge_requests_with_full_data, fetch_starred_projects, Auth, BroadcastMessage, GitHub, Instance, GITHUB_API_URL, MergeRequest,
+    Backend, MergeRequestsDomain, Provider, MergeRequestsQuery, Pagination,
 };
+use crate::settings::{Draft, QueryInputs, Settings};
 
+mod alerts;
 mod api;
+mod authors;
+mod board;
+mod branches;
+mod calendar;
+mod changelog;
+mod chart;
+mod codeowners;
+mod commits;
+mod custom_field;
+mod decode_errors;
+mod decorations;
+mod delegation;
+mod divergence;
+mod files;
+mod freeze;
+mod hotfix;
+mod identity;
+mod incidents;
+mod issues;
+mod jira;
+mod jobs;
+mod logs;
+mod menu;
+mod merge_order;
+mod milestones;
+mod oncall;
+mod outbox;
+mod participants;
+mod paths;
+mod polling;
+#[cfg(feature = "preview")]
+mod preview;
+mod raw;
+mod rebase;
+mod recent;
+mod releases;
+mod rules;
+mod search;
+mod settings;
+mod stacks;
+mod status;
+mod storage;
+mod tabs;
+mod test_report;
+mod timeline;
+mod toasts;
+mod usage;
+mod wizard;
+mod working_hours;
+
+/// Local storage key of the snoozed merge requests
+const SNOOZED_STORAGE_KEY: &str = "lab-bench-snoozed";
+const SNOOZE_DURATION: TimeDelta = TimeDelta::days(1);
+/// How many eligible approvers of an unsatisfied approval rule are listed on a row
+const MAX_ELIGIBLE_APPROVERS: usize = 5;
+/// Local storage key of the pinned merge requests
+const PINNED_STORAGE_KEY: &str = "lab-bench-pinned";
+/// Session storage key of the settings edited but not saved yet
+const DRAFT_STORAGE_KEY: &str = "lab-bench-draft";
+
+/// Full references of the pinned merge requests in their manual order, shared through context so
+/// rules can pin merge requests too
+#[derive(Clone, Copy)]
+struct Pinned(Signal<Vec<String>>);
+
+/// Ids of the merge requests selected for bulk actions
+#[derive(Clone, Copy)]
+struct Selected(Signal<HashSet<i64>>);
+
+/// The top level views that can be switched between in the header
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, EnumIter)]
+enum View {
+    #[default]
+    #[strum(serialize = "Merge Requests")]
+    MergeRequests,
+    Releases,
+    Changelog,
+    Branches,
+    #[strum(serialize = "Recently Viewed")]
+    Recent,
+    #[strum(serialize = "My Areas")]
+    MyAreas,
+    Hotfixes,
+    #[strum(serialize = "Release Divergence")]
+    ReleaseDivergence,
+    #[strum(serialize = "Team Approvals")]
+    TeamApprovals,
+    #[strum(serialize = "Your Usage")]
+    Usage,
+}
+
+/// How the merge requests are laid out
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+enum Layout {
+    #[default]
+    List,
+    Board,
+    Calendar,
+    Timeline,
+    ByAuthor,
+    ByMilestone,
+}
 
 fn main() {
-    dioxus_logger::init(Level::INFO).expect("failed to init logger");
+    logs::init();
+    api::on_decode_error(decode_errors::record);
+    #[cfg(feature = "preview")]
+    dioxus::launch(preview::Preview);
+    #[cfg(not(feature = "preview"))]
     dioxus::launch(App)
 }
 
@@ -31,29 +145,208 @@ fn App() -> Element {
     let initial_private_token = "";
 
     // Inputs
-    let mut gitlab_url = use_signal(|| initial_gitlab_url.to_string());
-    let mut private_token = use_signal(|| initial_private_token.to_string());
+    let mut instance = use_context_provider(|| {
+        Signal::new(Instance {
+            url: initial_gitlab_url.to_string(),
+            auth: Auth::default(),
+            private_token: initial_private_token.to_string(),
+            headers: Vec::new(),
+            request_tag: None,
+        })
+    });
     let mut query_expanded = use_signal(|| true);
+    let mut setup_needed = use_signal(wizard::needed);
+    // The user the token or session belongs to, checked whenever the credentials change
+    let mut identity = use_signal(|| None::<Result<api::User, String>>);
+    let check_token = move || {
+        spawn(async move {
+            *identity.write() = Some(
+                fetch_current_user(&instance())
+                    .await
+                    .map_err(|e| format!("invalid token: {e}")),
+            );
+        });
+    };
+    let mut view = use_signal(View::default);
+    let mut layout = use_signal(Layout::default);
     // TODO: on input update the `query` and`domains` signals dynamically
-    let mut query = use_signal(|| MergeRequestsQuery {
-        created_after: None,
-        created_before: None,
-        order_by: OrderBy::default(),
-        scope: Scope::All,
-        sort: Sort::default(),
-        state: None,
-        updated_after: None,
-        updated_before: None,
-        wip: None,
+    let mut query = use_signal(MergeRequestsQuery::default);
+    let mut query_inputs = use_signal(QueryInputs::default);
+    let mut headers = use_signal(String::new);
+    let mut authors = use_signal(String::new);
+    let mut assignees = use_signal(String::new);
+    let mut reviewers = use_signal(String::new);
+    let mut repos = use_signal(String::new);
+    let mut groups = use_signal(String::new);
+    let mut rules = use_signal(String::new);
+    let mut routed_labels = use_signal(String::new);
+    let mut monorepo = use_signal(String::new);
+    let mut path_prefixes = use_signal(String::new);
+    let freezes = use_context_provider(|| freeze::Freezes {
+        configured: Signal::new(String::new()),
+        windows: Signal::new(Vec::new()),
+        gitlab: Signal::new(Vec::new()),
+    });
+    let mut freeze_windows = freezes.configured;
+    let mut parsed_freeze_windows = freezes.windows;
+    use_effect(move || *parsed_freeze_windows.write() = freeze::parse(&freeze_windows()).unwrap_or_default());
+    let mut gitlab_freeze_periods = use_signal(|| false);
+    let mut github = use_signal(|| GitHub {
+        url: GITHUB_API_URL.to_string(),
+        token: String::new(),
     });
-    let mut author_domains = use_signal(|| {vec![]});
-    let mut project_domains = use_signal(|| {vec![]});
+    let mut github_repos = use_signal(String::new);
+    let working_hours::Team { configured: mut team, members: mut team_members } =
+        use_context_provider(|| working_hours::Team {
+            configured: Signal::new(String::new()),
+            members: Signal::new(HashMap::new()),
+        });
+    use_effect(move || *team_members.write() = working_hours::parse(&team()).unwrap_or_default());
+    let mut on_call_calendar = use_signal(String::new);
+    let mut release_branches = use_signal(String::new);
+    let mut backport_label = use_signal(String::new);
+    let incidents = use_context_provider(incidents::Incidents::load);
+    use_context_provider(outbox::Outbox::load);
+    let mut incident_url_template = incidents.url_template;
+    let jira::Jira(mut jira_url) = use_context_provider(|| jira::Jira(Signal::new(String::new())));
+    let polling::Jitter(mut poll_jitter) = use_context_provider(|| polling::Jitter(Signal::new(polling::DEFAULT_JITTER)));
+    let custom_field::CustomField(mut custom_field) =
+        use_context_provider(|| custom_field::CustomField(Signal::new(String::new())));
+    let mut log_level = use_signal(logs::LogLevel::default);
+    let mut log_scope = use_signal(logs::LogScope::default);
+    use_effect(move || logs::configure(log_level(), log_scope()));
+    let oncall::OnCall(mut shifts) = use_context_provider(|| oncall::OnCall(Signal::new(Vec::new())));
+    let settings = move || Settings {
+        gitlab_url: instance.read().url.clone(),
+        headers: headers(),
+        request_tag: instance.read().request_tag.clone(),
+        auth: instance.read().auth,
+        repos: repos(),
+        groups: groups(),
+        authors: authors(),
+        assignees: assignees(),
+        reviewers: reviewers(),
+        query: query(),
+        rules: rules(),
+        routed_labels: routed_labels(),
+        monorepo: monorepo(),
+        path_prefixes: path_prefixes(),
+        freeze_windows: freeze_windows(),
+        gitlab_freeze_periods: gitlab_freeze_periods(),
+        github_url: github.read().url.clone(),
+        github_repos: github_repos(),
+        team: team(),
+        on_call_calendar: on_call_calendar(),
+        release_branches: release_branches(),
+        backport_label: backport_label(),
+        incident_url_template: incident_url_template(),
+        jira_url: jira_url(),
+        poll_jitter: poll_jitter(),
+        custom_field: custom_field(),
+        log_level: log_level(),
+        log_scope: log_scope(),
+    };
+    // The settings last saved to or loaded from the snippet, the draft only holds edits of them
+    let mut saved = use_signal(settings);
+
+    let mut apply_settings = move |loaded: Settings| {
+        instance.write().url = loaded.gitlab_url;
+        instance.write().headers = Instance::parse_headers(&loaded.headers);
+        instance.write().request_tag = loaded.request_tag;
+        instance.write().auth = loaded.auth;
+        *headers.write() = loaded.headers;
+        *repos.write() = loaded.repos;
+        *groups.write() = loaded.groups;
+        *authors.write() = loaded.authors;
+        *assignees.write() = loaded.assignees;
+        *reviewers.write() = loaded.reviewers;
+        *rules.write() = loaded.rules;
+        *routed_labels.write() = loaded.routed_labels;
+        *monorepo.write() = loaded.monorepo;
+        *path_prefixes.write() = loaded.path_prefixes;
+        *freeze_windows.write() = loaded.freeze_windows;
+        *gitlab_freeze_periods.write() = loaded.gitlab_freeze_periods;
+        github.write().url = loaded.github_url;
+        *github_repos.write() = loaded.github_repos;
+        *team.write() = loaded.team;
+        *on_call_calendar.write() = loaded.on_call_calendar;
+        *release_branches.write() = loaded.release_branches;
+        *backport_label.write() = loaded.backport_label;
+        *incident_url_template.write() = loaded.incident_url_template;
+        *jira_url.write() = loaded.jira_url;
+        *poll_jitter.write() = loaded.poll_jitter;
+        *custom_field.write() = loaded.custom_field;
+        *log_level.write() = loaded.log_level;
+        *log_scope.write() = loaded.log_scope;
+        *query_inputs.write() = QueryInputs::of(&loaded.query);
+        *query.write() = loaded.query;
+    };
+    // Unsaved edits survive reloading the tab, restored once the first render is done so the
+    // draft is not overwritten by the defaults
+    let mut draft_restored = use_signal(|| false);
+    use_hook(move || {
+        spawn(async move {
+            if let Some(draft) = storage::load_session::<Draft>(DRAFT_STORAGE_KEY) {
+                apply_settings(draft.settings);
+                *query_inputs.write() = draft.inputs;
+            }
+            *draft_restored.write() = true;
+        })
+    });
+    use_effect(move || {
+        if !draft_restored() {
+            return;
+        }
+        let settings = settings();
+        if settings == saved() {
+            storage::remove_session(DRAFT_STORAGE_KEY);
+        } else {
+            let draft = Draft {
+                settings: Settings {
+                    headers: String::new(),
+                    ..settings
+                },
+                inputs: query_inputs(),
+            };
+            storage::save_session(DRAFT_STORAGE_KEY, &draft);
+        }
+    });
+
+    let domains = move || {
+        authors()
+            .split_whitespace()
+            .map(|x| MergeRequestsDomain::AuthorUsername(x.to_string()))
+            .chain(repos().split_whitespace().map(|x| MergeRequestsDomain::ProjectPath(x.to_string())))
+            .chain(groups().split_whitespace().map(|x| MergeRequestsDomain::GroupPath(x.to_string())))
+            .chain(assignees().split_whitespace().map(|x| MergeRequestsDomain::AssigneeUsername(x.to_string())))
+            .chain(reviewers().split_whitespace().map(|x| MergeRequestsDomain::ReviewerUsername(x.to_string())))
+            .chain(routed_labels().split_whitespace().map(|x| MergeRequestsDomain::Label(x.to_string())))
+            .collect::<Vec<_>>()
+    };
 
     // Outputs
-    let mut merge_requests_result = use_signal(|| Ok::<_, String>(Vec::new()));
+    let mut merge_requests_result = use_signal(|| Ok::<_, String>(Vec::<MergeRequest>::new()));
+    let toasts = use_signal(Vec::<toasts::Toast>::new);
+    let mut broadcast_messages = use_signal(Vec::<BroadcastMessage>::new);
+    let mut sound = use_signal(|| storage::load::<bool>(alerts::SOUND_STORAGE_KEY).unwrap_or_default());
+    let Pinned(mut pinned) = use_context_provider(|| {
+        Pinned(Signal::new(storage::load::<Vec<String>>(PINNED_STORAGE_KEY).unwrap_or_default()))
+    });
+    use_context_provider(|| Selected(Signal::new(HashSet::new())));
 
     rsx! {
+        toasts::Toasts { toasts }
         div { class: "max-w-screen-lg mx-auto mt-1",
+            for message in broadcast_messages().into_iter().filter(|m| m.is_active(Utc::now())) {
+                div {
+                    key: "{message.id}",
+                    class: "flex flex-row items-center font-ariel text-xs rounded-sm px-1 mb-1 bg-amber-100 text-amber-800",
+                    Icon { width: 12, height: 12, class: "mr-1", icon: FaTriangleExclamation }
+                    span { "{message.message}" }
+                }
+            }
+            decode_errors::DecodeFailures {}
+            outbox::QueuedActions { toasts }
             div { class: "flex flex-row justify-between",
                 div { class: "flex flex-row items-center",
                     h1 { class: "font-ariel text-2xl mr-1", "Lab Bench" }
@@ -66,43 +359,172 @@ fn App() -> Element {
                             Icon { width: 18, height: 18, icon: FaCaretRight }
                         }
                     }
+                    for v in View::iter() {
+                        span {
+                            class: if view() == v { "font-ariel text-sm ml-2 underline" } else { "font-ariel text-sm ml-2 cursor-pointer" },
+                            onclick: move |_event| {
+                                usage::record("view", v);
+                                *view.write() = v;
+                            },
+                            "{v}"
+                        }
+                    }
                 }
                 div { class: "flex flex-row items-center",
-                    if let Ok(r) = merge_requests_result() {
-                        span { class: "font-ariel text-lg mr-1", "{r.len()}" }
-                    }
-                    button {
-                        class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
-                        prevent_default: "onclick",
-                        onclick: move |_event| {
-                            spawn(async move {
-                                let mut domains = author_domains();
-                                domains.append(&mut project_domains().clone());
-                                *merge_requests_result
-                                    .write() = fetch_merge_requests(
-                                        &gitlab_url(),
-                                        &private_token(),
-                                        &query(),
-                                        &domains,
-                                    )
-                                    .await
-                                    .map_err(|e| e.to_string());
-                                if let Ok(merge_requests) = merge_requests_result() {
+                    search::GlobalSearch {}
+                    div { class: if view() == View::MergeRequests { "flex flex-row items-center" } else { "hidden" },
+                        if let Ok(r) = merge_requests_result() {
+                            span { class: "font-ariel text-lg mr-1", "{r.len()}" }
+                        }
+                        select {
+                            class: "block p-1 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                            onchange: move |event| {
+                                if let Some(l) = Layout::iter().find(|l| l.to_string() == event.value()) {
+                                    usage::record("layout", l);
+                                    *layout.write() = l;
+                                }
+                            },
+                            for l in usage::ranked("layout", Layout::iter(), Layout::to_string) {
+                                option { value: "{l}", selected: layout() == l, "{l}" }
+                            }
+                        }
+                        label {
+                            class: "flex flex-row items-center font-ariel text-xs mr-1",
+                            title: "beep when a pipeline fails on a pinned merge request or one targeting a protected branch",
+                            input {
+                                r#type: "checkbox",
+                                class: "mr-1",
+                                checked: sound(),
+                                onchange: move |event| {
+                                    *sound.write() = event.checked();
+                                    storage::save(alerts::SOUND_STORAGE_KEY, &sound());
+                                }
+                            }
+                            "Sound"
+                        }
+                        button {
+                            class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
+                            prevent_default: "onclick",
+                            onclick: move |_event| {
+                                spawn(async move {
+                                    let previous = merge_requests_result().unwrap_or_default();
+                                    let domains = domains();
+                                    // Fetched first so maintenance is announced while a slow query is running
+                                    match fetch_broadcast_messages(&instance()).await {
+                                        Ok(messages) => *broadcast_messages.write() = messages,
+                                        Err(e) => error!("failed fetching broadcast messages: {e}"),
+                                    }
                                     *merge_requests_result
-                                        .write() = fetch_merge_requests_with_full_data(
-                                            &gitlab_url(),
-                                            &private_token(),
+                                        .write() = instance()
+                                        .fetch_review_items(&query(), &domains)
+                                        .await
+                                        .map_err(|e| e.to_string());
+                                    // The simple view is meant to be fast and GraphQL already has most of the full data
+                                    let skip_full_data = query.read().simple_view || query.read().backend == Backend::Graphql;
+                                    if let (Ok(merge_requests), false) = (merge_requests_result(), skip_full_data) {
+                                        *merge_requests_result
+                                            .write() = fetch_merge_requests_with_full_data(
+                                                &instance(),
+                                                &merge_requests,
+                                            )
+                                            .await
+                                            .map_err(|e| e.to_string());
+                                    }
+                                    let github_domains = github_repos()
+                                        .split_whitespace()
+                                        .map(|x| MergeRequestsDomain::ProjectPath(x.to_string()))
+                                        .collect::<Vec<_>>();
+                                    if let (Ok(mut merge_requests), false) = (merge_requests_result(), github_domains.is_empty()) {
+                                        // The GitLab merge requests are still shown when GitHub fails
+                                        match github().fetch_review_items(&query(), &github_domains).await {
+                                            Ok(pull_requests) => {
+                                                merge_requests.extend(pull_requests);
+                                                *merge_requests_result.write() = Ok(merge_requests);
+                                            }
+                                            Err(e) => toasts::show(toasts, vec![format!("failed fetching GitHub pull requests: {e}")]),
+                                        }
+                                    }
+                                    let path_prefixes = path_prefixes();
+                                    let path_prefixes = path_prefixes.split_whitespace().collect::<Vec<_>>();
+                                    if let (Ok(merge_requests), false) = (merge_requests_result(), path_prefixes.is_empty()) {
+                                        *merge_requests_result.write() = Ok(
+                                            paths::filter_by_paths(&instance(), monorepo().trim(), &path_prefixes, merge_requests).await,
+                                        );
+                                    }
+                                    if let (Ok(merge_requests), true) = (merge_requests_result(), gitlab_freeze_periods()) {
+                                        let mut project_paths = merge_requests.iter().map(|mr| mr.project_path()).collect::<Vec<_>>();
+                                        project_paths.sort_unstable();
+                                        project_paths.dedup();
+                                        let mut gitlab_freezes = freezes.gitlab;
+                                        *gitlab_freezes.write() = freeze::fetch_windows(&instance(), &project_paths).await;
+                                    }
+                                    let on_call_calendar = on_call_calendar();
+                                    if !on_call_calendar.trim().is_empty() {
+                                        match oncall::fetch_shifts(on_call_calendar.trim()).await {
+                                            Ok(fetched) => *shifts.write() = fetched,
+                                            Err(e) => error!("failed fetching the on-call schedule: {e}"),
+                                        }
+                                    }
+                                    if let Ok(merge_requests) = merge_requests_result() {
+                                        toasts::show(toasts, toasts::transitions(&previous, &merge_requests));
+                                        let outcome = rules::evaluate(
+                                            &rules::parse(&rules()).unwrap_or_default(),
+                                            &previous,
                                             &merge_requests,
-                                        )
+                                        );
+                                        toasts::show(toasts, outcome.messages);
+                                        if !outcome.pin.is_empty() {
+                                            let mut pinned = pinned.write();
+                                            for reference in outcome.pin {
+                                                if !pinned.contains(&reference) {
+                                                    pinned.push(reference);
+                                                }
+                                            }
+                                            storage::save(PINNED_STORAGE_KEY, &*pinned);
+                                        }
+                                        if outcome.sound
+                                            || (sound()
+                                                && alerts::critical_failure(&instance(), &previous, &merge_requests, &pinned()).await)
+                                        {
+                                            alerts::play_alert();
+                                        }
+                                    }
+                                });
+                            },
+                            "Query"
+                        }
+                        button {
+                            class: "px-4 py-1 ml-1 border rounded-sm border-gray-300 bg-gray-100",
+                            title: "open merge requests you are a reviewer of and have not approved yet",
+                            prevent_default: "onclick",
+                            onclick: move |_event| {
+                                spawn(async move {
+                                    *merge_requests_result.write() = fetch_merge_requests_to_review(&instance(), &query())
                                         .await
                                         .map_err(|e| e.to_string());
-                                }
-                            });
-                        },
-                        "Query"
+                                });
+                            },
+                            "To Review"
+                        }
                     }
                 }
             }
+            if setup_needed() {
+                wizard::SetupWizard {
+                    gitlab_url: instance.read().url.clone(),
+                    on_finish: move |setup: Option<wizard::Setup>| {
+                        if let Some(setup) = setup {
+                            instance.write().url = setup.gitlab_url;
+                            instance.write().private_token = setup.private_token;
+                            *repos.write() = setup.repos;
+                            *groups.write() = setup.groups;
+                            query.write().scope = setup.scope;
+                            check_token();
+                        }
+                        *setup_needed.write() = false;
+                    },
+                }
+            }
             // Query builder
             // TODO: format this nicely
             div { class: "flex flex-col",
@@ -112,135 +534,709 @@ fn App() -> Element {
                         input {
                             r#type: "text",
                             class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            value: initial_gitlab_url,
+                            value: instance.read().url.clone(),
                             oninput: move |event| {
-                                *gitlab_url.write() = event.value();
+                                instance.write().url = event.value();
                             }
                         }
-                        label { class: "block", "Private Token" }
-                        input {
-                            r#type: "password",
+                        label { class: "block", title: "a session uses the browser's GitLab login, only when served from the instance's domain", "Auth" }
+                        select {
                             class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            value: initial_private_token,
-                            oninput: move |event| {
-                                *private_token.write() = event.value();
+                            onchange: move |event| {
+                                let auth = Auth::iter().find(|a| a.to_string() == event.value()).unwrap_or_default();
+                                instance.write().auth = auth;
+                                *identity.write() = None;
+                                if auth == Auth::Session {
+                                    spawn(async move {
+                                        *identity.write() = Some(
+                                            detect_session(&instance())
+                                                .await
+                                                .map_err(|e| format!("no usable session: {e}")),
+                                        );
+                                    });
+                                }
+                            },
+                            for a in Auth::iter() {
+                                option { value: "{a}", selected: instance.read().auth == a, "{a}" }
                             }
                         }
-                    }
-                    div { class: "flex flex-row",
-
-                        label { class: "block", "Start" }
-                        input {
-                            r#type: "text",
-                            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            oninput: move |_event| { todo!() }
-                        }
-                        label { class: "block", "End" }
-                        input {
-                            r#type: "text",
-                            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            oninput: move |_event| { todo!() }
+                        if instance.read().auth == Auth::Token {
+                            label { class: "block", "Private Token" }
+                            input {
+                                r#type: "password",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: instance.read().private_token.clone(),
+                                oninput: move |event| {
+                                    instance.write().private_token = event.value();
+                                },
+                                onchange: move |_event| check_token()
+                            }
                         }
-                    }
-                    div { class: "flex flex-row",
-                        label { class: "block", "Repos" }
-                        input {
-                            r#type: "text",
+                        identity::Identity { identity: identity() }
+                        label { class: "block", "Headers" }
+                        textarea {
                             class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                            rows: 1,
+                            placeholder: "Name: value",
+                            value: headers(),
                             oninput: move |event| {
-                                *project_domains.write() = event.value().split_whitespace().map(|x| MergeRequestsDomain::ProjectPath(x.to_string())).collect();
+                                instance.write().headers = Instance::parse_headers(&event.value());
+                                *headers.write() = event.value();
                             }
                         }
-                        label { class: "block", "Authors" }
+                        label { class: "block", title: "sent as the prefix of the X-Request-Id header to find the requests in the instance logs", "Request Tag" }
                         input {
                             r#type: "text",
                             class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                            value: instance.read().request_tag.clone().unwrap_or_default(),
                             oninput: move |event| {
-                                *author_domains.write() = event.value().split_whitespace().map(|x| MergeRequestsDomain::AuthorUsername(x.to_string())).collect();
+                                let request_tag = event.value().trim().to_string();
+                                instance.write().request_tag = (!request_tag.is_empty()).then_some(request_tag);
                             }
                         }
-                    }
-                    div { class: "flex flex-row",
-                        label { class: "block", "Sort" }
-                        select {
-                            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            onchange: move |event| {
-                                (*query.write()).sort = serde_json::from_str(&event.value()).unwrap();
+                        settings::SettingsSync {
+                            instance: instance(),
+                            settings: settings(),
+                            on_load: move |loaded: Settings| {
+                                *saved.write() = loaded.clone();
+                                apply_settings(loaded);
                             },
-                            for x in api::Sort::iter() {
-                                option {
-                                    value: serde_json::to_string(&x).unwrap(),
-                                    {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                            on_save: move |settings| *saved.write() = settings,
+                            on_unlock: move |private_token| {
+                                instance.write().private_token = private_token;
+                                check_token();
+                            },
+                        }
+                    }
+                    div { class: if view() == View::MergeRequests { "flex flex-col" } else { "hidden" },
+                        div { class: "flex flex-row",
+                            label { class: "block", "Start" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query_inputs.read().created_after.clone(),
+                                oninput: move |event| {
+                                    query.write().created_after = event.value().parse().ok();
+                                    query_inputs.write().created_after = event.value();
+                                }
+                            }
+                            label { class: "block", "End" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query_inputs.read().created_before.clone(),
+                                oninput: move |event| {
+                                    query.write().created_before = event.value().parse().ok();
+                                    query_inputs.write().created_before = event.value();
+                                }
+                            }
+                            label { class: "block", "Source Branch" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().source_branch.clone().unwrap_or_default(),
+                                oninput: move |event| {
+                                    let source_branch = event.value().trim().to_string();
+                                    query.write().source_branch = (!source_branch.is_empty()).then_some(source_branch);
                                 }
                             }
                         }
-                        label { class: "block", "Order By" }
-                        select {
-                            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            onchange: move |event| {
-                                (*query.write()).order_by = serde_json::from_str(&event.value()).unwrap();
-                            },
-                            for x in api::OrderBy::iter() {
-                                option {
-                                    value: serde_json::to_string(&x).unwrap(),
-                                    {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                        div { class: "flex flex-row",
+                            label { class: "block", title: "only merge requests deployed to this environment", "Environment" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                placeholder: "production",
+                                value: query.read().environment.clone().unwrap_or_default(),
+                                oninput: move |event| {
+                                    let environment = event.value().trim().to_string();
+                                    query.write().environment = (!environment.is_empty()).then_some(environment);
+                                }
+                            }
+                            label { class: "block", "Deployed After" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query_inputs.read().deployed_after.clone(),
+                                oninput: move |event| {
+                                    query.write().deployed_after = event.value().parse().ok();
+                                    query_inputs.write().deployed_after = event.value();
+                                }
+                            }
+                            label { class: "block", "Deployed Before" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query_inputs.read().deployed_before.clone(),
+                                oninput: move |event| {
+                                    query.write().deployed_before = event.value().parse().ok();
+                                    query_inputs.write().deployed_before = event.value();
                                 }
                             }
                         }
-                        label { class: "block", "Scope" }
-                        select {
-                            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            onchange: move |event| {
-                                (*query.write()).scope = serde_json::from_str(&event.value()).unwrap();
-                            },
-                            for x in api::Scope::iter() {
-                                option {
-                                    value: serde_json::to_string(&x).unwrap(),
-                                    {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                        div { class: "flex flex-row",
+                            label { class: "block", title: "REST API root of the GitHub instance", "GitHub" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: github.read().url.clone(),
+                                oninput: move |event| {
+                                    github.write().url = event.value();
+                                }
+                            }
+                            label { class: "block", title: "not saved with the settings", "GitHub Token" }
+                            input {
+                                r#type: "password",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: github.read().token.clone(),
+                                oninput: move |event| {
+                                    github.write().token = event.value();
+                                }
+                            }
+                            label { class: "block", title: "whitespace separated `owner/repo` whose pull requests are shown alongside the merge requests", "GitHub Repos" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: github_repos(),
+                                oninput: move |event| {
+                                    *github_repos.write() = event.value();
                                 }
                             }
                         }
-                        label { class: "block", "State" }
-                        select {
-                            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            onchange: move |event| {
-                                (*query.write()).state = serde_json::from_str(&event.value()).ok();
-                            },
-                            option {
-                                value: "",
-                                ""
-                            },
-                            for x in api::State::iter() {
-                                option {
-                                    value: serde_json::to_string(&x).unwrap(),
-                                    {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                        div { class: "flex flex-row",
+                            label { class: "block", title: "project whose merge requests are filtered by the paths they change", "Monorepo" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: monorepo(),
+                                oninput: move |event| {
+                                    *monorepo.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "only show monorepo merge requests changing files under these whitespace separated prefixes", "Paths" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                placeholder: "services/auth/",
+                                value: path_prefixes(),
+                                oninput: move |event| {
+                                    *path_prefixes.write() = event.value();
                                 }
                             }
                         }
-                        label { class: "block", "Wip" }
-                        select {
-                            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
-                            onchange: move |event| {
-                                (*query.write()).wip = serde_json::from_str(&event.value()).ok();
-                            },
-                            option {
-                                value: "",
-                                ""
-                            },
-                            for x in api::Wip::iter() {
+                        div { class: "flex flex-row",
+                            label { class: "block", "Repos" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: repos(),
+                                oninput: move |event| {
+                                    *repos.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "only fetch these merge request numbers of the repos", "IIDs" }
+                            input {
+                                r#type: "text",
+                                class: "block w-24 p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                placeholder: "!12 !34",
+                                value: query_inputs.read().iids.clone(),
+                                oninput: move |event| {
+                                    query.write().iids = event
+                                        .value()
+                                        .split(|c: char| c.is_whitespace() || c == ',')
+                                        .filter_map(|iid| iid.trim_start_matches('!').parse().ok())
+                                        .collect();
+                                    query_inputs.write().iids = event.value();
+                                }
+                            }
+                            button {
+                                r#type: "button",
+                                class: "px-2 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                                title: "add your starred projects to the repos",
+                                onclick: move |_event| {
+                                    spawn(async move {
+                                        match fetch_starred_projects(&instance()).await {
+                                            Ok(projects) => {
+                                                let mut repos = repos.write();
+                                                for project in projects {
+                                                    if !repos.split_whitespace().any(|r| r == project.path_with_namespace) {
+                                                        repos.push(' ');
+                                                        repos.push_str(&project.path_with_namespace);
+                                                    }
+                                                }
+                                                *repos = repos.trim().to_string();
+                                            }
+                                            Err(e) => toasts::show(toasts, vec![e.to_string()]),
+                                        }
+                                    });
+                                },
+                                "Import Starred"
+                            }
+                            label { class: "block", "Groups" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: groups(),
+                                oninput: move |event| {
+                                    *groups.write() = event.value();
+                                }
+                            }
+                            label { class: "flex flex-row items-center", title: "leave out the merge requests of archived projects of the groups",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "mr-1",
+                                    checked: query.read().non_archived,
+                                    onchange: move |event| query.write().non_archived = event.checked(),
+                                }
+                                "Non Archived"
+                            }
+                            label { class: "block", "Authors" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: authors(),
+                                oninput: move |event| {
+                                    *authors.write() = event.value();
+                                }
+                            }
+                        }
+                        div { class: "flex flex-row",
+                            label { class: "block", "Assignees" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: assignees(),
+                                oninput: move |event| {
+                                    *assignees.write() = event.value();
+                                }
+                            }
+                            label { class: "block", "Reviewers" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: reviewers(),
+                                oninput: move |event| {
+                                    *reviewers.write() = event.value();
+                                }
+                            }
+                            label {
+                                class: "block",
+                                title: "merge requests with these labels are always fetched and shown in their own section",
+                                "Routed Labels"
+                            }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: routed_labels(),
+                                oninput: move |event| {
+                                    *routed_labels.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "run the query as this user, requires an admin token", "Sudo" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().sudo.clone().unwrap_or_default(),
+                                oninput: move |event| {
+                                    let sudo = event.value().trim().to_string();
+                                    query.write().sudo = (!sudo.is_empty()).then_some(sudo);
+                                }
+                            }
+                            label { class: "flex flex-row items-center", title: "only fetch titles and states, much faster across many projects",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "mr-1",
+                                    checked: query.read().simple_view,
+                                    onchange: move |event| query.write().simple_view = event.checked(),
+                                }
+                                "Simple View"
+                            }
+                            label { class: "block", title: "graphql fetches projects and groups in one query each, without diffs, reports, and status checks, and can not filter by scope, archived projects, or sudo", "Backend" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                onchange: move |event| {
+                                    query.write().backend = Backend::iter().find(|b| b.to_string() == event.value()).unwrap_or_default();
+                                },
+                                for b in Backend::iter() {
+                                    option { value: "{b}", selected: query.read().backend == b, "{b}" }
+                                }
+                            }
+                            label { class: "block", title: "keyset pagination stays fast on large instances but GitLab only supports it for some orderings", "Pagination" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                onchange: move |event| {
+                                    query.write().pagination = Pagination::iter().find(|p| p.to_string() == event.value()).unwrap_or_default();
+                                },
+                                for p in Pagination::iter() {
+                                    option { value: "{p}", selected: query.read().pagination == p, "{p}" }
+                                }
+                            }
+                            label { class: "block", title: "merge requests fetched per request, at most 100", "Per Page" }
+                            input {
+                                r#type: "number",
+                                min: "1",
+                                max: "100",
+                                class: "block w-16 p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().per_page.map(|n| n.to_string()).unwrap_or_default(),
+                                oninput: move |event| {
+                                    query.write().per_page = event.value().trim().parse().ok();
+                                }
+                            }
+                            label { class: "block", title: "stop fetching after this many merge requests", "Max" }
+                            input {
+                                r#type: "number",
+                                min: "1",
+                                class: "block w-16 p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().max_results.map(|n| n.to_string()).unwrap_or_default(),
+                                oninput: move |event| {
+                                    query.write().max_results = event.value().trim().parse().ok();
+                                }
+                            }
+                        }
+                        div { class: "flex flex-row",
+                            label {
+                                class: "block",
+                                title: "one per line, e.g. `when pipeline fails and target_branch == main then notify + pin`",
+                                "Rules"
+                            }
+                            textarea {
+                                class: "block w-full p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: rules(),
+                                oninput: move |event| {
+                                    *rules.write() = event.value();
+                                }
+                            }
+                        }
+                        if let Err(e) = rules::parse(&rules()) {
+                            span { class: "font-ariel text-xs text-red-700", "{e}" }
+                        }
+                        div { class: "flex flex-row",
+                            label {
+                                class: "block",
+                                title: "one per line, e.g. `0 18 * * 5 .. 0 8 * * 1 in Europe/Berlin on main`",
+                                "Freezes"
+                            }
+                            textarea {
+                                class: "block w-full p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: freeze_windows(),
+                                oninput: move |event| {
+                                    *freeze_windows.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "also read the deploy freeze periods of the fetched projects from GitLab", "GitLab Freezes" }
+                            input {
+                                r#type: "checkbox",
+                                checked: gitlab_freeze_periods(),
+                                onchange: move |event| {
+                                    *gitlab_freeze_periods.write() = event.checked();
+                                }
+                            }
+                        }
+                        if let Err(e) = freeze::parse(&freeze_windows()) {
+                            span { class: "font-ariel text-xs text-red-700", "{e}" }
+                        }
+                        div { class: "flex flex-row",
+                            label {
+                                class: "block",
+                                title: "working hours of reviewers, one per line, e.g. `alice Europe/Berlin 8-16`",
+                                "Team"
+                            }
+                            textarea {
+                                class: "block w-full p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: team(),
+                                oninput: move |event| {
+                                    *team.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "iCalendar URL of the on-call schedule, e.g. a PagerDuty or Opsgenie export", "On-Call" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: on_call_calendar(),
+                                oninput: move |event| {
+                                    *on_call_calendar.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "URL of the incident named by an `incident::<id>` label, `{{id}}` is replaced by the id", "Incidents" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                placeholder: "https://tracker.example.com/{{id}}",
+                                value: incident_url_template(),
+                                oninput: move |event| {
+                                    *incident_url_template.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "base URL of the Jira instance issue keys like ABC-1234 in merge requests link to", "Jira" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                placeholder: "https://example.atlassian.net",
+                                value: jira_url(),
+                                oninput: move |event| {
+                                    *jira_url.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "how much periodic refreshes vary their interval, in percent either way", "Jitter" }
+                            input {
+                                r#type: "number",
+                                min: "0",
+                                max: "100",
+                                class: "block w-16 p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: "{poll_jitter}",
+                                oninput: move |event| {
+                                    if let Ok(jitter) = event.value().trim().parse() {
+                                        *poll_jitter.write() = jitter;
+                                    }
+                                }
+                            }
+                            label { class: "block", title: "an extra field on the rows, each `{{path}}` is replaced by the value at the dot separated path in the JSON of the merge request, not available with the graphql backend", "Custom Field" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                placeholder: "{{milestone.title}}",
+                                value: custom_field(),
+                                oninput: move |event| {
+                                    *custom_field.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "the least severe events to log, errors are always logged", "Log Level" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                onchange: move |event| {
+                                    *log_level.write() = logs::LogLevel::iter().find(|l| l.to_string() == event.value()).unwrap_or_default();
+                                },
+                                for l in logs::LogLevel::iter() {
+                                    option { value: "{l}", selected: log_level() == l, "{l}" }
+                                }
+                            }
+                            label { class: "block", title: "log requests to GitLab and GitHub, the rest of the app, or everything including dependencies", "Log Scope" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                onchange: move |event| {
+                                    *log_scope.write() = logs::LogScope::iter().find(|l| l.to_string() == event.value()).unwrap_or_default();
+                                },
+                                for l in logs::LogScope::iter() {
+                                    option { value: "{l}", selected: log_scope() == l, "{l}" }
+                                }
+                            }
+                            span {
+                                class: "font-ariel text-xs underline cursor-pointer ml-1",
+                                title: "save the recent log lines as a text file to attach to bug reports",
+                                onclick: move |_event| logs::download(),
+                                "download logs"
+                            }
+                        }
+                        if let Err(e) = working_hours::parse(&team()) {
+                            span { class: "font-ariel text-xs text-red-700", "{e}" }
+                        }
+                        div { class: "flex flex-row",
+                            label {
+                                class: "block",
+                                title: "release branches to compare with the branch they were cut from, one per line, e.g. `group/app main release/1.2`",
+                                "Release Branches"
+                            }
+                            textarea {
+                                class: "block w-full p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: release_branches(),
+                                oninput: move |event| {
+                                    *release_branches.write() = event.value();
+                                }
+                            }
+                            label { class: "block", title: "merge requests into the base branch with this label are missing until they are picked onto the release branch", "Backport Label" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: backport_label(),
+                                oninput: move |event| {
+                                    *backport_label.write() = event.value();
+                                }
+                            }
+                        }
+                        if let Err(e) = divergence::parse(&release_branches()) {
+                            span { class: "font-ariel text-xs text-red-700", "{e}" }
+                        }
+                        div { class: "flex flex-row",
+                            label { class: "block", title: "exclude merge requests with any of these comma separated labels", "Not Labels" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().not_labels.clone().unwrap_or_default(),
+                                oninput: move |event| {
+                                    let not_labels = event.value().trim().to_string();
+                                    query.write().not_labels = (!not_labels.is_empty()).then_some(not_labels);
+                                }
+                            }
+                            label { class: "block", title: "exclude merge requests by this username", "Not Author" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().not_author_username.clone().unwrap_or_default(),
+                                oninput: move |event| {
+                                    let not_author_username = event.value().trim().to_string();
+                                    query.write().not_author_username = (!not_author_username.is_empty()).then_some(not_author_username);
+                                }
+                            }
+                            label { class: "block", title: "exclude merge requests in this milestone", "Not Milestone" }
+                            input {
+                                r#type: "text",
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().not_milestone.clone().unwrap_or_default(),
+                                oninput: move |event| {
+                                    let not_milestone = event.value().trim().to_string();
+                                    query.write().not_milestone = (!not_milestone.is_empty()).then_some(not_milestone);
+                                }
+                            }
+                        }
+                        div { class: "flex flex-row",
+                            label { class: "block", "Sort" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: serde_json::to_string(&query.read().sort).unwrap(),
+                                onchange: move |event| {
+                                    usage::record("sort", remove_first_and_last_chars(&event.value()));
+                                    query.write().sort = serde_json::from_str(&event.value()).unwrap();
+                                },
+                                for x in usage::ranked("sort", api::Sort::iter(), |x| remove_first_and_last_chars(&serde_json::to_string(x).unwrap()).to_string()) {
+                                    option {
+                                        value: serde_json::to_string(&x).unwrap(),
+                                        {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                                    }
+                                }
+                            }
+                            label { class: "block", "Order By" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: serde_json::to_string(&query.read().order_by).unwrap(),
+                                onchange: move |event| {
+                                    usage::record("order by", remove_first_and_last_chars(&event.value()));
+                                    query.write().order_by = serde_json::from_str(&event.value()).unwrap();
+                                },
+                                for x in usage::ranked("order by", api::OrderBy::iter(), |x| remove_first_and_last_chars(&serde_json::to_string(x).unwrap()).to_string()) {
+                                    option {
+                                        value: serde_json::to_string(&x).unwrap(),
+                                        {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                                    }
+                                }
+                            }
+                            label { class: "block", "Scope" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: serde_json::to_string(&query.read().scope).unwrap(),
+                                onchange: move |event| {
+                                    usage::record("scope", remove_first_and_last_chars(&event.value()));
+                                    query.write().scope = serde_json::from_str(&event.value()).unwrap();
+                                },
+                                for x in usage::ranked("scope", api::Scope::iter(), |x| remove_first_and_last_chars(&serde_json::to_string(x).unwrap()).to_string()) {
+                                    option {
+                                        value: serde_json::to_string(&x).unwrap(),
+                                        {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                                    }
+                                }
+                            }
+                            label { class: "block", "State" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().state.as_ref().map(|x| serde_json::to_string(x).unwrap()).unwrap_or_default(),
+                                onchange: move |event| {
+                                    query.write().state = serde_json::from_str(&event.value()).ok();
+                                },
                                 option {
-                                    value: serde_json::to_string(&x).unwrap(),
-                                    {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                                    value: "",
+                                    ""
+                                },
+                                for x in api::State::iter() {
+                                    option {
+                                        value: serde_json::to_string(&x).unwrap(),
+                                        {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                                    }
+                                }
+                            }
+                            label { class: "block", "Wip" }
+                            select {
+                                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                                value: query.read().wip.as_ref().map(|x| serde_json::to_string(x).unwrap()).unwrap_or_default(),
+                                onchange: move |event| {
+                                    query.write().wip = serde_json::from_str(&event.value()).ok();
+                                },
+                                option {
+                                    value: "",
+                                    ""
+                                },
+                                for x in api::Wip::iter() {
+                                    option {
+                                        value: serde_json::to_string(&x).unwrap(),
+                                        {remove_first_and_last_chars(&serde_json::to_string(&x).unwrap())}
+                                    }
                                 }
                             }
                         }
                     }
                 }
             }
-            // MR list
-            match merge_requests_result.read().clone(){
-                Ok(merge_request_list) =>  rsx!(MergeRequestList { merge_request_list }),
-                Err(e) => rsx!(span {"{e}"}),
+            match view() {
+                // MR list
+                View::MergeRequests => match merge_requests_result.read().clone() {
+                    Ok(merge_request_list) => {
+                        let routed_labels = routed_labels().split_whitespace().map(str::to_string).collect::<Vec<_>>();
+                        let (routed_merge_request_list, merge_request_list): (Vec<_>, Vec<_>) = merge_request_list
+                            .into_iter()
+                            .partition(|mr| mr.labels.iter().any(|l| routed_labels.contains(&l.name)));
+                        rsx! {
+                            rebase::BatchRebase {
+                                merge_request_list: routed_merge_request_list.iter().chain(&merge_request_list).cloned().collect::<Vec<_>>(),
+                            }
+                            merge_order::MergeAssistant {
+                                merge_request_list: routed_merge_request_list.iter().chain(&merge_request_list).cloned().collect::<Vec<_>>(),
+                            }
+                            if !routed_merge_request_list.is_empty() {
+                                div { class: "border-b-2",
+                                    h2 { class: "font-ariel text-sm", "Labeled {routed_labels.join(\", \")}" }
+                                    MergeRequestList { merge_request_list: routed_merge_request_list }
+                                }
+                            }
+                            match layout() {
+                                Layout::List => rsx!(MergeRequestList { merge_request_list }),
+                                Layout::Board => rsx!(board::Board { merge_request_list }),
+                                Layout::Calendar => rsx!(calendar::Calendar { merge_request_list }),
+                                Layout::Timeline => rsx!(timeline::Timeline { merge_request_list }),
+                                Layout::ByAuthor => rsx!(authors::ByAuthor { merge_request_list }),
+                                Layout::ByMilestone => rsx!(milestones::ByMilestone { merge_request_list }),
+                            }
+                        }
+                    }
+                    Err(e) => rsx!(span {"{e}"}),
+                },
+                View::Releases => rsx!(releases::Releases {
+                    instance: instance(),
+                }),
+                View::Changelog => rsx!(changelog::Changelog {
+                    instance: instance(),
+                }),
+                View::Branches => rsx!(branches::Branches {
+                    instance: instance(),
+                }),
+                View::Recent => rsx!(recent::RecentlyViewed {}),
+                View::MyAreas => rsx!(codeowners::MyAreas {
+                    instance: instance(),
+                    merge_request_list: merge_requests_result().unwrap_or_default(),
+                }),
+                View::Hotfixes => rsx!(hotfix::Hotfixes {
+                    instance: instance(),
+                    query: query(),
+                    domains: domains(),
+                    toasts,
+                }),
+                View::ReleaseDivergence => rsx!(divergence::ReleaseDivergence {
+                    instance: instance(),
+                    pairs: release_branches(),
+                    backport_label: backport_label(),
+                    toasts,
+                }),
+                View::TeamApprovals => rsx!(delegation::TeamApprovals {
+                    merge_request_list: merge_requests_result().unwrap_or_default(),
+                }),
+                View::Usage => rsx!(usage::YourUsage {}),
             }
         }
     }
@@ -252,11 +1248,88 @@ fn remove_first_and_last_chars(s: &str) -> &str {
 
 #[component]
 fn MergeRequestList(merge_request_list: Vec<MergeRequest>) -> Element {
+    // Merge request id to when it is snoozed until
+    let mut snoozed = use_signal(|| {
+        storage::load::<HashMap<i64, DateTime<Utc>>>(SNOOZED_STORAGE_KEY).unwrap_or_default()
+    });
+    let Pinned(mut pinned) = use_context::<Pinned>();
+    let mut dragging = use_signal(|| None::<String>);
+    let now = Utc::now();
+
+    let (pinned_merge_requests, merge_requests): (Vec<_>, Vec<_>) = merge_request_list
+        .into_iter()
+        .filter(|mr| snoozed.read().get(&mr.id).is_none_or(|until| *until < now))
+        .partition(|mr| pinned.read().contains(&mr.references.full));
+    let mut pinned_merge_requests = pinned_merge_requests;
+    pinned_merge_requests.sort_by_key(|mr| pinned.read().iter().position(|r| *r == mr.references.full));
+
+    let on_snooze = move |id| {
+        let mut snoozed = snoozed.write();
+        snoozed.retain(|_, until| *until > now);
+        snoozed.insert(id, now + SNOOZE_DURATION);
+        storage::save(SNOOZED_STORAGE_KEY, &*snoozed);
+    };
+    let on_pin = move |reference: String| {
+        let mut pinned = pinned.write();
+        match pinned.iter().position(|r| *r == reference) {
+            Some(i) => {
+                pinned.remove(i);
+            }
+            None => pinned.push(reference),
+        }
+        storage::save(PINNED_STORAGE_KEY, &*pinned);
+    };
+
     rsx!(
+        if !pinned_merge_requests.is_empty() {
+            ul { class: "list-none border-b-2",
+                for merge_request in pinned_merge_requests {
+                    li {
+                        key: "{merge_request.references.full}",
+                        class: "flex flex-col py-1 border-b cursor-move",
+                        draggable: "true",
+                        ondragstart: {
+                            let reference = merge_request.references.full.clone();
+                            move |_event| *dragging.write() = Some(reference.clone())
+                        },
+                        prevent_default: "ondragover ondrop",
+                        ondragover: move |_event| {},
+                        ondrop: {
+                            let target = merge_request.references.full.clone();
+                            move |_event| {
+                                let Some(dragged) = dragging.write().take() else {
+                                    return;
+                                };
+                                let mut pinned = pinned.write();
+                                pinned.retain(|r| *r != dragged);
+                                let i = pinned.iter().position(|r| *r == target).unwrap_or(pinned.len());
+                                pinned.insert(i, dragged);
+                                storage::save(PINNED_STORAGE_KEY, &*pinned);
+                            }
+                        },
+                        MergeRequest {
+                            pinned: true,
+                            on_snooze,
+                            on_pin,
+                            merge_request: merge_request.clone(),
+                        }
+                    }
+                }
+            }
+        }
         ul { class: "list-none",
-            for merge_request in merge_request_list {
-                li { key: "{merge_request.references.full}", class: "flex flex-col py-1 border-b",
-                    MergeRequest { merge_request }
+            for stacks::Stacked { merge_request, depth, stacked_on } in stacks::stacks(merge_requests) {
+                li {
+                    key: "{merge_request.references.full}",
+                    class: "flex flex-col py-1 border-b",
+                    style: "margin-left: {depth}rem;",
+                    MergeRequest {
+                        pinned: false,
+                        on_snooze,
+                        on_pin,
+                        merge_request,
+                        stacked_on,
+                    }
                 }
             }
         }
@@ -264,42 +1337,149 @@ fn MergeRequestList(merge_request_list: Vec<MergeRequest>) -> Element {
 }
 
 #[component]
-fn MergeRequest(merge_request: MergeRequest) -> Element {
-    use crate::api::{
-        MergeStatus::{self, *},
-        PipelineStatus::{self, *},
-        State::{self, *},
-    };
+fn MergeRequest(
+    merge_request: MergeRequest,
+    pinned: bool,
+    on_snooze: EventHandler<i64>,
+    on_pin: EventHandler<String>,
+    /// Short reference of the merge request this one is stacked on
+    stacked_on: Option<String>,
+) -> Element {
+    use crate::api::StatusCheckStatus;
 
     let MergeRequest {
+        assignees,
         author,
         created_at,
         detailed_merge_status,
         head_pipeline,
+        labels,
         merge_when_pipeline_succeeds,
+        milestone,
         references,
         reviewers,
+        reviewer_states,
+        security_report,
+        code_quality_report,
         source_branch,
+        status_checks,
+        changes_requested_at,
+        failed_jobs,
+        last_pushed_at,
+        latest_comment,
+        approved_sha,
+        approvals_required,
+        approvals_left,
+        approved_by,
+        approval_rules,
+        sha,
         state,
+        target_coverage,
+        time_stats,
         title,
+        unresolved_threads,
         updated_at,
         user_notes_count,
         web_url,
         ..
-    } = merge_request;
+    } = merge_request.clone();
+    let id = merge_request.id;
+    let reference = references.full.clone();
+    let file_types = files::file_types(&merge_request)
+        .into_iter()
+        .map(|(file_type, count)| format!("{file_type} {count}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let change_warnings = files::change_warnings(&merge_request).join(", ");
+    let diff_size = files::diff_size(&merge_request);
+    // Approvals only count towards the rules when given by eligible approvers
+    let approvals = match (approvals_required, approved_by.len()) {
+        (0, 0) => None,
+        (0, approved) => Some(format!("{approved} approvals")),
+        (required, _) => Some(format!("{}/{required} approvals", required - approvals_left)),
+    };
+    // Who still has to approve, listing a few of the eligible approvers of each unsatisfied rule
+    let unsatisfied_rules = approval_rules
+        .into_iter()
+        .filter(|rule| !rule.approved)
+        .map(|rule| {
+            let eligible = rule
+                .eligible_approvers
+                .into_iter()
+                .filter(|u| !rule.approved_by.iter().any(|a| a.id == u.id))
+                .collect::<Vec<_>>();
+            let more = eligible.len().saturating_sub(MAX_ELIGIBLE_APPROVERS);
+            let eligible = eligible.into_iter().take(MAX_ELIGIBLE_APPROVERS).collect::<Vec<_>>();
+            (rule.name, rule.rule_type, rule.approvals_required, eligible, more)
+        })
+        .collect::<Vec<_>>();
+    let approvers = approved_by
+        .iter()
+        .map(|u| u.username.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut menu_open = use_signal(|| false);
+    let Selected(mut selected) = use_context::<Selected>();
+    let frozen_until = use_context::<freeze::Freezes>()
+        .frozen_until(&merge_request, Utc::now())
+        .map(|until| until.format("%a %b %-d %H:%M UTC").to_string());
+    let team_members = use_context::<working_hours::Team>().members;
+    let team = team_members.read();
+    let oncall::OnCall(shifts) = use_context::<oncall::OnCall>();
+    let incident_url = use_context::<incidents::Incidents>().url(&merge_request);
 
     let head_pipeline: api::Pipeline = head_pipeline.unwrap_or_default();
     let pipeline_time_in_min = head_pipeline.duration.num_minutes();
     let pipeline_queued_time_in_min = head_pipeline.queued_duration.num_minutes();
+    let failed_jobs = failed_jobs.join(", ");
+    let coverage = head_pipeline.coverage.clone();
+    let coverage_title = match &target_coverage {
+        Some(target_coverage) => format!("coverage, {target_coverage}% on {}", merge_request.target_branch),
+        None => "coverage".to_string(),
+    };
+    let coverage_delta = coverage.as_deref().zip(target_coverage.as_deref()).and_then(|(coverage, target)| {
+        Some(coverage.parse::<f64>().ok()? - target.parse::<f64>().ok()?)
+    });
+    let pipeline_title = match failed_jobs.as_str() {
+        "" => format!("pipeline:{}", head_pipeline.status),
+        failed_jobs => format!("pipeline:{} failed: {failed_jobs}", head_pipeline.status),
+    };
 
     rsx!(
-        div { class: "flex flex-row justify-between",
+        div {
+            class: "flex flex-row justify-between",
+            prevent_default: "oncontextmenu",
+            oncontextmenu: move |_event| *menu_open.write() = true,
             // Left column
             div { class: "flex flex-col",
+                if let Some(incident_url) = incident_url {
+                    div { class: "flex flex-row items-center font-ariel text-xs rounded-sm px-1 mb-1 bg-red-100 text-red-700",
+                        Icon { width: 12, height: 12, class: "mr-1", icon: FaFire }
+                        span { class: "mr-1", "incident" }
+                        a { class: "underline", href: incident_url.clone(), "{incident_url}" }
+                    }
+                }
                 div { class: "flex flex-row items-center",
+                    input {
+                        r#type: "checkbox",
+                        class: "mr-1",
+                        title: "select for bulk actions",
+                        checked: selected.read().contains(&id),
+                        onchange: move |event| {
+                            if event.checked() {
+                                selected.write().insert(id);
+                            } else {
+                                selected.write().remove(&id);
+                            }
+                        },
+                    }
                     a {
                         class: "font-ariel text-sm mr-1",
                         href: web_url.as_ref(),
+                        onclick: {
+                            let merge_request = merge_request.clone();
+                            move |_event| recent::record(&merge_request)
+                        },
                         "{title}"
                     }
                     span {
@@ -311,6 +1491,33 @@ fn MergeRequest(merge_request: MergeRequest) -> Element {
                 }
                 div { class: "flex flex-row items-center",
                     span { class: "font-ariel text-xs mr-1", "{references.full}" }
+                    if let Some((additions, deletions, files)) = diff_size {
+                        span { class: "font-ariel text-xs rounded-full px-2 mr-1 bg-gray-100", title: "added and deleted lines",
+                            span { class: "text-green-700", "+{additions}" }
+                            span { class: "text-red-700 ml-1", "−{deletions}" }
+                            span { class: "ml-1", if files == 1 { "1 file" } else { "{files} files" } }
+                        }
+                    }
+                    if !file_types.is_empty() {
+                        span { class: "font-ariel text-xs rounded-full px-2 mr-1 bg-gray-100", title: "changed files by type",
+                            "{file_types}"
+                        }
+                    }
+                    if let Some(frozen_until) = frozen_until {
+                        span {
+                            class: "flex flex-row items-center font-ariel text-xs rounded-full px-2 mr-1 bg-blue-100",
+                            title: "merging into {merge_request.target_branch} is frozen until {frozen_until}",
+                            Icon { width: 12, height: 12, class: "mr-1", icon: FaSnowflake }
+                            "frozen until {frozen_until}"
+                        }
+                    }
+                    if let Some(stacked_on) = stacked_on {
+                        span {
+                            class: "font-ariel text-xs rounded-full px-2 mr-1 bg-gray-100",
+                            title: "targets the source branch of {stacked_on}",
+                            "stacked on {stacked_on}"
+                        }
+                    }
                     div { class: "font-ariel text-xs",
                         span { class: "mr-1", title: created_at.to_string(),
                             "created {time_ago(created_at)} by"
@@ -318,6 +1525,32 @@ fn MergeRequest(merge_request: MergeRequest) -> Element {
                         a { href: author.web_url, "{author.username}" }
                     }
                 }
+                if let Some(milestone) = milestone {
+                    div { class: "flex flex-row items-center font-ariel text-xs",
+                        span { class: "mr-1", "milestone" }
+                        a {
+                            class: "underline mr-1",
+                            href: milestone.web_url,
+                            title: milestone.due_date.map(|d| format!("due {d}")).unwrap_or_default(),
+                            "{milestone.title}"
+                        }
+                    }
+                }
+                if !labels.is_empty() {
+                    div { class: "flex flex-row flex-wrap items-center",
+                        for label in labels {
+                            span {
+                                class: "font-ariel text-xs rounded-full px-2 mr-1",
+                                style: "background-color: {label.color}; color: {label.text_color};",
+                                title: label.description.clone().unwrap_or_default(),
+                                "{label.name}"
+                            }
+                        }
+                    }
+                }
+                jira::JiraLinks { merge_request: merge_request.clone() }
+                decorations::Decorations { merge_request: merge_request.clone() }
+                custom_field::CustomFieldValue { merge_request: merge_request.clone() }
             }
             // Right column
             div { class: "flex flex-col",
@@ -327,45 +1560,22 @@ fn MergeRequest(merge_request: MergeRequest) -> Element {
                         class: "mr-1",
                         href: web_url,
                         title: "{state}:{detailed_merge_status}",
-                        match (merge_when_pipeline_succeeds, state, detailed_merge_status) {
-                            (_, _, MergeStatus::Unknown) | (_, State::Unknown, _) => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaCircleQuestion,
-                                fill: "#dd2b0e",
-                            }),
-                            (_, Closed | Locked, _) => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaBan,
-                                fill: "#dd2b0e",
-                            }),
-                            (_, Opened, BlockedStatus | DraftStatus | JiraAssociationMissing | NeedRebase | Conflict
-                            | DiscussionsNotResolved | NotApproved | RequestedChanges | Checking | Unchecked | CiMustPass
-                            | CiStillRunning | ExternalStatusChecks | NotOpen) => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaListCheck,
-                                fill: "#1f75cb",
-                            }),
-                            (true, Opened, Mergeable) => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaSpinner,
-                                fill: "#108548",
-                            }),
-                            (false, Opened, Mergeable) => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaCircleCheck,
-                                fill: "#108548",
-                            }),
-                            (_, Merged, _) => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaCodeMerge,
-                                fill: "#108548",
-                            }),
+                        status::StatusIconView {
+                            icon: status::merge_status_icon(merge_when_pipeline_succeeds, state, detailed_merge_status),
+                        }
+                    }
+                    if let Some(approvals) = approvals {
+                        span {
+                            class: if approvals_required > 0 && approvals_left == 0 { "font-ariel text-xs rounded-full px-2 mr-1 bg-green-100" } else { "font-ariel text-xs rounded-full px-2 mr-1 bg-gray-100" },
+                            title: "approved by {approvers}",
+                            "{approvals}"
+                        }
+                    }
+                    if approved_sha.is_some() && approved_sha != sha {
+                        span {
+                            class: "font-ariel text-xs text-red-700 mr-1",
+                            title: "approved at {approved_sha.as_deref().unwrap_or_default()}, head is {sha.as_deref().unwrap_or_default()}",
+                            "changed since approved"
                         }
                     }
                     // Comments
@@ -375,45 +1585,49 @@ fn MergeRequest(merge_request: MergeRequest) -> Element {
                         span { class: "mr-1", "{user_notes_count}" }
                         Icon { width: 12, height: 12, fill: "#626168", icon: FaComment }
                     }
+                    if unresolved_threads > 0 {
+                        span { class: "font-ariel text-xs text-amber-800 rounded-sm px-1 ml-1 bg-amber-100",
+                            if unresolved_threads == 1 { "1 unresolved thread" } else { "{unresolved_threads} unresolved threads" }
+                        }
+                    }
+                    // Security findings
+                    if let Some(security_report) = security_report.filter(|r| r.added > 0) {
+                        div {
+                            class: "flex flex-row items-center font-ariel text-sm ml-2",
+                            title: "{security_report.added} new and {security_report.fixed} fixed security findings",
+                            span { class: "mr-1", "{security_report.added}" }
+                            Icon { width: 12, height: 12, fill: "#dd2b0e", icon: FaShieldHalved }
+                        }
+                    }
+                    // Large, binary, or generated changes
+                    if !change_warnings.is_empty() {
+                        div {
+                            class: "flex flex-row items-center ml-2",
+                            title: "{change_warnings}",
+                            Icon { width: 12, height: 12, fill: "#c17d10", icon: FaTriangleExclamation }
+                        }
+                    }
+                    // Code quality
+                    if let Some(code_quality_report) = code_quality_report.filter(|r| r.added > 0 || r.fixed > 0) {
+                        div {
+                            class: "flex flex-row items-center font-ariel text-sm ml-2",
+                            title: "code quality: {code_quality_report.added} new and {code_quality_report.fixed} resolved violations",
+                            Icon { width: 12, height: 12, fill: "#626168", icon: FaGauge }
+                            if code_quality_report.added > 0 {
+                                span { class: "ml-1 text-red-700", "+{code_quality_report.added}" }
+                            }
+                            if code_quality_report.fixed > 0 {
+                                span { class: "ml-1 text-green-700", "-{code_quality_report.fixed}" }
+                            }
+                        }
+                    }
                     span { class: "mx-2", "|" }
                     // Pipeline status
                     a {
                         class: "mr-1",
-                        title: "pipeline:{head_pipeline.status}",
+                        title: pipeline_title,
                         href: head_pipeline.web_url,
-                        match head_pipeline.status {
-                            PipelineStatus::Unknown => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaCircleQuestion,
-                                fill: "#dd2b0e",
-                            }),
-                            Failed => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaCircleExclamation,
-                                fill: "#dd2b0e",
-                            }),
-                            Canceled => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaBan,
-                                fill: "#dd2b0e",
-                            }),
-                            Created | WaitingForResource | Preparing | Pending
-                            | Running | Skipped | Manual | Scheduled => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaSpinner,
-                                fill: "#1f75cb",
-                            }),
-                            Success => rsx!(Icon {
-                                width: 16,
-                                height: 16,
-                                icon: FaCircleCheck,
-                                fill: "#108548",
-                            }),
-                        }
+                        status::StatusIconView { icon: status::pipeline_status_icon(&head_pipeline.status) }
                     }
                     // Pipeline time
                     span {
@@ -421,6 +1635,21 @@ fn MergeRequest(merge_request: MergeRequest) -> Element {
                         title: "duration: {pipeline_time_in_min} queued: {pipeline_queued_time_in_min}",
                         "{pipeline_time_in_min}m"
                     }
+                    if let Some(coverage) = coverage {
+                        span { class: "font-ariel text-sm mr-1", title: coverage_title, "{coverage}%" }
+                        if let Some(delta) = coverage_delta.filter(|d| d.abs() >= 0.01) {
+                            span {
+                                class: if delta < 0.0 { "font-ariel text-xs text-red-700 mr-1" } else { "font-ariel text-xs text-green-700 mr-1" },
+                                "{delta:+.2}"
+                            }
+                        }
+                    }
+                    span {
+                        class: "cursor-pointer",
+                        title: "actions",
+                        onclick: move |_event| *menu_open.write() = !menu_open(),
+                        Icon { width: 12, height: 12, fill: "#626168", icon: FaEllipsisVertical }
+                    }
                 }
                 div { class: "flex flex-row justify-end",
                     span {
@@ -431,22 +1660,151 @@ fn MergeRequest(merge_request: MergeRequest) -> Element {
                 }
             }
         }
+        div { class: "flex flex-row items-center",
+            span { class: "font-ariel text-xs mr-1", "assignees:" }
+            if assignees.is_empty() {
+                span { class: "font-ariel text-xs", "none" }
+            }
+            for assignee in assignees {
+                if let Some(working_hours) = team.get(&assignee.username) {
+                    span {
+                        class: if working_hours.is_online(Utc::now()) { "w-2 h-2 mr-1 rounded-full bg-green-500" } else { "w-2 h-2 mr-1 rounded-full bg-gray-300" },
+                        title: working_hours.describe(Utc::now()),
+                    }
+                }
+                a { class: "font-ariel text-xs mr-1", href: assignee.web_url.clone(), "{assignee.username}" }
+                if oncall::is_on_call(&shifts.read(), &assignee, Utc::now()) {
+                    span { class: "font-ariel text-xs rounded-full px-2 mr-1 bg-red-100", title: "on call now", "on call" }
+                }
+            }
+        }
         div { class: "flex flex-row items-center",
             span { class: "font-ariel text-xs mr-1", "reviewers:" }
             if reviewers.is_empty() {
                 span { class: "font-ariel text-xs", "none" }
             }
             for reviewer in reviewers {
-                a { class: "font-ariel text-xs mr-1", href: reviewer.web_url, "{reviewer.username}" }
+                if let Some(state) = reviewer_states.iter().find(|r| r.user.id == reviewer.id).map(|r| r.state) {
+                    span { class: "mr-1", title: state.to_string().replace('_', " "),
+                        status::StatusIconView { icon: status::review_state_icon(state) }
+                    }
+                }
+                if let Some(working_hours) = team.get(&reviewer.username) {
+                    span {
+                        class: if working_hours.is_online(Utc::now()) { "w-2 h-2 mr-1 rounded-full bg-green-500" } else { "w-2 h-2 mr-1 rounded-full bg-gray-300" },
+                        title: working_hours.describe(Utc::now()),
+                    }
+                }
+                a { class: "font-ariel text-xs mr-1", href: reviewer.web_url.clone(), "{reviewer.username}" }
+                if oncall::is_on_call(&shifts.read(), &reviewer, Utc::now()) {
+                    span { class: "font-ariel text-xs rounded-full px-2 mr-1 bg-red-100", title: "on call now", "on call" }
+                }
+            }
+        }
+        for (name, rule_type, approvals_required, eligible, more) in unsatisfied_rules {
+            div { class: "flex flex-row flex-wrap items-center",
+                span {
+                    class: "font-ariel text-xs mr-1",
+                    title: "{rule_type} rule requiring {approvals_required} approvals",
+                    "needs {name}:"
+                }
+                if eligible.is_empty() {
+                    span { class: "font-ariel text-xs mr-1", "no eligible approvers" }
+                }
+                for user in eligible {
+                    a { class: "font-ariel text-xs mr-1", href: user.web_url, "{user.username}" }
+                }
+                if more > 0 {
+                    span { class: "font-ariel text-xs", "+{more} more" }
+                }
+            }
+        }
+        if let Some(changes_requested_at) = changes_requested_at {
+            div { class: "flex flex-row items-center",
+                span { class: "font-ariel text-xs mr-1", title: changes_requested_at.to_string(),
+                    "changes requested {time_ago(changes_requested_at)}"
+                }
+                if last_pushed_at.is_none_or(|pushed_at| pushed_at < changes_requested_at) {
+                    span { class: "font-ariel text-xs text-red-700", "no push since" }
+                }
+            }
+        }
+        if let Some(comment) = latest_comment {
+            div { class: "flex flex-row items-center min-w-0",
+                span { class: "font-ariel text-xs whitespace-nowrap mr-1", title: comment.created_at.to_string(),
+                    "{comment.author.username} commented {time_ago(comment.created_at)}:"
+                }
+                span { class: "font-ariel text-xs truncate", title: comment.body.clone(),
+                    "{comment.body.lines().next().unwrap_or_default()}"
+                }
+            }
+        }
+        if time_stats.time_estimate > 0 || time_stats.total_time_spent > 0 {
+            div { class: "flex flex-row items-center font-ariel text-xs",
+                span {
+                    class: if time_stats.time_estimate > 0 && time_stats.total_time_spent > time_stats.time_estimate { "mr-1 text-red-700" } else { "mr-1" },
+                    "{time_stats.human_total_time_spent()} spent"
+                }
+                if time_stats.time_estimate > 0 {
+                    span { "of {time_stats.human_time_estimate()} estimated" }
+                }
+            }
+        }
+        if !failed_jobs.is_empty() {
+            div { class: "flex flex-row items-center",
+                span { class: "font-ariel text-xs text-red-700", "failed: {failed_jobs}" }
+            }
+        }
+        if status_checks.iter().any(|c| c.status != StatusCheckStatus::Passed) {
+            div { class: "flex flex-row items-center",
+                span { class: "font-ariel text-xs mr-1", "blocking checks:" }
+                for check in status_checks.into_iter().filter(|c| c.status != StatusCheckStatus::Passed) {
+                    a {
+                        class: "font-ariel text-xs mr-1",
+                        href: check.external_url,
+                        "{check.name} ({check.status})"
+                    }
+                }
+            }
+        }
+        test_report::TestSummary { merge_request: merge_request.clone() }
+        jobs::Jobs { merge_request: merge_request.clone() }
+        commits::Commits { merge_request: merge_request.clone() }
+        issues::ClosesIssues { merge_request: merge_request.clone() }
+        participants::Participants { merge_request: merge_request.clone() }
+        if menu_open() {
+            menu::MergeRequestMenu {
+                pinned,
+                on_snooze: move |_| on_snooze.call(id),
+                on_pin: move |_| on_pin.call(reference.clone()),
+                on_close: move |_| *menu_open.write() = false,
+                merge_request: merge_request.clone(),
             }
         }
     )
 }
 
+/// How long ago the time was, or how long until it for times in the future like upcoming releases
+/// or commits from clocks running ahead
 fn time_ago(time: DateTime<Utc>) -> String {
     static FORMATTER: OnceLock<Formatter> = OnceLock::new();
-    let formatter = FORMATTER.get_or_init(|| Formatter::new());
-    formatter.convert((Utc::now() - time).to_std().unwrap())
+    static UNTIL_FORMATTER: OnceLock<Formatter> = OnceLock::new();
+    let now = Utc::now();
+    match (now - time).to_std() {
+        Ok(elapsed) => FORMATTER.get_or_init(Formatter::new).convert(elapsed),
+        Err(_) => {
+            // Rounded up, the truncation of the formatter would otherwise make it a unit short
+            let until = (time - now).to_std().unwrap_or_default();
+            let until =
+                std::time::Duration::from_secs(until.as_secs() + u64::from(until.subsec_nanos() > 0));
+            let formatter = UNTIL_FORMATTER.get_or_init(|| {
+                let mut formatter = Formatter::new();
+                formatter.ago("");
+                formatter
+            });
+            format!("in {}", formatter.convert(until))
+        }
+    }
 }
 
 fn set_clipboard(v: &str) {
@@ -456,3 +1814,18 @@ fn set_clipboard(v: &str) {
         .expect("clipboard to exist")
         .write_text(v);
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn future_times_are_ahead() {
+        let now = Utc::now();
+        assert_eq!(time_ago(now - TimeDelta::days(3)), "3 days ago");
+        assert_eq!(time_ago(now + TimeDelta::days(3)), "in 3 days");
+        assert_eq!(time_ago(now + TimeDelta::milliseconds(100)), "in 1 second");
+    }
+}
@@ -0,0 +1,240 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{
+    FaBellSlash, FaBullhorn, FaCode, FaCodeBranch, FaCopy, FaEye, FaFire, FaThumbsUp, FaThumbtack,
+    FaUserPlus, FaXmark,
+};
+use dioxus_free_icons::Icon;
+
+use crate::api::{
+    assign_merge_request, create_todo, fetch_current_user, set_reviewers, Instance, MergeRequest,
+};
+use crate::incidents::Incidents;
+use crate::outbox::{Action, Outbox, Outcome};
+use crate::raw::RawJson;
+use crate::{set_clipboard, usage};
+
+/// The reminder posted by the nudge action, mentioning the pending reviewers
+fn nudge_note(merge_request: &MergeRequest) -> Option<String> {
+    let mentions = merge_request
+        .pending_reviewers()
+        .iter()
+        .map(|u| format!("@{}", u.username))
+        .collect::<Vec<_>>();
+    if mentions.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Hi {}, a friendly reminder that this merge request is waiting on your review. Thanks!",
+        mentions.join(" ")
+    ))
+}
+
+/// All the actions available on a merge request row, opened with right click or the kebab icon
+#[component]
+pub fn MergeRequestMenu(
+    merge_request: MergeRequest,
+    pinned: bool,
+    on_snooze: EventHandler<()>,
+    on_pin: EventHandler<()>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut status = use_signal(String::new);
+    let mut claim_with_todo = use_signal(|| false);
+    let mut incidents = use_context::<Incidents>();
+    let mut outbox = use_context::<Outbox>();
+    let incident_url = incidents
+        .links
+        .read()
+        .get(&merge_request.references.full)
+        .cloned();
+
+    let source_branch = merge_request.source_branch.clone();
+    let reference = merge_request.references.full.clone();
+    let pipeline_url = merge_request
+        .head_pipeline
+        .as_ref()
+        .map(|p| p.web_url.clone())
+        .filter(|url| !url.is_empty());
+    let approve_merge_request_ = merge_request.clone();
+    let nudge_merge_request = merge_request.clone();
+    let claim_merge_request = merge_request.clone();
+    let incident_merge_request = merge_request.clone();
+    let raw_merge_request = merge_request.clone();
+    let mut raw_open = use_signal(|| false);
+
+    rsx! {
+        div { class: "flex flex-row items-center font-ariel text-xs border rounded-sm border-gray-300 bg-gray-100 p-1 my-1",
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                onclick: move |_event| {
+                    usage::record("menu", "copy branch");
+                    set_clipboard(&source_branch);
+                    on_close.call(());
+                },
+                Icon { width: 12, height: 12, icon: FaCodeBranch }
+                span { class: "ml-1", "copy branch" }
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                onclick: move |_event| {
+                    usage::record("menu", "copy reference");
+                    set_clipboard(&reference);
+                    on_close.call(());
+                },
+                Icon { width: 12, height: 12, icon: FaCopy }
+                span { class: "ml-1", "copy reference" }
+            }
+            if let Some(pipeline_url) = pipeline_url {
+                a { class: "mr-2", href: pipeline_url, "open pipeline" }
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                onclick: move |_event| {
+                    usage::record("menu", "approve");
+                    let merge_request = approve_merge_request_.clone();
+                    spawn(async move {
+                        *status.write() = match outbox.perform(&instance(), Action::Approve, &merge_request).await {
+                            Ok(Outcome::Done) => "approved".to_string(),
+                            Ok(Outcome::Queued) => "offline, approval queued".to_string(),
+                            Err(e) => e.to_string(),
+                        };
+                    });
+                },
+                Icon { width: 12, height: 12, icon: FaThumbsUp }
+                span { class: "ml-1", "approve" }
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                title: "post a reminder mentioning the reviewers who have not approved",
+                onclick: move |_event| {
+                    usage::record("menu", "nudge reviewers");
+                    let merge_request = nudge_merge_request.clone();
+                    let Some(note) = nudge_note(&merge_request) else {
+                        *status.write() = "no pending reviewers".to_string();
+                        return;
+                    };
+                    spawn(async move {
+                        *status.write() = match outbox.perform(&instance(), Action::Comment(note), &merge_request).await {
+                            Ok(Outcome::Done) => "reviewers nudged".to_string(),
+                            Ok(Outcome::Queued) => "offline, reminder queued".to_string(),
+                            Err(e) => e.to_string(),
+                        };
+                    });
+                },
+                Icon { width: 12, height: 12, icon: FaBullhorn }
+                span { class: "ml-1", "nudge reviewers" }
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer",
+                title: "add yourself to the reviewers",
+                onclick: move |_event| {
+                    usage::record("menu", "claim review");
+                    let merge_request = claim_merge_request.clone();
+                    spawn(async move {
+                        let instance = instance();
+                        let claimed = async {
+                            let user = fetch_current_user(&instance).await?;
+                            let mut reviewer_ids = merge_request.reviewers.iter().map(|u| u.id).collect::<Vec<_>>();
+                            if !reviewer_ids.contains(&user.id) {
+                                reviewer_ids.push(user.id);
+                            }
+                            set_reviewers(&instance, &merge_request, &reviewer_ids).await?;
+                            if claim_with_todo() {
+                                create_todo(&instance, &merge_request).await?;
+                            }
+                            anyhow::Ok(())
+                        };
+                        *status.write() = match claimed.await {
+                            Ok(()) => "review claimed".to_string(),
+                            Err(e) => e.to_string(),
+                        };
+                    });
+                },
+                Icon { width: 12, height: 12, icon: FaEye }
+                span { class: "ml-1", "claim review" }
+            }
+            label { class: "flex flex-row items-center mx-1 mr-2", title: "also add it to your todo list",
+                input {
+                    r#type: "checkbox",
+                    class: "mr-1",
+                    checked: claim_with_todo(),
+                    onchange: move |event| *claim_with_todo.write() = event.checked(),
+                }
+                "todo"
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                onclick: move |_event| {
+                    usage::record("menu", "assign to me");
+                    let merge_request = merge_request.clone();
+                    spawn(async move {
+                        let instance = instance();
+                        let assigned = async {
+                            let user = fetch_current_user(&instance).await?;
+                            assign_merge_request(&instance, &merge_request, &[user.id]).await
+                        };
+                        *status.write() = match assigned.await {
+                            Ok(()) => "assigned to you".to_string(),
+                            Err(e) => e.to_string(),
+                        };
+                    });
+                },
+                Icon { width: 12, height: 12, icon: FaUserPlus }
+                span { class: "ml-1", "assign to me" }
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                onclick: move |_event| {
+                    usage::record("menu", "pin");
+                    on_pin.call(());
+                    on_close.call(());
+                },
+                Icon { width: 12, height: 12, icon: FaThumbtack }
+                span { class: "ml-1", if pinned { "unpin" } else { "pin" } }
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                title: "hide for a day",
+                onclick: move |_event| {
+                    usage::record("menu", "snooze");
+                    on_snooze.call(());
+                },
+                Icon { width: 12, height: 12, icon: FaBellSlash }
+                span { class: "ml-1", "snooze" }
+            }
+            span {
+                class: "flex flex-row items-center cursor-pointer mr-2",
+                title: "the JSON the API returned for this merge request",
+                onclick: move |_event| {
+                    usage::record("menu", "view raw");
+                    *raw_open.write() = true;
+                },
+                Icon { width: 12, height: 12, icon: FaCode }
+                span { class: "ml-1", "view raw" }
+            }
+            label { class: "flex flex-row items-center mr-2", title: "attach an incident or ticket, kept in this browser",
+                Icon { width: 12, height: 12, class: "mr-1", icon: FaFire }
+                input {
+                    r#type: "url",
+                    class: "px-1 border rounded-sm border-gray-300 text-xs",
+                    placeholder: "incident URL",
+                    value: incident_url.unwrap_or_default(),
+                    onchange: move |event| incidents.attach(&incident_merge_request, &event.value()),
+                }
+            }
+            span { class: "flex-grow", "{status}" }
+            span {
+                class: "cursor-pointer",
+                onclick: move |_event| on_close.call(()),
+                Icon { width: 12, height: 12, icon: FaXmark }
+            }
+        }
+        if raw_open() {
+            RawJson {
+                merge_request: raw_merge_request.clone(),
+                on_close: move |()| *raw_open.write() = false,
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use dioxus::prelude::*;
+use gloo_timers::future::sleep;
+use tracing::error;
+
+use crate::api::{
+    fetch_blocking_merge_request_ids, fetch_merge_request_by_iid, merge_merge_request, Instance,
+    MergeRequest, PipelineStatus,
+};
+use crate::freeze::Freezes;
+use crate::polling::{jittered, Jitter};
+use crate::Selected;
+
+/// How often the pipeline of the next merge request is checked on
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Order merge requests so each is merged after the ones it depends on, which are the ones it
+/// is stacked on, that is whose source branch it targets in the same project, and the ones
+/// blocking it
+pub fn merge_order(
+    merge_requests: &[MergeRequest],
+    blocking: &HashMap<i64, Vec<i64>>,
+) -> Result<Vec<MergeRequest>> {
+    let depends_on = |merge_request: &MergeRequest, other: &MergeRequest| {
+        merge_request.is_stacked_on(other)
+            || blocking
+                .get(&merge_request.id)
+                .is_some_and(|ids| ids.contains(&other.id))
+    };
+    let mut remaining = merge_requests.to_vec();
+    let mut order = Vec::new();
+    while !remaining.is_empty() {
+        let Some(i) = remaining
+            .iter()
+            .position(|mr| !remaining.iter().any(|other| depends_on(mr, other)))
+        else {
+            let references = remaining
+                .iter()
+                .map(|mr| mr.references.full.as_str())
+                .collect::<Vec<_>>();
+            return Err(anyhow!(
+                "circular dependency between {}",
+                references.join(", ")
+            ));
+        };
+        order.push(remaining.remove(i));
+    }
+    Ok(order)
+}
+
+/// Plan the merge order of the selected merge requests and merge them one at a time, waiting
+/// for the pipeline of the next one to pass in between
+#[component]
+pub fn MergeAssistant(merge_request_list: Vec<MergeRequest>) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let Selected(selected) = use_context::<Selected>();
+    let freezes = use_context::<Freezes>();
+    let Jitter(jitter) = use_context::<Jitter>();
+    let mut order = use_signal(Vec::<MergeRequest>::new);
+    // How many of the merge requests in the order are merged
+    let mut merged = use_signal(|| 0);
+    let mut waiting = use_signal(|| false);
+    let mut status = use_signal(String::new);
+
+    if selected.read().len() < 2 && order.read().is_empty() {
+        return None;
+    }
+
+    let plan = move |_event| {
+        let merge_requests = merge_request_list
+            .iter()
+            .filter(|mr| selected.read().contains(&mr.id))
+            .cloned()
+            .collect::<Vec<_>>();
+        spawn(async move {
+            let instance = instance();
+            let mut blocking = HashMap::new();
+            for merge_request in &merge_requests {
+                match fetch_blocking_merge_request_ids(&instance, merge_request).await {
+                    Ok(ids) => {
+                        blocking.insert(merge_request.id, ids);
+                    }
+                    Err(e) => error!(
+                        "failed fetching what blocks {}: {e}",
+                        merge_request.references.full
+                    ),
+                }
+            }
+            *merged.write() = 0;
+            match merge_order(&merge_requests, &blocking) {
+                Ok(planned) => {
+                    *order.write() = planned;
+                    status.write().clear();
+                }
+                Err(e) => {
+                    order.write().clear();
+                    *status.write() = e.to_string();
+                }
+            }
+        });
+    };
+
+    // Merging into a frozen branch waits until the freeze ends
+    let next_frozen_until = order
+        .read()
+        .get(merged())
+        .and_then(|mr| freezes.frozen_until(mr, Utc::now()));
+
+    let merge_next = move |_event| {
+        let Some(merge_request) = order.read().get(merged()).cloned() else {
+            return;
+        };
+        if let Some(frozen_until) = freezes.frozen_until(&merge_request, Utc::now()) {
+            *status.write() = format!(
+                "merging into {} is frozen until {frozen_until}",
+                merge_request.target_branch
+            );
+            return;
+        }
+        let next = order.read().get(merged() + 1).cloned();
+        spawn(async move {
+            let instance = instance();
+            *waiting.write() = true;
+            if let Err(e) = merge_merge_request(&instance, &merge_request).await {
+                *status.write() = e.to_string();
+                *waiting.write() = false;
+                return;
+            }
+            *merged.write() += 1;
+            let Some(next) = next else {
+                *status.write() = "all merged".to_string();
+                *waiting.write() = false;
+                return;
+            };
+            let reference = &next.references.full;
+            *status.write() = format!("waiting for the pipeline of {reference}");
+            // Give GitLab time to retarget stacked merge requests and start their pipelines
+            loop {
+                sleep(jittered(POLL_INTERVAL, *jitter.peek())).await;
+                let pipeline_status =
+                    match fetch_merge_request_by_iid(&instance, next.project_id, next.iid).await {
+                        Ok(mr) => mr.head_pipeline.map(|p| p.status),
+                        Err(e) => {
+                            *status.write() = e.to_string();
+                            break;
+                        }
+                    };
+                match pipeline_status {
+                    Some(PipelineStatus::Success) | None => {
+                        *status.write() = format!("{reference} is ready to merge");
+                        break;
+                    }
+                    Some(PipelineStatus::Failed | PipelineStatus::Canceled) => {
+                        *status.write() = format!("the pipeline of {reference} did not pass");
+                        break;
+                    }
+                    Some(_) => {}
+                }
+            }
+            *waiting.write() = false;
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col font-ariel text-xs border rounded-sm border-gray-300 bg-gray-100 p-1 my-1",
+            div { class: "flex flex-row items-center",
+                span { class: "mr-2", "Merge assistant" }
+                button {
+                    r#type: "button",
+                    class: "px-2 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                    disabled: waiting(),
+                    onclick: plan,
+                    "Plan"
+                }
+                button {
+                    r#type: "button",
+                    class: "px-2 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                    disabled: waiting() || merged() >= order.read().len() || next_frozen_until.is_some(),
+                    onclick: merge_next,
+                    "Merge Next"
+                }
+                if let Some(frozen_until) = next_frozen_until {
+                    span { class: "mr-1 text-blue-700", "frozen until {frozen_until}" }
+                }
+                span { "{status}" }
+            }
+            ol { class: "list-decimal list-inside",
+                for (i, merge_request) in order().into_iter().enumerate() {
+                    li {
+                        key: "{merge_request.id}",
+                        class: if i < merged() { "line-through" },
+                        "{merge_request.references.full} {merge_request.title}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::References;
+
+    use super::*;
+
+    fn merge_request(id: i64, source_branch: &str, target_branch: &str) -> MergeRequest {
+        MergeRequest {
+            id,
+            project_id: 4,
+            source_branch: source_branch.to_string(),
+            target_branch: target_branch.to_string(),
+            references: References {
+                full: format!("my-group/my-project!{id}"),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn ids(order: Result<Vec<MergeRequest>>) -> Vec<i64> {
+        order.unwrap().iter().map(|mr| mr.id).collect()
+    }
+
+    #[test]
+    fn stacked_merge_requests_follow_their_parent() {
+        let child = merge_request(1, "feature-part-2", "feature-part-1");
+        let parent = merge_request(2, "feature-part-1", "main");
+        assert_eq!(ids(merge_order(&[child, parent], &HashMap::new())), [2, 1]);
+    }
+
+    #[test]
+    fn blocked_merge_requests_follow_the_blocking_ones() {
+        let blocked = merge_request(1, "use-new-api", "main");
+        let blocking = merge_request(2, "add-new-api", "main");
+        let order = merge_order(&[blocked, blocking], &HashMap::from([(1, vec![2])]));
+        assert_eq!(ids(order), [2, 1]);
+    }
+
+    #[test]
+    fn independent_merge_requests_keep_their_order() {
+        let merge_requests = [
+            merge_request(3, "fix-typo", "main"),
+            merge_request(1, "bump-deps", "main"),
+            merge_request(2, "docs", "main"),
+        ];
+        assert_eq!(
+            ids(merge_order(&merge_requests, &HashMap::new())),
+            [3, 1, 2]
+        );
+    }
+
+    #[test]
+    fn circular_dependencies_are_an_error() {
+        let merge_requests = [
+            merge_request(1, "a", "b"),
+            merge_request(2, "b", "a"),
+            merge_request(3, "docs", "main"),
+        ];
+        let e = merge_order(&merge_requests, &HashMap::new()).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "circular dependency between my-group/my-project!1, my-group/my-project!2"
+        );
+    }
+}
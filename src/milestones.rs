@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use dioxus::prelude::*;
+
+use crate::api::{MergeRequest, Milestone};
+use crate::MergeRequestList;
+
+/// The merge requests grouped by milestone, the next due first and those without one last
+fn by_milestone(
+    merge_request_list: Vec<MergeRequest>,
+) -> Vec<(Option<Milestone>, Vec<MergeRequest>)> {
+    // Milestones without a due date sort after those with one, then by title
+    let mut groups = BTreeMap::<(bool, Option<NaiveDate>, String), (Milestone, Vec<_>)>::new();
+    let mut without = Vec::new();
+    for merge_request in merge_request_list {
+        match merge_request.milestone.clone() {
+            Some(milestone) => {
+                let key = (
+                    milestone.due_date.is_none(),
+                    milestone.due_date,
+                    milestone.title.clone(),
+                );
+                groups
+                    .entry(key)
+                    .or_insert_with(|| (milestone, Vec::new()))
+                    .1
+                    .push(merge_request);
+            }
+            None => without.push(merge_request),
+        }
+    }
+    let mut groups = groups
+        .into_values()
+        .map(|(milestone, merge_requests)| (Some(milestone), merge_requests))
+        .collect::<Vec<_>>();
+    if !without.is_empty() {
+        groups.push((None, without));
+    }
+    groups
+}
+
+/// The merge requests in a section per milestone, for planning releases around them
+#[component]
+pub fn ByMilestone(merge_request_list: Vec<MergeRequest>) -> Element {
+    rsx! {
+        for (milestone, merge_request_list) in by_milestone(merge_request_list) {
+            div { key: "{milestone.as_ref().map_or(0, |m| m.id)}", class: "flex flex-col my-1",
+                div { class: "flex flex-row items-center border-b",
+                    match &milestone {
+                        Some(milestone) => rsx! {
+                            a { class: "font-ariel text-sm mr-2", href: milestone.web_url.clone(), "{milestone.title}" }
+                            if let Some(due_date) = milestone.due_date {
+                                span { class: "font-ariel text-xs mr-2", "due {due_date}" }
+                            }
+                            if milestone.state == "closed" {
+                                span { class: "font-ariel text-xs mr-2", "closed" }
+                            }
+                        },
+                        None => rsx!(span { class: "font-ariel text-sm mr-2", "No milestone" }),
+                    }
+                    span { class: "font-ariel text-xs", "{merge_request_list.len()}" }
+                }
+                MergeRequestList { merge_request_list }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge_request(id: i64, milestone: Option<(i64, &str, Option<&str>)>) -> MergeRequest {
+        MergeRequest {
+            id,
+            milestone: milestone.map(|(id, title, due_date)| Milestone {
+                id,
+                title: title.to_string(),
+                state: "active".to_string(),
+                due_date: due_date.map(|d| d.parse().unwrap()),
+                web_url: String::new(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn milestones_are_grouped_by_due_date() {
+        let groups = by_milestone(vec![
+            merge_request(1, Some((3, "backlog", None))),
+            merge_request(2, None),
+            merge_request(3, Some((2, "17.7", Some("2024-12-19")))),
+            merge_request(4, Some((1, "17.6", Some("2024-11-21")))),
+            merge_request(5, Some((2, "17.7", Some("2024-12-19")))),
+        ]);
+        let groups = groups
+            .into_iter()
+            .map(|(milestone, merge_requests)| {
+                let ids = merge_requests.iter().map(|mr| mr.id).collect::<Vec<_>>();
+                (milestone.map(|m| m.title), ids)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            groups,
+            [
+                (Some("17.6".to_string()), vec![4]),
+                (Some("17.7".to_string()), vec![3, 5]),
+                (Some("backlog".to_string()), vec![1]),
+                (None, vec![2]),
+            ]
+        );
+    }
+}
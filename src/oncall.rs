@@ -0,0 +1,233 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use dioxus::prelude::*;
+
+use crate::api::{fetch_calendar, User};
+
+/// The shifts of the configured on-call schedule, shared through context so rows can badge the
+/// reviewers who are on call
+#[derive(Clone, Copy)]
+pub struct OnCall(pub Signal<Vec<Shift>>);
+
+/// An event of an on-call schedule exported as iCalendar, as PagerDuty and Opsgenie do
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shift {
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Lower case summary, description, and attendees, which is where the schedules name who is
+    /// on call
+    text: String,
+    /// Lower case common names and email local parts of the attendees
+    attendees: Vec<String>,
+}
+
+impl Shift {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    /// Whether an attendee is the user by username or full name, or the shift names the user by
+    /// full name as whole words
+    pub fn covers(&self, user: &User) -> bool {
+        let username = user.username.to_lowercase();
+        let name = user.name.to_lowercase();
+        self.attendees
+            .iter()
+            .any(|a| !a.is_empty() && (*a == username || *a == name))
+            || (!name.is_empty() && contains_words(&self.text, &name))
+    }
+}
+
+/// Whether `words` appear in `text` without a letter or digit right before or after them, so
+/// `al` is not found in `alice`
+fn contains_words(text: &str, words: &str) -> bool {
+    text.match_indices(words).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + words.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Whether any active shift covers the user
+pub fn is_on_call(shifts: &[Shift], user: &User, now: DateTime<Utc>) -> bool {
+    shifts.iter().any(|s| s.is_active(now) && s.covers(user))
+}
+
+pub async fn fetch_shifts(url: &str) -> Result<Vec<Shift>> {
+    Ok(parse(&fetch_calendar(url).await?))
+}
+
+/// Parse the events of an iCalendar feed. Events without a start or end are skipped, and times
+/// without a known time zone are taken as UTC.
+pub fn parse(ical: &str) -> Vec<Shift> {
+    // Long lines are folded by starting the continuation lines with whitespace
+    let mut lines = Vec::<String>::new();
+    for line in ical.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut shifts = Vec::new();
+    let mut event = None::<Event>;
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        match (name, &mut event) {
+            ("BEGIN", _) if value == "VEVENT" => event = Some(Event::default()),
+            ("END", Some(_)) if value == "VEVENT" => {
+                if let Some(shift) = event.take().and_then(Event::into_shift) {
+                    shifts.push(shift);
+                }
+            }
+            ("DTSTART", Some(event)) => event.start = parse_time(params, value),
+            ("DTEND", Some(event)) => event.end = parse_time(params, value),
+            ("SUMMARY", Some(event)) => event.summary = unescape(value),
+            ("DESCRIPTION" | "ATTENDEE", Some(event)) => {
+                event.text.push(' ');
+                event.text.push_str(&unescape(line));
+                if name == "ATTENDEE" {
+                    event.attendees.extend(attendee(params, value));
+                }
+            }
+            _ => {}
+        }
+    }
+    shifts
+}
+
+#[derive(Default)]
+struct Event {
+    summary: String,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    text: String,
+    attendees: Vec<String>,
+}
+
+impl Event {
+    fn into_shift(self) -> Option<Shift> {
+        Some(Shift {
+            text: format!("{} {}", self.summary, self.text).to_lowercase(),
+            summary: self.summary,
+            start: self.start?,
+            end: self.end?,
+            attendees: self.attendees,
+        })
+    }
+}
+
+/// Parse a `DATE-TIME` in UTC (`20240612T090000Z`), local to a `TZID`, or a whole `DATE`
+fn parse_time(params: &str, value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+    let time = NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").ok()?;
+    let timezone = params
+        .split(';')
+        .find_map(|param| param.strip_prefix("TZID="))
+        .and_then(|tzid| tzid.trim_matches('"').parse::<Tz>().ok());
+    match timezone {
+        Some(timezone) if !value.ends_with('Z') => timezone
+            .from_local_datetime(&time)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc)),
+        _ => Some(time.and_utc()),
+    }
+}
+
+/// The common name and email local part of an `ATTENDEE;CN=Bob Jones:mailto:bob@example.com`
+fn attendee(params: &str, value: &str) -> Vec<String> {
+    let name = params
+        .split(';')
+        .find_map(|param| param.strip_prefix("CN="))
+        .map(|name| name.trim_matches('"'));
+    let email = value
+        .strip_prefix("mailto:")
+        .or_else(|| value.strip_prefix("MAILTO:"))
+        .and_then(|email| email.split_once('@'))
+        .map(|(local, _)| local);
+    name.into_iter()
+        .chain(email)
+        .map(|name| unescape(name).to_lowercase())
+        .collect()
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEDULE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+DTSTART:20240612T090000Z\r
+DTEND:20240613T090000Z\r
+SUMMARY:On Call - Alice Smith - Primary\r
+DESCRIPTION:Alice Smith is on call\\nhttps://example.pagerduty.com/users/P1\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART;TZID=Europe/Berlin:20240613T110000\r
+DTEND;TZID=Europe/Berlin:20240614T110000\r
+SUMMARY:Secondary\r
+ATTENDEE;CN=Bob Jones:mailto:bob@exampl\r
+ e.com\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn user(username: &str, name: &str) -> User {
+        User {
+            username: username.to_string(),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn shifts_cover_named_users_while_active() {
+        let shifts = parse(SCHEDULE);
+        assert_eq!(shifts.len(), 2);
+        let alice = user("asmith", "Alice Smith");
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 12, 0, 0).unwrap();
+        assert!(is_on_call(&shifts, &alice, now));
+        let later = Utc.with_ymd_and_hms(2024, 6, 13, 12, 0, 0).unwrap();
+        assert!(!is_on_call(&shifts, &alice, later));
+    }
+
+    #[test]
+    fn folded_lines_and_time_zones_are_handled() {
+        let shifts = parse(SCHEDULE);
+        assert_eq!(
+            shifts[1].start,
+            Utc.with_ymd_and_hms(2024, 6, 13, 9, 0, 0).unwrap()
+        );
+        assert!(shifts[1].covers(&user("bob", "")));
+        assert!(shifts[1].covers(&user("bjones", "Bob Jones")));
+        assert!(!shifts[1].covers(&user("carol", "Carol White")));
+    }
+
+    #[test]
+    fn names_inside_other_words_do_not_cover() {
+        let shifts = parse(SCHEDULE);
+        assert!(shifts[0].covers(&user("asmith", "Alice Smith")));
+        assert!(!shifts[0].covers(&user("al", "")));
+        assert!(!shifts[0].covers(&user("on", "")));
+        assert!(!shifts[0].covers(&user("ali", "Ali")));
+        assert!(!shifts[1].covers(&user("bo", "")));
+        assert!(!shifts[1].covers(&user("ed", "Ed")));
+    }
+}
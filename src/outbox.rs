@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+use gloo_timers::future::sleep;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::api::{
+    approve_merge_request, fetch_merge_request_by_iid, post_note, Instance, MergeRequest, State,
+};
+use crate::polling::{jittered, Jitter};
+use crate::{storage, tabs, toasts};
+
+/// Local storage key of the actions waiting for the browser to be back online
+const OUTBOX_STORAGE_KEY: &str = "lab-bench-outbox";
+/// How often to check whether the browser is back online to replay the queued actions
+const REPLAY_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a tab keeps replaying for the other open tabs without renewing, see [`tabs::lead`]
+const LEADER_LEASE: Duration = Duration::from_secs(30);
+
+/// The mutating actions which can wait until the browser is back online
+#[derive(Clone, Debug, Display, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    #[strum(serialize = "approval")]
+    Approve,
+    #[strum(serialize = "comment")]
+    Comment(String),
+}
+
+/// An action taken while offline, with what the merge request looked like at the time
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Queued {
+    pub action: Action,
+    pub project_id: i64,
+    pub iid: i64,
+    pub reference: String,
+    /// The head commit the action was taken on
+    pub sha: Option<String>,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl Queued {
+    /// Why the action no longer makes sense for the refreshed merge request
+    fn conflict(&self, refreshed: &MergeRequest) -> Option<String> {
+        if refreshed.state != State::Opened {
+            return Some(format!("{} is {} now", self.reference, refreshed.state));
+        }
+        if self.action == Action::Approve && refreshed.sha != self.sha {
+            return Some(format!("{} has new commits", self.reference));
+        }
+        None
+    }
+}
+
+pub enum Outcome {
+    Done,
+    Queued,
+}
+
+/// Actions queued while offline, shared through context so the menu can queue them and the app
+/// can replay them
+#[derive(Clone, Copy)]
+pub struct Outbox {
+    pub queued: Signal<Vec<Queued>>,
+}
+
+impl Outbox {
+    pub fn load() -> Self {
+        Outbox {
+            queued: Signal::new(storage::load(OUTBOX_STORAGE_KEY).unwrap_or_default()),
+        }
+    }
+
+    /// Take the action now, or queue it when the browser is offline or goes offline while
+    /// taking it
+    pub async fn perform(
+        &mut self,
+        instance: &Instance,
+        action: Action,
+        merge_request: &MergeRequest,
+    ) -> Result<Outcome> {
+        if !is_offline() {
+            match send(instance, &action, merge_request).await {
+                Ok(()) => return Ok(Outcome::Done),
+                Err(e) if !is_offline() => return Err(e),
+                Err(_) => {}
+            }
+        }
+        // Other open tabs may have queued or replayed actions since
+        self.reload();
+        self.queued.write().push(Queued {
+            action,
+            project_id: merge_request.project_id,
+            iid: merge_request.iid,
+            reference: merge_request.references.full.clone(),
+            sha: merge_request.sha.clone(),
+            queued_at: Utc::now(),
+        });
+        self.save();
+        Ok(Outcome::Queued)
+    }
+
+    /// Take the queued actions against the refreshed merge requests, oldest first. Actions which
+    /// conflict with the refreshed state or fail are dropped, replaying stops when the browser
+    /// goes offline again. Returns what happened to each action.
+    pub async fn replay(&mut self, instance: &Instance) -> Vec<String> {
+        let mut messages = Vec::new();
+        loop {
+            let Some(queued) = self.queued.peek().first().cloned() else {
+                break;
+            };
+            if is_offline() {
+                break;
+            }
+            let replayed = async {
+                let refreshed =
+                    fetch_merge_request_by_iid(instance, queued.project_id, queued.iid).await?;
+                match queued.conflict(&refreshed) {
+                    Some(conflict) => Ok(Some(conflict)),
+                    None => send(instance, &queued.action, &refreshed)
+                        .await
+                        .map(|()| None),
+                }
+            };
+            let message = match replayed.await {
+                Ok(None) => format!("offline {} of {} sent", queued.action, queued.reference),
+                Ok(Some(conflict)) => format!("offline {} dropped, {conflict}", queued.action),
+                Err(_) if is_offline() => break,
+                Err(e) => format!(
+                    "offline {} of {} failed: {e}",
+                    queued.action, queued.reference
+                ),
+            };
+            messages.push(message);
+            self.reload();
+            let position = self.queued.peek().iter().position(|q| *q == queued);
+            if let Some(i) = position {
+                self.queued.write().remove(i);
+            }
+            self.save();
+        }
+        messages
+    }
+
+    /// Pick up the actions other open tabs queued or replayed, they share the local storage
+    pub fn reload(&mut self) {
+        let queued = storage::load(OUTBOX_STORAGE_KEY).unwrap_or_default();
+        if *self.queued.peek() != queued {
+            *self.queued.write() = queued;
+        }
+    }
+
+    pub fn discard(&mut self) {
+        self.queued.write().clear();
+        self.save();
+    }
+
+    fn save(&self) {
+        storage::save(OUTBOX_STORAGE_KEY, &*self.queued.peek());
+    }
+}
+
+/// The actions queued while offline, replayed every [`REPLAY_INTERVAL`] once back online with
+/// a toast telling what happened to each. Only one of the open tabs replays so no action is
+/// sent twice.
+#[component]
+pub fn QueuedActions(toasts: Signal<Vec<toasts::Toast>>) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut outbox = use_context::<Outbox>();
+    let Jitter(jitter) = use_context::<Jitter>();
+
+    use_hook(move || {
+        spawn(async move {
+            loop {
+                outbox.reload();
+                if !outbox.queued.peek().is_empty() && tabs::lead("outbox", LEADER_LEASE) {
+                    let messages = outbox.replay(&instance()).await;
+                    toasts::show(toasts, messages);
+                }
+                sleep(jittered(REPLAY_INTERVAL, *jitter.peek())).await;
+            }
+        })
+    });
+
+    let queued = outbox.queued.read();
+    if queued.is_empty() {
+        return None;
+    }
+    let count = queued.len();
+    let actions = queued
+        .iter()
+        .map(|q| format!("{} of {}", q.action, q.reference))
+        .collect::<Vec<_>>()
+        .join(", ");
+    rsx! {
+        div { class: "flex flex-row items-center font-ariel text-xs rounded-sm px-1 mb-1 bg-gray-100",
+            span { class: "mr-2", title: actions,
+                if count == 1 { "1 action queued until back online" } else { "{count} actions queued until back online" }
+            }
+            span {
+                class: "underline cursor-pointer",
+                onclick: move |_event| outbox.discard(),
+                "discard"
+            }
+        }
+    }
+}
+
+async fn send(instance: &Instance, action: &Action, merge_request: &MergeRequest) -> Result<()> {
+    match action {
+        Action::Approve => approve_merge_request(instance, merge_request).await,
+        Action::Comment(body) => post_note(instance, merge_request, body).await,
+    }
+}
+
+pub fn is_offline() -> bool {
+    web_sys::window().is_some_and(|window| !window.navigator().on_line())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(action: Action) -> Queued {
+        Queued {
+            action,
+            project_id: 1,
+            iid: 14,
+            reference: "group/project!14".to_string(),
+            sha: Some("abc".to_string()),
+            queued_at: Utc::now(),
+        }
+    }
+
+    fn refreshed(state: State, sha: &str) -> MergeRequest {
+        MergeRequest {
+            state,
+            sha: Some(sha.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn approvals_conflict_with_new_commits() {
+        let approval = queued(Action::Approve);
+        assert_eq!(approval.conflict(&refreshed(State::Opened, "abc")), None);
+        assert_eq!(
+            approval.conflict(&refreshed(State::Opened, "def")),
+            Some("group/project!14 has new commits".to_string())
+        );
+        let comment = queued(Action::Comment("looks good".to_string()));
+        assert_eq!(comment.conflict(&refreshed(State::Opened, "def")), None);
+    }
+
+    #[test]
+    fn actions_conflict_with_closed_merge_requests() {
+        let comment = queued(Action::Comment("looks good".to_string()));
+        assert_eq!(
+            comment.conflict(&refreshed(State::Merged, "abc")),
+            Some("group/project!14 is merged now".to_string())
+        );
+    }
+}
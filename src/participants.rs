@@ -0,0 +1,56 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight};
+use dioxus_free_icons::Icon;
+
+use crate::api::{fetch_participants, Instance, MergeRequest, User};
+
+/// Who already took part in a merge request, fetched the first time the section is expanded
+#[component]
+pub fn Participants(merge_request: MergeRequest) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut expanded = use_signal(|| false);
+    let mut participants = use_signal(|| None::<Result<Vec<User>, String>>);
+
+    let toggle = move |_event| {
+        *expanded.write() = !expanded();
+        if participants.peek().is_some() {
+            return;
+        }
+        let merge_request = merge_request.clone();
+        spawn(async move {
+            *participants.write() = Some(
+                fetch_participants(&instance(), &merge_request)
+                    .await
+                    .map_err(|e| e.to_string()),
+            );
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col",
+            span { class: "flex flex-row items-center font-ariel text-xs cursor-pointer", onclick: toggle,
+                if expanded() {
+                    Icon { width: 12, height: 12, icon: FaCaretDown }
+                } else {
+                    Icon { width: 12, height: 12, icon: FaCaretRight }
+                }
+                "participants"
+            }
+            if expanded() {
+                match participants() {
+                    Some(Ok(participants)) => rsx! {
+                        div { class: "flex flex-row flex-wrap items-center ml-3",
+                            for user in participants {
+                                a { key: "{user.id}", class: "mr-1", href: user.web_url.clone(), title: "{user.name} ({user.username})",
+                                    img { class: "w-4 h-4 rounded-full", src: user.avatar_url.clone(), alt: user.username.clone() }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx!(span { class: "font-ariel text-xs ml-3", "{e}" }),
+                    None => rsx!(span { class: "font-ariel text-xs ml-3", "loading" }),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use futures::stream::{self, StreamExt};
+use tracing::error;
+
+use crate::api::{fetch_diffs, Instance, MergeRequest, MAX_CONCURRENT_REQUESTS};
+
+/// Keep the merge requests of the monorepo which change a path under one of the prefixes, and
+/// every merge request of other projects. Merge requests whose changes can not be fetched are
+/// kept rather than silently hidden. The changes are fetched at most
+/// [`MAX_CONCURRENT_REQUESTS`] at once, keeping the order of the merge requests.
+pub async fn filter_by_paths(
+    instance: &Instance,
+    monorepo: &str,
+    prefixes: &[&str],
+    merge_requests: Vec<MergeRequest>,
+) -> Vec<MergeRequest> {
+    stream::iter(merge_requests)
+        .map(|mut merge_request| async move {
+            if merge_request.project_path() != monorepo {
+                return Some(merge_request);
+            }
+            // Without the full data the diffs still have to be fetched
+            if merge_request.diffs.is_empty() {
+                match fetch_diffs(instance, &merge_request).await {
+                    Ok(diffs) => merge_request.diffs = diffs,
+                    Err(e) => {
+                        error!(
+                            "failed fetching the changes of {}: {e}",
+                            merge_request.references.full
+                        );
+                        return Some(merge_request);
+                    }
+                }
+            }
+            merge_request
+                .changed_paths()
+                .iter()
+                .any(|path| prefixes.iter().any(|prefix| path.starts_with(prefix)))
+                .then_some(merge_request)
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .filter_map(std::future::ready)
+        .collect()
+        .await
+}
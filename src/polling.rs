@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+
+pub const DEFAULT_JITTER: u32 = 20;
+
+/// How much periodic refreshes vary their interval, in percent either way, shared through
+/// context so every polling loop uses the configured jitter
+#[derive(Clone, Copy)]
+pub struct Jitter(pub Signal<u32>);
+
+/// The interval varied randomly by up to `percent` either way, so the loops of several open
+/// dashboards drift apart instead of hitting the instance at the same moment
+pub fn jittered(interval: Duration, percent: u32) -> Duration {
+    let mut random = [0; 4];
+    if getrandom::getrandom(&mut random).is_err() {
+        return interval;
+    }
+    jitter(
+        interval,
+        percent,
+        u32::from_le_bytes(random) as f64 / u32::MAX as f64,
+    )
+}
+
+/// Vary the interval by `percent` scaled by `random` between 0 and 1, where 0.5 keeps it
+fn jitter(interval: Duration, percent: u32, random: f64) -> Duration {
+    let percent = percent.min(100) as f64 / 100.0;
+    interval.mul_f64(1.0 + (random * 2.0 - 1.0) * percent)
+}
+
+/// When to send each of `count` requests to spread them evenly over the first half of the
+/// interval, leaving the second half for the last responses to arrive
+pub fn stagger(interval: Duration, count: usize) -> Vec<Duration> {
+    let window = interval / 2;
+    (0..count)
+        .map(|i| window.mul_f64(i as f64 / count.max(1) as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_within_the_percentage() {
+        let interval = Duration::from_secs(30);
+        assert_eq!(jitter(interval, 20, 0.0), Duration::from_secs(24));
+        assert_eq!(jitter(interval, 20, 0.5), interval);
+        assert_eq!(jitter(interval, 20, 1.0), Duration::from_secs(36));
+        assert_eq!(jitter(interval, 0, 1.0), interval);
+    }
+
+    #[test]
+    fn requests_are_staggered_over_half_the_interval() {
+        let delays = stagger(Duration::from_secs(30), 3);
+        assert_eq!(
+            delays,
+            [
+                Duration::ZERO,
+                Duration::from_secs(5),
+                Duration::from_secs(10)
+            ]
+        );
+        assert!(stagger(Duration::from_secs(30), 0).is_empty());
+    }
+}
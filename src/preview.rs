@@ -0,0 +1,125 @@
+//! Renders every component from fixtures, covering each status permutation, for visual
+//! development. Built instead of the app with the `preview` feature.
+
+use std::collections::{HashMap, HashSet};
+
+use dioxus::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::api::{Auth, Instance, MergeRequest, MergeStatus, PipelineStatus, State};
+use crate::menu::MergeRequestMenu;
+use crate::custom_field::CustomField;
+use crate::freeze::Freezes;
+use crate::incidents::Incidents;
+use crate::jira::Jira;
+use crate::oncall::OnCall;
+use crate::outbox::Outbox;
+use crate::working_hours::Team;
+use crate::Selected;
+
+const MERGE_REQUEST_FIXTURE: &str = include_str!("../fixtures/gitlab/16.11/merge_request.json");
+
+fn fixture() -> MergeRequest {
+    serde_json::from_str(MERGE_REQUEST_FIXTURE).expect("invalid merge request fixture")
+}
+
+#[component]
+pub fn Preview() -> Element {
+    use_context_provider(|| {
+        Signal::new(Instance {
+            url: "https://gitlab.example.com/api/v4".to_string(),
+            auth: Auth::default(),
+            private_token: String::new(),
+            headers: Vec::new(),
+            request_tag: None,
+        })
+    });
+    use_context_provider(|| Selected(Signal::new(HashSet::new())));
+    use_context_provider(|| Freezes {
+        configured: Signal::new(String::new()),
+        windows: Signal::new(Vec::new()),
+        gitlab: Signal::new(Vec::new()),
+    });
+    use_context_provider(|| Team {
+        configured: Signal::new(String::new()),
+        members: Signal::new(HashMap::new()),
+    });
+    use_context_provider(|| CustomField(Signal::new(String::new())));
+    use_context_provider(|| Jira(Signal::new(String::new())));
+    use_context_provider(|| OnCall(Signal::new(Vec::new())));
+    use_context_provider(Incidents::load);
+    use_context_provider(Outbox::load);
+
+    let mut merge_status_permutations = Vec::new();
+    for state in State::iter() {
+        for detailed_merge_status in MergeStatus::iter() {
+            for merge_when_pipeline_succeeds in [false, true] {
+                let mut merge_request = fixture();
+                merge_request.id = merge_status_permutations.len() as i64;
+                merge_request.title =
+                    format!("{state}, {detailed_merge_status}, merge when pipeline succeeds {merge_when_pipeline_succeeds}");
+                merge_request.state = state;
+                merge_request.detailed_merge_status = detailed_merge_status;
+                merge_request.merge_when_pipeline_succeeds = merge_when_pipeline_succeeds;
+                merge_status_permutations.push(merge_request);
+            }
+        }
+    }
+    let pipeline_permutations = PipelineStatus::iter()
+        .map(Some)
+        .chain([None])
+        .enumerate()
+        .map(|(i, status)| {
+            let mut merge_request = fixture();
+            merge_request.id = i as i64;
+            merge_request.title = match &status {
+                Some(status) => format!("pipeline {status}"),
+                None => "no pipeline".to_string(),
+            };
+            merge_request.head_pipeline = status.and_then(|status| {
+                let mut pipeline = fixture().head_pipeline?;
+                pipeline.status = status;
+                Some(pipeline)
+            });
+            merge_request
+        })
+        .collect::<Vec<_>>();
+
+    rsx! {
+        div { class: "max-w-screen-lg mx-auto mt-1",
+            h1 { class: "font-ariel text-2xl", "Component Preview" }
+            h2 { class: "font-ariel text-lg border-b-2", "Merge status" }
+            PreviewList { merge_request_list: merge_status_permutations }
+            h2 { class: "font-ariel text-lg border-b-2", "Pipeline status" }
+            PreviewList { merge_request_list: pipeline_permutations }
+            h2 { class: "font-ariel text-lg border-b-2", "Errors" }
+            span { "merge requests request failed with status 401 Unauthorized" }
+            h2 { class: "font-ariel text-lg border-b-2", "Menu" }
+            MergeRequestMenu {
+                merge_request: fixture(),
+                pinned: false,
+                on_snooze: |_| {},
+                on_pin: |_| {},
+                on_close: |_| {},
+            }
+        }
+    }
+}
+
+#[component]
+fn PreviewList(merge_request_list: Vec<MergeRequest>) -> Element {
+    rsx! {
+        ul { class: "list-none",
+            for merge_request in merge_request_list {
+                li { key: "{merge_request.id}", class: "flex flex-col py-1 border-b",
+                    crate::MergeRequest {
+                        merge_request,
+                        pinned: false,
+                        on_snooze: |_| {},
+                        on_pin: |_| {},
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use dioxus::prelude::*;
+
+use crate::api::{fetch_merge_request_by_iid, Instance, MergeRequest};
+use crate::set_clipboard;
+
+/// The JSON the API returned for a merge request, pretty printed over the page. Merge requests
+/// fetched over GraphQL don't keep theirs, so it is fetched over REST.
+#[component]
+pub fn RawJson(merge_request: MergeRequest, on_close: EventHandler<()>) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut json = use_signal(|| {
+        merge_request
+            .raw
+            .as_deref()
+            .map(|raw| Ok(serde_json::to_string_pretty(raw).unwrap_or_default()))
+    });
+
+    use_hook(move || {
+        if json.peek().is_some() {
+            return;
+        }
+        spawn(async move {
+            let fetched = fetch_merge_request_by_iid(
+                &instance(),
+                merge_request.project_id,
+                merge_request.iid,
+            )
+            .await
+            .map(|mr| {
+                mr.raw
+                    .map(|raw| serde_json::to_string_pretty(&*raw).unwrap_or_default())
+                    .unwrap_or_default()
+            })
+            .map_err(|e| e.to_string());
+            *json.write() = Some(fetched);
+        });
+    });
+
+    rsx! {
+        div {
+            class: "fixed inset-0 z-50 flex items-center justify-center",
+            style: "background-color: rgb(0 0 0 / 0.4);",
+            onclick: move |_event| on_close.call(()),
+            div {
+                class: "flex flex-col w-full max-w-2xl max-h-screen p-2 rounded-sm shadow-lg bg-white",
+                onclick: move |event| event.stop_propagation(),
+                div { class: "flex flex-row items-center justify-end font-ariel text-xs mb-1",
+                    if let Some(Ok(json)) = json() {
+                        span {
+                            class: "underline cursor-pointer mr-2",
+                            onclick: move |_event| set_clipboard(&json),
+                            "copy"
+                        }
+                    }
+                    span {
+                        class: "underline cursor-pointer",
+                        onclick: move |_event| on_close.call(()),
+                        "close"
+                    }
+                }
+                match json() {
+                    Some(Ok(json)) => rsx!(pre { class: "overflow-auto font-mono text-xs", "{json}" }),
+                    Some(Err(e)) => rsx!(span { class: "font-ariel text-xs", "{e}" }),
+                    None => rsx!(span { class: "font-ariel text-xs", "loading" }),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use gloo_timers::future::sleep;
+
+use crate::api::{fetch_rebase_status, rebase_merge_request, Instance, MergeRequest, MergeStatus};
+use crate::Selected;
+
+/// How often a running rebase is checked on
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Give up following a rebase after this many checks
+const MAX_POLLS: usize = 60;
+
+/// Bulk actions on the selected merge requests, for now rebasing the ones which need it and
+/// following the rebases until they finish
+#[component]
+pub fn BatchRebase(merge_request_list: Vec<MergeRequest>) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let Selected(mut selected) = use_context::<Selected>();
+    // Full reference to the progress of its rebase
+    let mut progress = use_signal(HashMap::<String, String>::new);
+
+    let need_rebase = merge_request_list
+        .into_iter()
+        .filter(|mr| selected.read().contains(&mr.id))
+        .filter(|mr| mr.detailed_merge_status == MergeStatus::NeedRebase)
+        .collect::<Vec<_>>();
+
+    if selected.read().is_empty() && progress.read().is_empty() {
+        return None;
+    }
+
+    rsx! {
+        div { class: "flex flex-col font-ariel text-xs border rounded-sm border-gray-300 bg-gray-100 p-1 my-1",
+            div { class: "flex flex-row items-center",
+                span { class: "mr-2", "{selected.read().len()} selected, {need_rebase.len()} need a rebase" }
+                button {
+                    r#type: "button",
+                    class: "px-2 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                    disabled: need_rebase.is_empty(),
+                    onclick: move |_event| {
+                        for merge_request in need_rebase.clone() {
+                            spawn(async move {
+                                let reference = merge_request.references.full.clone();
+                                let mut set_progress = move |message: String| {
+                                    progress.write().insert(reference.clone(), message);
+                                };
+                                set_progress("rebasing".to_string());
+                                let instance = instance();
+                                if let Err(e) = rebase_merge_request(&instance, &merge_request).await {
+                                    set_progress(e.to_string());
+                                    return;
+                                }
+                                for _ in 0..MAX_POLLS {
+                                    sleep(POLL_INTERVAL).await;
+                                    match fetch_rebase_status(&instance, &merge_request).await {
+                                        Ok(status) if status.rebase_in_progress => {}
+                                        Ok(status) => {
+                                            set_progress(match status.merge_error {
+                                                Some(merge_error) => format!("failed: {merge_error}"),
+                                                None => "rebased".to_string(),
+                                            });
+                                            return;
+                                        }
+                                        Err(e) => {
+                                            set_progress(e.to_string());
+                                            return;
+                                        }
+                                    }
+                                }
+                                set_progress("still rebasing, check GitLab".to_string());
+                            });
+                        }
+                    },
+                    "Rebase"
+                }
+                button {
+                    r#type: "button",
+                    class: "px-2 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                    onclick: move |_event| {
+                        selected.write().clear();
+                        progress.write().clear();
+                    },
+                    "Clear"
+                }
+            }
+            for (reference, message) in progress() {
+                span { key: "{reference}", "{reference}: {message}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    fetch_merge_request_by_iid, fetch_merge_requests_with_full_data, Instance, MergeRequest,
+};
+use crate::{storage, time_ago, MergeRequestList};
+
+/// Local storage key of the recently viewed merge requests
+const RECENT_STORAGE_KEY: &str = "lab-bench-recent";
+const RECENT_LIMIT: usize = 20;
+
+/// A merge request which was clicked through to GitLab
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Viewed {
+    project_id: i64,
+    iid: i64,
+    reference: String,
+    title: String,
+    web_url: String,
+    viewed_at: DateTime<Utc>,
+}
+
+/// Remember that a merge request was viewed, most recent first
+pub fn record(merge_request: &MergeRequest) {
+    let mut recent = storage::load::<Vec<Viewed>>(RECENT_STORAGE_KEY).unwrap_or_default();
+    recent.retain(|v| v.reference != merge_request.references.full);
+    recent.insert(
+        0,
+        Viewed {
+            project_id: merge_request.project_id,
+            iid: merge_request.iid,
+            reference: merge_request.references.full.clone(),
+            title: merge_request.title.clone(),
+            web_url: merge_request.web_url.clone(),
+            viewed_at: Utc::now(),
+        },
+    );
+    recent.truncate(RECENT_LIMIT);
+    storage::save(RECENT_STORAGE_KEY, &recent);
+}
+
+/// The merge requests recently clicked through to GitLab, which can be refreshed to see their
+/// current status
+#[component]
+pub fn RecentlyViewed() -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let recent =
+        use_signal(|| storage::load::<Vec<Viewed>>(RECENT_STORAGE_KEY).unwrap_or_default());
+    let mut refreshed = use_signal(|| None::<Result<Vec<MergeRequest>, String>>);
+
+    rsx! {
+        div { class: "flex flex-row items-center my-1",
+            button {
+                class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
+                disabled: recent.read().is_empty(),
+                onclick: move |_event| {
+                    spawn(async move {
+                        *refreshed.write() = Some(refresh(&instance(), &recent()).await.map_err(|e| e.to_string()));
+                    });
+                },
+                "Refresh"
+            }
+        }
+        match refreshed() {
+            Some(Ok(merge_request_list)) => rsx!(MergeRequestList { merge_request_list }),
+            Some(Err(e)) => rsx!(span {"{e}"}),
+            None => rsx!(
+                ul { class: "list-none",
+                    for viewed in recent() {
+                        li { key: "{viewed.reference}", class: "flex flex-row justify-between py-1 border-b",
+                            div { class: "flex flex-col",
+                                a { class: "font-ariel text-sm", href: viewed.web_url, "{viewed.title}" }
+                                span { class: "font-ariel text-xs", "{viewed.reference}" }
+                            }
+                            span { class: "font-ariel text-xs", title: viewed.viewed_at.to_string(),
+                                "viewed {time_ago(viewed.viewed_at)}"
+                            }
+                        }
+                    }
+                }
+            ),
+        }
+    }
+}
+
+async fn refresh(instance: &Instance, recent: &[Viewed]) -> anyhow::Result<Vec<MergeRequest>> {
+    let mut merge_requests = Vec::new();
+    for viewed in recent {
+        merge_requests
+            .push(fetch_merge_request_by_iid(instance, viewed.project_id, viewed.iid).await?);
+    }
+    fetch_merge_requests_with_full_data(instance, &merge_requests).await
+}
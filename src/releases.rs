@@ -0,0 +1,142 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight, FaTag};
+use dioxus_free_icons::Icon;
+
+use crate::api::{Instance, fetch_merge_requests_between_refs, fetch_releases, Release};
+use crate::{time_ago, MergeRequestList};
+
+#[component]
+pub fn Releases(instance: Instance) -> Element {
+    let mut project_path = use_signal(String::new);
+    let mut releases_result = use_signal(|| Ok::<_, String>(Vec::<Release>::new()));
+
+    let load_instance = instance.clone();
+
+    rsx! {
+        div { class: "flex flex-row items-center my-1",
+            label { class: "block", "Repo" }
+            input {
+                r#type: "text",
+                class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                oninput: move |event| {
+                    *project_path.write() = event.value().trim().to_string();
+                }
+            }
+            button {
+                class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
+                onclick: move |_event| {
+                    let instance = load_instance.clone();
+                    spawn(async move {
+                        *releases_result
+                            .write() = fetch_releases(&instance, &project_path())
+                            .await
+                            .map_err(|e| e.to_string());
+                    });
+                },
+                "Load"
+            }
+        }
+        match releases_result.read().clone() {
+            Ok(releases) => rsx!(
+                ul { class: "list-none",
+                    for (i, release) in releases.iter().enumerate() {
+                        li { key: "{release.tag_name}", class: "flex flex-col py-1 border-b",
+                            ReleaseItem {
+                                instance: instance.clone(),
+                                project_path: project_path(),
+                                release: release.clone(),
+                                previous_tag_name: releases.get(i + 1).map(|r| r.tag_name.clone()),
+                            }
+                        }
+                    }
+                }
+            ),
+            Err(e) => rsx!(span {"{e}"}),
+        }
+    }
+}
+
+/// A single release which can be expanded to list the merge requests merged since the previous
+/// release
+#[component]
+fn ReleaseItem(
+    instance: Instance,
+    project_path: String,
+    release: Release,
+    previous_tag_name: Option<String>,
+) -> Element {
+    let mut expanded = use_signal(|| false);
+    let mut merge_requests_result = use_signal(|| None::<Result<_, String>>);
+
+    let Release {
+        name,
+        tag_name,
+        created_at,
+        released_at,
+        links,
+        ..
+    } = release;
+    let released_at = released_at.unwrap_or(created_at);
+    let has_previous = previous_tag_name.is_some();
+    let range = match &previous_tag_name {
+        Some(previous_tag_name) => format!("{previous_tag_name}...{tag_name}"),
+        None => format!("...{tag_name}"),
+    };
+
+    rsx! {
+        div { class: "flex flex-row justify-between",
+            div { class: "flex flex-row items-center",
+                span {
+                    class: "cursor-pointer",
+                    onclick: move |_event| {
+                        *expanded.write() = !expanded();
+                        // The first release has nothing to compare against
+                        let Some(previous_tag_name) = previous_tag_name.clone() else {
+                            return;
+                        };
+                        if !expanded() || merge_requests_result.read().is_some() {
+                            return;
+                        }
+                        let instance = instance.clone();
+                        let project_path = project_path.clone();
+                        let tag_name = tag_name.clone();
+                        spawn(async move {
+                            *merge_requests_result
+                                .write() = Some(
+                                fetch_merge_requests_between_refs(
+                                        &instance,
+                                        &project_path,
+                                        &previous_tag_name,
+                                        &tag_name,
+                                    )
+                                    .await
+                                    .map_err(|e| e.to_string()),
+                            );
+                        });
+                    },
+                    if expanded() {
+                        Icon { width: 16, height: 16, icon: FaCaretDown }
+                    } else {
+                        Icon { width: 16, height: 16, icon: FaCaretRight }
+                    }
+                }
+                Icon { width: 12, height: 12, fill: "#626168", icon: FaTag }
+                a { class: "font-ariel text-sm mx-1", href: links.self_url, "{name}" }
+                span { class: "font-ariel text-xs", "{range}" }
+            }
+            span { class: "font-ariel text-xs", title: released_at.to_string(),
+                "released {time_ago(released_at)}"
+            }
+        }
+        if expanded() {
+            div { class: "ml-4",
+                match merge_requests_result.read().clone() {
+                    None if !has_previous => rsx!(span { class: "font-ariel text-xs", "no previous release to compare against" }),
+                    None => rsx!(span { class: "font-ariel text-xs", "loading..." }),
+                    Some(Ok(merge_request_list)) => rsx!(MergeRequestList { merge_request_list }),
+                    Some(Err(e)) => rsx!(span {"{e}"}),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+use crate::api::{MergeRequest, PipelineStatus, ReviewState, State};
+
+/// A user written rule evaluated against every merge request on each refresh, one per line as
+/// `when <condition> [and <condition>...] then <action> [+ <action>...]`, for example
+/// `when pipeline fails and target_branch == main then notify + pin`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// The rule as written, shown in notifications
+    source: String,
+    conditions: Vec<Condition>,
+    actions: Vec<Action>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Condition {
+    /// The head pipeline failed since the previous refresh
+    PipelineFails,
+    /// The head pipeline succeeded since the previous refresh
+    PipelinePasses,
+    /// A reviewer approved since the previous refresh
+    Approved,
+    /// Merged since the previous refresh
+    Merged,
+    Draft,
+    Equals(Field, String),
+    NotEquals(Field, String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Author,
+    Label,
+    Project,
+    SourceBranch,
+    TargetBranch,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Notify,
+    Pin,
+    Sound,
+}
+
+/// What the rules asked for after a refresh
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Outcome {
+    pub messages: Vec<String>,
+    /// Full references of the merge requests to pin
+    pub pin: Vec<String>,
+    pub sound: bool,
+}
+
+/// Parse the rules, one per line. Blank lines and lines starting with `#` are ignored.
+pub fn parse(rules: &str) -> Result<Vec<Rule>> {
+    rules
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        .map(|(i, line)| line.parse().map_err(|e| anyhow!("rule {}: {e}", i + 1)))
+        .collect()
+}
+
+/// Evaluate the rules against merge requests which were also part of the previous refresh
+pub fn evaluate(rules: &[Rule], previous: &[MergeRequest], current: &[MergeRequest]) -> Outcome {
+    let mut outcome = Outcome::default();
+    for merge_request in current {
+        let Some(before) = previous.iter().find(|mr| mr.id == merge_request.id) else {
+            continue;
+        };
+        for rule in rules {
+            if !rule
+                .conditions
+                .iter()
+                .all(|c| c.matches(before, merge_request))
+            {
+                continue;
+            }
+            for action in &rule.actions {
+                match action {
+                    Action::Notify => outcome.messages.push(format!(
+                        "{} matched `{}`",
+                        merge_request.references.short, rule.source
+                    )),
+                    Action::Pin => outcome.pin.push(merge_request.references.full.clone()),
+                    Action::Sound => outcome.sound = true,
+                }
+            }
+        }
+    }
+    outcome
+}
+
+impl Condition {
+    fn matches(&self, before: &MergeRequest, merge_request: &MergeRequest) -> bool {
+        let pipeline_status =
+            |mr: &MergeRequest| mr.head_pipeline.as_ref().map(|p| p.status.clone());
+        let became = |status| {
+            pipeline_status(merge_request) == Some(status)
+                && pipeline_status(before) != pipeline_status(merge_request)
+        };
+        match self {
+            Condition::PipelineFails => became(PipelineStatus::Failed),
+            Condition::PipelinePasses => became(PipelineStatus::Success),
+            Condition::Approved => merge_request.reviewer_states.iter().any(|reviewer| {
+                reviewer.state == ReviewState::Approved
+                    && !before
+                        .reviewer_states
+                        .iter()
+                        .any(|r| r.user.id == reviewer.user.id && r.state == ReviewState::Approved)
+            }),
+            Condition::Merged => {
+                before.state != State::Merged && merge_request.state == State::Merged
+            }
+            Condition::Draft => merge_request.draft,
+            Condition::Equals(field, value) => field.matches(merge_request, value),
+            Condition::NotEquals(field, value) => !field.matches(merge_request, value),
+        }
+    }
+}
+
+impl Field {
+    fn matches(self, merge_request: &MergeRequest, value: &str) -> bool {
+        match self {
+            Field::Author => merge_request.author.username == value,
+            Field::Label => merge_request.labels.iter().any(|l| l.name == value),
+            Field::Project => merge_request.project_path() == value,
+            Field::SourceBranch => merge_request.source_branch == value,
+            Field::TargetBranch => merge_request.target_branch == value,
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(rule: &str) -> Result<Self> {
+        let source = rule.trim().to_string();
+        let rule = source
+            .strip_prefix("when ")
+            .ok_or_else(|| anyhow!("must start with `when`"))?;
+        let (conditions, actions) = rule
+            .split_once(" then ")
+            .ok_or_else(|| anyhow!("missing `then`"))?;
+        Ok(Rule {
+            source: source.clone(),
+            conditions: conditions
+                .split(" and ")
+                .map(str::parse)
+                .collect::<Result<_>>()?,
+            actions: actions.split('+').map(str::parse).collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl FromStr for Condition {
+    type Err = Error;
+
+    fn from_str(condition: &str) -> Result<Self> {
+        let condition = condition.trim();
+        if let Some((field, value)) = condition.split_once("!=") {
+            return Ok(Condition::NotEquals(
+                field.parse()?,
+                value.trim().to_string(),
+            ));
+        }
+        if let Some((field, value)) = condition.split_once("==") {
+            return Ok(Condition::Equals(field.parse()?, value.trim().to_string()));
+        }
+        match condition {
+            "pipeline fails" => Ok(Condition::PipelineFails),
+            "pipeline passes" => Ok(Condition::PipelinePasses),
+            "approved" => Ok(Condition::Approved),
+            "merged" => Ok(Condition::Merged),
+            "draft" => Ok(Condition::Draft),
+            _ => Err(anyhow!("unknown condition `{condition}`")),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(field: &str) -> Result<Self> {
+        match field.trim() {
+            "author" => Ok(Field::Author),
+            "label" => Ok(Field::Label),
+            "project" => Ok(Field::Project),
+            "source_branch" => Ok(Field::SourceBranch),
+            "target_branch" => Ok(Field::TargetBranch),
+            field => Err(anyhow!("unknown field `{field}`")),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(action: &str) -> Result<Self> {
+        match action.trim() {
+            "notify" => Ok(Action::Notify),
+            "pin" => Ok(Action::Pin),
+            "sound" => Ok(Action::Sound),
+            action => Err(anyhow!("unknown action `{action}`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{Pipeline, References, Reviewer, User};
+
+    use super::*;
+
+    fn merge_request(status: PipelineStatus) -> MergeRequest {
+        MergeRequest {
+            id: 1,
+            target_branch: "main".to_string(),
+            references: References {
+                full: "my-group/my-project!14".to_string(),
+                short: "!14".to_string(),
+                relative: "!14".to_string(),
+            },
+            head_pipeline: Some(Pipeline {
+                status,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn approved_by(id: i64, mut merge_request: MergeRequest) -> MergeRequest {
+        merge_request.reviewer_states.push(Reviewer {
+            user: User {
+                id,
+                ..Default::default()
+            },
+            state: ReviewState::Approved,
+            created_at: Default::default(),
+        });
+        merge_request
+    }
+
+    #[test]
+    fn rules_parse_conditions_and_actions() {
+        let rules =
+            parse("# pipelines\nwhen pipeline fails and target_branch == main then notify + pin")
+                .unwrap();
+        assert_eq!(
+            rules,
+            [Rule {
+                source: "when pipeline fails and target_branch == main then notify + pin"
+                    .to_string(),
+                conditions: vec![
+                    Condition::PipelineFails,
+                    Condition::Equals(Field::TargetBranch, "main".to_string()),
+                ],
+                actions: vec![Action::Notify, Action::Pin],
+            }]
+        );
+        assert_eq!(
+            "when label != backend then sound"
+                .parse::<Rule>()
+                .unwrap()
+                .conditions,
+            [Condition::NotEquals(Field::Label, "backend".to_string())]
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_reported_by_line() {
+        let error = |rules: &str| parse(rules).unwrap_err().to_string();
+        assert_eq!(
+            error("pipeline fails then notify"),
+            "rule 1: must start with `when`"
+        );
+        assert_eq!(error("when pipeline fails"), "rule 1: missing `then`");
+        assert_eq!(
+            error("when draft then pin\n\nwhen pipeline stalls then notify"),
+            "rule 3: unknown condition `pipeline stalls`"
+        );
+        assert_eq!(
+            error("when draft then email"),
+            "rule 1: unknown action `email`"
+        );
+        assert_eq!(
+            error("when reviewer == alex then pin"),
+            "rule 1: unknown field `reviewer`"
+        );
+    }
+
+    #[test]
+    fn pipeline_fails_only_when_it_became_failed() {
+        let rules =
+            parse("when pipeline fails and target_branch == main then notify + pin").unwrap();
+        let running = [merge_request(PipelineStatus::Running)];
+        let failed = [merge_request(PipelineStatus::Failed)];
+        let outcome = evaluate(&rules, &running, &failed);
+        assert_eq!(
+            outcome.messages,
+            ["!14 matched `when pipeline fails and target_branch == main then notify + pin`"]
+        );
+        assert_eq!(outcome.pin, ["my-group/my-project!14"]);
+        assert!(!outcome.sound);
+        // Still failed on the next refresh
+        assert_eq!(evaluate(&rules, &failed, &failed), Outcome::default());
+        // Not part of the previous refresh
+        assert_eq!(evaluate(&rules, &[], &failed), Outcome::default());
+    }
+
+    #[test]
+    fn approved_only_for_new_approvers() {
+        let rules = parse("when approved then sound").unwrap();
+        let unapproved = [merge_request(PipelineStatus::Success)];
+        let approved = [approved_by(7, unapproved[0].clone())];
+        let approved_again = [approved_by(9, approved[0].clone())];
+        assert!(evaluate(&rules, &unapproved, &approved).sound);
+        assert!(!evaluate(&rules, &approved, &approved).sound);
+        assert!(evaluate(&rules, &approved, &approved_again).sound);
+    }
+
+    #[test]
+    fn not_equals_excludes_the_value() {
+        let rules = parse("when target_branch != main then sound").unwrap();
+        let into_main = [merge_request(PipelineStatus::Success)];
+        assert!(!evaluate(&rules, &into_main, &into_main).sound);
+        let into_develop = [MergeRequest {
+            target_branch: "develop".to_string(),
+            ..into_main[0].clone()
+        }];
+        assert!(evaluate(&rules, &into_develop, &into_develop).sound);
+    }
+}
@@ -0,0 +1,86 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaMagnifyingGlass, FaXmark};
+use dioxus_free_icons::Icon;
+
+use strum::IntoEnumIterator;
+
+use crate::api::{search_merge_requests, Instance, MergeRequest, SearchIn};
+use crate::time_ago;
+
+/// Search box in the header searching all the merge requests of the instance, not just the ones
+/// matched by the query, with the results shown in a popover
+#[component]
+pub fn GlobalSearch() -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut search = use_signal(String::new);
+    let mut search_in = use_signal(SearchIn::default);
+    let mut results = use_signal(|| None::<Result<Vec<MergeRequest>, String>>);
+
+    rsx! {
+        div { class: "relative mr-1",
+            form {
+                class: "flex flex-row items-center",
+                prevent_default: "onsubmit",
+                onsubmit: move |_event| {
+                    if search().trim().is_empty() {
+                        return;
+                    }
+                    spawn(async move {
+                        *results
+                            .write() = Some(
+                            search_merge_requests(&instance(), search().trim(), search_in())
+                                .await
+                                .map_err(|e| e.to_string()),
+                        );
+                    });
+                },
+                Icon { width: 12, height: 12, icon: FaMagnifyingGlass }
+                input {
+                    r#type: "search",
+                    class: "block ml-1 p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                    placeholder: "search all merge requests",
+                    oninput: move |event| {
+                        *search.write() = event.value();
+                    }
+                }
+                select {
+                    class: "block ml-1 p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                    title: "what the search matches",
+                    onchange: move |event| {
+                        *search_in.write() = SearchIn::iter().find(|s| s.to_string() == event.value()).unwrap_or_default();
+                    },
+                    option { value: "{SearchIn::TitleAndDescription}", "both" }
+                    option { value: "{SearchIn::Title}", "titles" }
+                    option { value: "{SearchIn::Description}", "descriptions" }
+                }
+            }
+            if let Some(results_) = results() {
+                div { class: "absolute right-0 z-10 w-96 p-1 border rounded-sm border-gray-300 bg-gray-100",
+                    div { class: "flex flex-row justify-end",
+                        span {
+                            class: "cursor-pointer",
+                            onclick: move |_event| *results.write() = None,
+                            Icon { width: 12, height: 12, icon: FaXmark }
+                        }
+                    }
+                    match results_ {
+                        Ok(merge_requests) if merge_requests.is_empty() => rsx!(span { class: "font-ariel text-xs", "no matches" }),
+                        Ok(merge_requests) => rsx!(
+                            ul { class: "list-none",
+                                for merge_request in merge_requests {
+                                    li { key: "{merge_request.id}", class: "flex flex-col py-1 border-b",
+                                        a { class: "font-ariel text-xs", href: merge_request.web_url, "{merge_request.title}" }
+                                        span { class: "font-ariel text-xs", title: merge_request.updated_at.to_string(),
+                                            "{merge_request.references.full} by {merge_request.author.username}, updated {time_ago(merge_request.updated_at)}"
+                                        }
+                                    }
+                                }
+                            }
+                        ),
+                        Err(e) => rsx!(span { class: "font-ariel text-xs text-red-700", "{e}" }),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Result};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::api::{
+    fetch_snippet_content, save_private_snippet, Auth, Instance, MergeRequestsQuery, GITHUB_API_URL,
+};
+use crate::logs::{LogLevel, LogScope};
+use crate::{polling, storage};
+
+/// The private snippet the encrypted settings are synced through
+const SNIPPET_TITLE: &str = "lab-bench settings";
+const SNIPPET_FILE_PATH: &str = "lab-bench-settings.txt";
+/// Local storage key of the encrypted private token
+const TOKEN_STORAGE_KEY: &str = "lab-bench-token";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PBKDF2_ROUNDS: u32 = 600_000;
+
+/// The user configurable state of the app. The private token is deliberately not part of it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Settings {
+    pub gitlab_url: String,
+    #[serde(default)]
+    pub auth: Auth,
+    /// Static headers one per line as `Name: value`
+    pub headers: String,
+    /// Prefix of the `X-Request-Id` header
+    #[serde(default)]
+    pub request_tag: Option<String>,
+    /// Whitespace separated project paths
+    pub repos: String,
+    /// Whitespace separated group paths
+    #[serde(default)]
+    pub groups: String,
+    /// Whitespace separated usernames
+    pub authors: String,
+    /// Whitespace separated usernames
+    #[serde(default)]
+    pub assignees: String,
+    /// Whitespace separated usernames
+    #[serde(default)]
+    pub reviewers: String,
+    #[serde(with = "persisted_query")]
+    pub query: MergeRequestsQuery,
+    /// Rules evaluated on each refresh, one per line
+    #[serde(default)]
+    pub rules: String,
+    /// Whitespace separated labels whose merge requests are shown in their own section
+    #[serde(default)]
+    pub routed_labels: String,
+    /// Project path whose merge requests are filtered by `path_prefixes`
+    #[serde(default)]
+    pub monorepo: String,
+    /// Whitespace separated path prefixes
+    #[serde(default)]
+    pub path_prefixes: String,
+    /// Merge freeze windows, one per line
+    #[serde(default)]
+    pub freeze_windows: String,
+    /// Also read the deploy freeze periods of the fetched projects from GitLab
+    #[serde(default)]
+    pub gitlab_freeze_periods: bool,
+    /// REST API root of the GitHub instance, the token is not part of the settings either
+    #[serde(default = "github_url_default")]
+    pub github_url: String,
+    /// Whitespace separated `owner/repo` whose pull requests are shown with the merge requests
+    #[serde(default)]
+    pub github_repos: String,
+    /// Working hours of team members, one per line
+    #[serde(default)]
+    pub team: String,
+    /// iCalendar URL of the on-call schedule
+    #[serde(default)]
+    pub on_call_calendar: String,
+    /// Release branches compared with the branch they were cut from, see
+    /// [`crate::divergence::parse`]
+    #[serde(default)]
+    pub release_branches: String,
+    /// Label of merge requests which should be picked onto the release branches
+    #[serde(default)]
+    pub backport_label: String,
+    /// URL of the incident named by an `incident::<id>` label, `{id}` is replaced by the id
+    #[serde(default)]
+    pub incident_url_template: String,
+    /// Base URL of the Jira instance issue keys in merge requests link to
+    #[serde(default)]
+    pub jira_url: String,
+    /// How much periodic refreshes vary their interval, in percent either way
+    #[serde(default = "poll_jitter_default")]
+    pub poll_jitter: u32,
+    /// Template of an extra field on the rows over the JSON of the merge requests, see
+    /// [`crate::custom_field::render`]
+    #[serde(default)]
+    pub custom_field: String,
+    #[serde(default)]
+    pub log_level: LogLevel,
+    /// Which part of the app to log
+    #[serde(default)]
+    pub log_scope: LogScope,
+}
+
+/// Unsaved edits kept in session storage so they survive reloading the tab
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Draft {
+    /// Without the headers, which may hold credentials and are only kept encrypted
+    pub settings: Settings,
+    pub inputs: QueryInputs,
+}
+
+/// The text of the query inputs which are parsed as they are typed, kept so a value that does not
+/// parse yet is not cleared
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct QueryInputs {
+    pub created_after: String,
+    pub created_before: String,
+    pub deployed_after: String,
+    pub deployed_before: String,
+    pub iids: String,
+}
+
+impl QueryInputs {
+    pub fn of(query: &MergeRequestsQuery) -> Self {
+        let time = |time: Option<DateTime<Utc>>| time.map(|t| t.to_rfc3339()).unwrap_or_default();
+        Self {
+            created_after: time(query.created_after),
+            created_before: time(query.created_before),
+            deployed_after: time(query.deployed_after),
+            deployed_before: time(query.deployed_before),
+            iids: query
+                .iids
+                .iter()
+                .map(|iid| format!("!{iid}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+fn github_url_default() -> String {
+    GITHUB_API_URL.to_string()
+}
+
+fn poll_jitter_default() -> u32 {
+    polling::DEFAULT_JITTER
+}
+
+/// `MergeRequestsQuery` is sent as the URL parameters of the merge requests endpoints, so the
+/// options that are not parameters are skipped there but still belong in the settings
+mod persisted_query {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::api::{Backend, MergeRequestsQuery, Pagination};
+
+    #[derive(Deserialize, Serialize)]
+    struct PersistedQuery {
+        #[serde(flatten)]
+        params: MergeRequestsQuery,
+        #[serde(default)]
+        sudo: Option<String>,
+        #[serde(default)]
+        pagination: Pagination,
+        #[serde(default)]
+        per_page: Option<usize>,
+        #[serde(default)]
+        max_results: Option<usize>,
+        #[serde(default)]
+        iids: Vec<i64>,
+        #[serde(default = "non_archived_default")]
+        non_archived: bool,
+        #[serde(default)]
+        backend: Backend,
+        #[serde(default)]
+        simple_view: bool,
+    }
+
+    fn non_archived_default() -> bool {
+        true
+    }
+
+    pub fn serialize<S: Serializer>(
+        query: &MergeRequestsQuery,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        PersistedQuery {
+            sudo: query.sudo.clone(),
+            pagination: query.pagination,
+            per_page: query.per_page,
+            max_results: query.max_results,
+            iids: query.iids.clone(),
+            non_archived: query.non_archived,
+            backend: query.backend,
+            simple_view: query.simple_view,
+            params: query.clone(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<MergeRequestsQuery, D::Error> {
+        let persisted = PersistedQuery::deserialize(deserializer)?;
+        Ok(MergeRequestsQuery {
+            sudo: persisted.sudo,
+            pagination: persisted.pagination,
+            per_page: persisted.per_page,
+            max_results: persisted.max_results,
+            iids: persisted.iids,
+            non_archived: persisted.non_archived,
+            backend: persisted.backend,
+            simple_view: persisted.simple_view,
+            ..persisted.params
+        })
+    }
+}
+
+impl Settings {
+    /// Serialize and encrypt the settings with a key derived from the passphrase
+    pub fn encrypt(&self, passphrase: &str) -> Result<String> {
+        encrypt(&serde_json::to_vec(self)?, passphrase)
+    }
+
+    /// Decrypt and deserialize settings created by [`Settings::encrypt`]
+    pub fn decrypt(encrypted: &str, passphrase: &str) -> Result<Self> {
+        Ok(serde_json::from_slice(&decrypt(encrypted, passphrase)?)?)
+    }
+}
+
+/// Encrypt with a key derived from the passphrase, returning base64 of the salt, nonce, and
+/// ciphertext
+fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<String> {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    getrandom::getrandom(&mut salt)?;
+    getrandom::getrandom(&mut nonce)?;
+    let ciphertext = cipher(passphrase, &salt)
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| anyhow!("failed encrypting"))?;
+    let blob = [&salt[..], &nonce[..], &ciphertext].concat();
+    Ok(BASE64_STANDARD.encode(blob))
+}
+
+fn decrypt(encrypted: &str, passphrase: &str) -> Result<Vec<u8>> {
+    let blob = BASE64_STANDARD.decode(encrypted.trim())?;
+    if blob.len() < SALT_LEN + NONCE_LEN {
+        return Err(anyhow!("encrypted data is truncated"));
+    }
+    let (salt, rest) = blob.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    cipher(passphrase, salt)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("failed decrypting, is the passphrase correct?"))
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+    let mut key = [0; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+/// Sync the settings through an encrypted private snippet so they follow the user between
+/// machines without a separate backend. The same passphrase protects the private token when it is
+/// remembered in local storage.
+#[component]
+pub fn SettingsSync(
+    instance: Instance,
+    settings: Settings,
+    on_load: EventHandler<Settings>,
+    on_save: EventHandler<Settings>,
+    on_unlock: EventHandler<String>,
+) -> Element {
+    let mut passphrase = use_signal(String::new);
+    let mut status = use_signal(String::new);
+    let mut token_stored = use_signal(|| load_encrypted_token().is_some());
+
+    let save_instance = instance.clone();
+    let private_token = instance.private_token.clone();
+
+    rsx! {
+        label { class: "block", title: "encrypts the settings synced through a private snippet", "Passphrase" }
+        input {
+            r#type: "password",
+            class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+            oninput: move |event| {
+                *passphrase.write() = event.value();
+            }
+        }
+        button {
+            r#type: "button",
+            class: "px-2 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+            disabled: passphrase().is_empty(),
+            onclick: move |_event| {
+                let instance = save_instance.clone();
+                let settings = settings.clone();
+                spawn(async move {
+                    *status.write() = match save_settings(&instance, &settings, &passphrase()).await {
+                        Ok(()) => {
+                            on_save.call(settings);
+                            "saved".to_string()
+                        }
+                        Err(e) => e.to_string(),
+                    };
+                });
+            },
+            "Save Settings"
+        }
+        button {
+            r#type: "button",
+            class: "px-2 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+            disabled: passphrase().is_empty(),
+            onclick: move |_event| {
+                let instance = instance.clone();
+                spawn(async move {
+                    *status.write() = match load_settings(&instance, &passphrase()).await {
+                        Ok(settings) => {
+                            on_load.call(settings);
+                            "loaded".to_string()
+                        }
+                        Err(e) => e.to_string(),
+                    };
+                });
+            },
+            "Load Settings"
+        }
+        if token_stored() {
+            button {
+                r#type: "button",
+                class: "px-2 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                disabled: passphrase().is_empty(),
+                onclick: move |_event| {
+                    match load_encrypted_token().map(|encrypted| decrypt(&encrypted, &passphrase())) {
+                        Some(Ok(token)) => {
+                            on_unlock.call(String::from_utf8_lossy(&token).to_string());
+                            *status.write() = "unlocked".to_string();
+                        }
+                        Some(Err(e)) => *status.write() = e.to_string(),
+                        None => *token_stored.write() = false,
+                    }
+                },
+                "Unlock Token"
+            }
+            button {
+                r#type: "button",
+                class: "px-2 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                onclick: move |_event| {
+                    storage::remove_item(TOKEN_STORAGE_KEY);
+                    *token_stored.write() = false;
+                },
+                "Forget Token"
+            }
+        } else {
+            button {
+                r#type: "button",
+                class: "px-2 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                title: "store the token encrypted with the passphrase in this browser",
+                disabled: passphrase().is_empty() || private_token.is_empty(),
+                onclick: move |_event| {
+                    match encrypt(private_token.as_bytes(), &passphrase()) {
+                        Ok(encrypted) => {
+                            storage::set_item(TOKEN_STORAGE_KEY, &encrypted);
+                            *token_stored.write() = load_encrypted_token().is_some();
+                        }
+                        Err(e) => *status.write() = e.to_string(),
+                    }
+                },
+                "Remember Token"
+            }
+        }
+        span { class: "font-ariel text-xs", "{status}" }
+    }
+}
+
+async fn save_settings(instance: &Instance, settings: &Settings, passphrase: &str) -> Result<()> {
+    let encrypted = settings.encrypt(passphrase)?;
+    save_private_snippet(instance, SNIPPET_TITLE, SNIPPET_FILE_PATH, &encrypted).await?;
+    Ok(())
+}
+
+async fn load_settings(instance: &Instance, passphrase: &str) -> Result<Settings> {
+    let encrypted = fetch_snippet_content(instance, SNIPPET_TITLE)
+        .await?
+        .ok_or_else(|| anyhow!("no saved settings found"))?;
+    Settings::decrypt(&encrypted, passphrase)
+}
+
+fn load_encrypted_token() -> Option<String> {
+    storage::get_item(TOKEN_STORAGE_KEY)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::api::{Backend, Pagination};
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Persisted(#[serde(with = "persisted_query")] MergeRequestsQuery);
+
+    #[test]
+    fn query_options_survive_persisting() {
+        let query = MergeRequestsQuery {
+            source_branch: Some("main".to_string()),
+            sudo: Some("alex".to_string()),
+            pagination: Pagination::Keyset,
+            per_page: Some(50),
+            max_results: Some(200),
+            iids: vec![12, 34],
+            non_archived: false,
+            backend: Backend::Graphql,
+            simple_view: true,
+            ..MergeRequestsQuery::default()
+        };
+        let json = serde_json::to_string(&Persisted(query.clone())).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Persisted>(&json).unwrap(),
+            Persisted(query)
+        );
+    }
+
+    #[test]
+    fn query_inputs_show_the_query() {
+        let query = MergeRequestsQuery {
+            created_after: Some("2024-10-01T08:00:00Z".parse().unwrap()),
+            iids: vec![12, 34],
+            ..MergeRequestsQuery::default()
+        };
+        let inputs = QueryInputs::of(&query);
+        assert_eq!(inputs.created_after, "2024-10-01T08:00:00+00:00");
+        assert_eq!(inputs.created_after.parse().ok(), query.created_after);
+        assert_eq!(inputs.created_before, "");
+        assert_eq!(inputs.iids, "!12 !34");
+    }
+
+    #[test]
+    fn query_options_default_when_missing() {
+        let json = r#"{"created_after":null,"created_before":null,"order_by":"created_at","scope":"all","sort":"desc","state":null,"updated_after":null,"updated_before":null,"wip":null}"#;
+        let Persisted(query) = serde_json::from_str(json).unwrap();
+        assert_eq!(query, MergeRequestsQuery::default());
+    }
+}
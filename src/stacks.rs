@@ -0,0 +1,112 @@
+use crate::api::MergeRequest;
+
+/// A merge request in a stack with how deep it is stacked
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stacked {
+    pub merge_request: MergeRequest,
+    pub depth: usize,
+    /// Short reference of the merge request it is stacked on
+    pub stacked_on: Option<String>,
+}
+
+/// Order merge requests so each is followed by the ones stacked on it, keeping the original
+/// order otherwise
+pub fn stacks(merge_requests: Vec<MergeRequest>) -> Vec<Stacked> {
+    let parent = |merge_request: &MergeRequest| {
+        merge_requests
+            .iter()
+            .position(|other| other.id != merge_request.id && merge_request.is_stacked_on(other))
+    };
+    let parents = merge_requests.iter().map(parent).collect::<Vec<_>>();
+
+    let mut visited = vec![false; merge_requests.len()];
+    let mut stacked = Vec::new();
+    // Roots first, then whatever is left which can only be merge requests stacked in a circle
+    let roots = (0..merge_requests.len())
+        .filter(|i| parents[*i].is_none())
+        .chain(0..merge_requests.len())
+        .collect::<Vec<_>>();
+    for root in roots {
+        let mut todo = vec![(root, 0)];
+        while let Some((i, depth)) = todo.pop() {
+            if visited[i] {
+                continue;
+            }
+            visited[i] = true;
+            stacked.push(Stacked {
+                merge_request: merge_requests[i].clone(),
+                depth,
+                stacked_on: parents[i].map(|p| merge_requests[p].references.short.clone()),
+            });
+            // Reversed so the children are visited in their original order
+            for child in (0..merge_requests.len()).rev() {
+                if parents[child] == Some(i) {
+                    todo.push((child, depth + 1));
+                }
+            }
+        }
+    }
+    stacked
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::References;
+
+    use super::*;
+
+    fn merge_request(id: i64, source_branch: &str, target_branch: &str) -> MergeRequest {
+        MergeRequest {
+            id,
+            project_id: 4,
+            source_branch: source_branch.to_string(),
+            target_branch: target_branch.to_string(),
+            references: References {
+                short: format!("!{id}"),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn rows(stacked: &[Stacked]) -> Vec<(i64, usize, Option<&str>)> {
+        stacked
+            .iter()
+            .map(|s| (s.merge_request.id, s.depth, s.stacked_on.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn stacked_merge_requests_follow_their_parent() {
+        let stacked = stacks(vec![
+            merge_request(1, "part-3", "part-2"),
+            merge_request(2, "docs", "main"),
+            merge_request(3, "part-1", "main"),
+            merge_request(4, "part-2", "part-1"),
+            merge_request(5, "part-2-tests", "part-1"),
+        ]);
+        assert_eq!(
+            rows(&stacked),
+            [
+                (2, 0, None),
+                (3, 0, None),
+                (4, 1, Some("!3")),
+                (1, 2, Some("!4")),
+                (5, 1, Some("!3")),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_requests_stacked_in_a_circle_are_all_kept() {
+        let stacked = stacks(vec![
+            merge_request(1, "a", "b"),
+            merge_request(2, "b", "a"),
+            merge_request(3, "docs", "main"),
+        ]);
+        assert_eq!(
+            rows(&stacked),
+            [(3, 0, None), (1, 0, Some("!2")), (2, 1, Some("!1"))]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{
+    FaBan, FaCircle, FaCircleCheck, FaCircleExclamation, FaCircleQuestion, FaCircleXmark,
+    FaCodeMerge, FaCommentDots, FaListCheck, FaSpinner,
+};
+use dioxus_free_icons::Icon;
+use strum::Display;
+
+use crate::api::{MergeStatus, PipelineStatus, ReviewState, State};
+
+/// The icon a status is shown with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatusIcon {
+    pub glyph: Glyph,
+    pub color: Color,
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum Glyph {
+    Ban,
+    Circle,
+    CircleCheck,
+    CircleExclamation,
+    CircleQuestion,
+    CircleXmark,
+    CodeMerge,
+    CommentDots,
+    ListCheck,
+    Spinner,
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum Color {
+    Red,
+    Blue,
+    Green,
+    Gray,
+}
+
+impl Color {
+    pub fn fill(self) -> &'static str {
+        match self {
+            Color::Red => "#dd2b0e",
+            Color::Blue => "#1f75cb",
+            Color::Green => "#108548",
+            Color::Gray => "#89888d",
+        }
+    }
+}
+
+impl StatusIcon {
+    const fn new(glyph: Glyph, color: Color) -> Self {
+        Self { glyph, color }
+    }
+}
+
+/// The icon of the overall merge status of a merge request
+pub fn merge_status_icon(
+    merge_when_pipeline_succeeds: bool,
+    state: State,
+    detailed_merge_status: MergeStatus,
+) -> StatusIcon {
+    use MergeStatus::*;
+    use State::*;
+
+    match (merge_when_pipeline_succeeds, state, detailed_merge_status) {
+        (_, _, MergeStatus::Unknown) | (_, State::Unknown, _) => {
+            StatusIcon::new(Glyph::CircleQuestion, Color::Red)
+        }
+        (_, Closed | Locked, _) => StatusIcon::new(Glyph::Ban, Color::Red),
+        (
+            _,
+            Opened,
+            BlockedStatus
+            | DraftStatus
+            | JiraAssociationMissing
+            | NeedRebase
+            | Conflict
+            | DiscussionsNotResolved
+            | NotApproved
+            | RequestedChanges
+            | Checking
+            | Unchecked
+            | CiMustPass
+            | CiStillRunning
+            | ExternalStatusChecks
+            | NotOpen,
+        ) => StatusIcon::new(Glyph::ListCheck, Color::Blue),
+        (true, Opened, Mergeable) => StatusIcon::new(Glyph::Spinner, Color::Green),
+        (false, Opened, Mergeable) => StatusIcon::new(Glyph::CircleCheck, Color::Green),
+        (_, Merged, _) => StatusIcon::new(Glyph::CodeMerge, Color::Green),
+    }
+}
+
+/// The icon of a pipeline status
+pub fn pipeline_status_icon(status: &PipelineStatus) -> StatusIcon {
+    use PipelineStatus::*;
+
+    match status {
+        Unknown => StatusIcon::new(Glyph::CircleQuestion, Color::Red),
+        Failed => StatusIcon::new(Glyph::CircleExclamation, Color::Red),
+        Canceled => StatusIcon::new(Glyph::Ban, Color::Red),
+        Created | WaitingForResource | Preparing | Pending | Running | Skipped | Manual
+        | Scheduled => StatusIcon::new(Glyph::Spinner, Color::Blue),
+        Success => StatusIcon::new(Glyph::CircleCheck, Color::Green),
+    }
+}
+
+/// The icon of a reviewer's review state, only requested changes block the merge request
+pub fn review_state_icon(state: ReviewState) -> StatusIcon {
+    use ReviewState::*;
+
+    match state {
+        Unknown => StatusIcon::new(Glyph::CircleQuestion, Color::Red),
+        RequestedChanges => StatusIcon::new(Glyph::CircleXmark, Color::Red),
+        Reviewed => StatusIcon::new(Glyph::CommentDots, Color::Blue),
+        Unreviewed | Unapproved => StatusIcon::new(Glyph::Circle, Color::Gray),
+        Approved => StatusIcon::new(Glyph::CircleCheck, Color::Green),
+    }
+}
+
+#[component]
+pub fn StatusIconView(icon: StatusIcon) -> Element {
+    let fill = icon.color.fill();
+    match icon.glyph {
+        Glyph::Ban => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaBan,
+            fill
+        }),
+        Glyph::Circle => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaCircle,
+            fill
+        }),
+        Glyph::CircleCheck => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaCircleCheck,
+            fill
+        }),
+        Glyph::CircleExclamation => {
+            rsx!(Icon {
+                width: 16,
+                height: 16,
+                icon: FaCircleExclamation,
+                fill
+            })
+        }
+        Glyph::CircleQuestion => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaCircleQuestion,
+            fill
+        }),
+        Glyph::CircleXmark => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaCircleXmark,
+            fill
+        }),
+        Glyph::CodeMerge => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaCodeMerge,
+            fill
+        }),
+        Glyph::CommentDots => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaCommentDots,
+            fill
+        }),
+        Glyph::ListCheck => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaListCheck,
+            fill
+        }),
+        Glyph::Spinner => rsx!(Icon {
+            width: 16,
+            height: 16,
+            icon: FaSpinner,
+            fill
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    const SNAPSHOT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/status_icons.snap");
+
+    fn snapshot() -> String {
+        let mut snapshot = String::new();
+        for state in State::iter() {
+            for detailed_merge_status in MergeStatus::iter() {
+                for merge_when_pipeline_succeeds in [false, true] {
+                    let icon = merge_status_icon(
+                        merge_when_pipeline_succeeds,
+                        state,
+                        detailed_merge_status,
+                    );
+                    writeln!(
+                        snapshot,
+                        "merge {state} {detailed_merge_status} {merge_when_pipeline_succeeds} -> {} {}",
+                        icon.glyph, icon.color
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        for status in PipelineStatus::iter() {
+            let icon = pipeline_status_icon(&status);
+            writeln!(
+                snapshot,
+                "pipeline {status} -> {} {}",
+                icon.glyph, icon.color
+            )
+            .unwrap();
+        }
+        for state in ReviewState::iter() {
+            let icon = review_state_icon(state);
+            writeln!(snapshot, "review {state} -> {} {}", icon.glyph, icon.color).unwrap();
+        }
+        snapshot
+    }
+
+    /// Any change to the mapping has to be reviewed in the snapshot. Regenerate it by running the
+    /// tests with `UPDATE_SNAPSHOTS=1`.
+    #[test]
+    fn status_icons_match_snapshot() {
+        let snapshot = snapshot();
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(SNAPSHOT_PATH, &snapshot).unwrap();
+        }
+        let expected = std::fs::read_to_string(SNAPSHOT_PATH).unwrap();
+        assert_eq!(
+            snapshot, expected,
+            "status icons changed, see {SNAPSHOT_PATH}"
+        );
+    }
+
+    #[test]
+    fn only_unknown_merge_statuses_are_shown_as_unknown() {
+        for state in State::iter() {
+            for detailed_merge_status in MergeStatus::iter() {
+                for merge_when_pipeline_succeeds in [false, true] {
+                    let icon = merge_status_icon(
+                        merge_when_pipeline_succeeds,
+                        state,
+                        detailed_merge_status,
+                    );
+                    let unknown =
+                        state == State::Unknown || detailed_merge_status == MergeStatus::Unknown;
+                    assert_eq!(
+                        icon.glyph == Glyph::CircleQuestion,
+                        unknown,
+                        "{state} {detailed_merge_status} {merge_when_pipeline_succeeds}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn only_unknown_pipeline_statuses_are_shown_as_unknown() {
+        for status in PipelineStatus::iter() {
+            assert_eq!(
+                pipeline_status_icon(&status).glyph == Glyph::CircleQuestion,
+                status == PipelineStatus::Unknown,
+                "{status}"
+            );
+        }
+    }
+
+    #[test]
+    fn open_merge_statuses_are_not_red() {
+        for detailed_merge_status in MergeStatus::iter().filter(|s| *s != MergeStatus::Unknown) {
+            for merge_when_pipeline_succeeds in [false, true] {
+                let icon = merge_status_icon(
+                    merge_when_pipeline_succeeds,
+                    State::Opened,
+                    detailed_merge_status,
+                );
+                assert_ne!(icon.color, Color::Red, "{detailed_merge_status}");
+            }
+        }
+    }
+
+    #[test]
+    fn auto_merge_is_shown_as_in_progress() {
+        assert_eq!(
+            merge_status_icon(true, State::Opened, MergeStatus::Mergeable),
+            StatusIcon::new(Glyph::Spinner, Color::Green)
+        );
+        assert_eq!(
+            merge_status_icon(false, State::Opened, MergeStatus::Mergeable),
+            StatusIcon::new(Glyph::CircleCheck, Color::Green)
+        );
+    }
+
+    #[test]
+    fn failed_and_canceled_pipelines_are_red() {
+        assert_eq!(
+            pipeline_status_icon(&PipelineStatus::Failed),
+            StatusIcon::new(Glyph::CircleExclamation, Color::Red)
+        );
+        assert_eq!(
+            pipeline_status_icon(&PipelineStatus::Canceled),
+            StatusIcon::new(Glyph::Ban, Color::Red)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::error;
+
+/// Load a JSON value from local storage
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let value = get_item(key)?;
+    serde_json::from_str(&value)
+        .inspect_err(|e| error!("failed decoding {key} from local storage: {e}"))
+        .ok()
+}
+
+/// Save a value to local storage as JSON
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match serde_json::to_string(value) {
+        Ok(value) => set_item(key, &value),
+        Err(e) => error!("failed encoding {key} for local storage: {e}"),
+    }
+}
+
+/// Load a JSON value from session storage, which survives reloading the tab but not closing it
+pub fn load_session<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let value = session_storage()?.get_item(key).ok().flatten()?;
+    serde_json::from_str(&value)
+        .inspect_err(|e| error!("failed decoding {key} from session storage: {e}"))
+        .ok()
+}
+
+/// Save a value to session storage as JSON
+pub fn save_session<T: Serialize>(key: &str, value: &T) {
+    let Some(storage) = session_storage() else {
+        return;
+    };
+    match serde_json::to_string(value) {
+        Ok(value) => {
+            if storage.set_item(key, &value).is_err() {
+                error!("failed storing {key} in session storage");
+            }
+        }
+        Err(e) => error!("failed encoding {key} for session storage: {e}"),
+    }
+}
+
+pub fn remove_session(key: &str) {
+    if let Some(storage) = session_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+pub fn get_item(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+pub fn set_item(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        if storage.set_item(key, value).is_err() {
+            error!("failed storing {key} in local storage");
+        }
+    }
+}
+
+pub fn remove_item(key: &str) {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(key);
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+fn session_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.session_storage().ok().flatten()
+}
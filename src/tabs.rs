@@ -0,0 +1,79 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// Which tab runs a polling loop for all the open tabs, until the lease expires
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+struct Lease {
+    tab: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// A random id of this tab, as every tab runs its own instance of the app
+fn tab_id() -> &'static str {
+    static TAB_ID: OnceLock<String> = OnceLock::new();
+    TAB_ID.get_or_init(|| {
+        let mut id = [0; 8];
+        let _ = getrandom::getrandom(&mut id);
+        id.iter().map(|b| format!("{b:02x}")).collect()
+    })
+}
+
+/// Whether this tab should run the named polling loop now. The first tab to ask leads and keeps
+/// leading by asking again before `lease` runs out, another tab takes over once it has. Browsers
+/// slow down the timers of background tabs, so leading moves to a tab in the foreground when
+/// the lease is a few intervals long.
+pub fn lead(name: &str, lease: Duration) -> bool {
+    let key = format!("lab-bench-leader-{name}");
+    let current = storage::load::<Lease>(&key);
+    match claim(current.as_ref(), tab_id(), Utc::now(), lease) {
+        Some(lease) => {
+            storage::save(&key, &lease);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Let another tab take over the named polling loop right away, if this tab leads it
+pub fn release(name: &str) {
+    let key = format!("lab-bench-leader-{name}");
+    if storage::load::<Lease>(&key).is_some_and(|l| l.tab == tab_id()) {
+        storage::remove_item(&key);
+    }
+}
+
+/// The renewed or taken over lease, `None` while another tab holds it
+fn claim(current: Option<&Lease>, tab: &str, now: DateTime<Utc>, lease: Duration) -> Option<Lease> {
+    if current.is_some_and(|l| l.tab != tab && now < l.expires_at) {
+        return None;
+    }
+    Some(Lease {
+        tab: tab.to_string(),
+        expires_at: now + TimeDelta::from_std(lease).unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn leases_are_renewed_and_taken_over_once_expired() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 12, 0, 0).unwrap();
+        let lease = Duration::from_secs(90);
+        let first = claim(None, "a", now, lease).unwrap();
+        assert_eq!(first.expires_at, now + TimeDelta::seconds(90));
+        assert_eq!(claim(Some(&first), "b", now, lease), None);
+        let later = now + TimeDelta::seconds(30);
+        assert_eq!(claim(Some(&first), "a", later, lease).unwrap().tab, "a");
+        let expired = now + TimeDelta::seconds(91);
+        assert_eq!(claim(Some(&first), "b", expired, lease).unwrap().tab, "b");
+    }
+}
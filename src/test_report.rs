@@ -0,0 +1,109 @@
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::{FaCaretDown, FaCaretRight};
+use dioxus_free_icons::Icon;
+
+use crate::api::{fetch_test_report, Instance, MergeRequest, TestReport};
+
+/// Failing test cases listed when expanded, the rest are counted
+const MAX_FAILING_CASES: usize = 50;
+
+/// The failed and errored test cases as `<suite>: <class> <name>`
+fn failing_cases(report: &TestReport) -> Vec<String> {
+    report
+        .test_suites
+        .iter()
+        .flat_map(|suite| {
+            suite
+                .test_cases
+                .iter()
+                .filter(|case| matches!(case.status.as_str(), "failed" | "error"))
+                .map(move |case| match &case.classname {
+                    Some(classname) if !classname.is_empty() => {
+                        format!("{}: {classname} {}", suite.name, case.name)
+                    }
+                    _ => format!("{}: {}", suite.name, case.name),
+                })
+        })
+        .collect()
+}
+
+/// Test case counts of a failed head pipeline, expandable to the failing test cases which are
+/// fetched the first time
+#[component]
+pub fn TestSummary(merge_request: MergeRequest) -> Element {
+    let instance = use_context::<Signal<Instance>>();
+    let mut expanded = use_signal(|| false);
+    let mut failing = use_signal(|| None::<Result<Vec<String>, String>>);
+
+    let totals = merge_request.test_totals.clone()?;
+    let pipeline_id = merge_request.head_pipeline.as_ref()?.id;
+    let project_id = merge_request.project_id;
+    let toggle = move |_event| {
+        *expanded.write() = !expanded();
+        if failing.peek().is_some() {
+            return;
+        }
+        spawn(async move {
+            *failing.write() = Some(
+                fetch_test_report(&instance(), project_id, pipeline_id)
+                    .await
+                    .map(|report| failing_cases(&report))
+                    .map_err(|e| e.to_string()),
+            );
+        });
+    };
+
+    rsx! {
+        div { class: "flex flex-col",
+            span { class: "flex flex-row items-center font-ariel text-xs cursor-pointer", onclick: toggle,
+                if expanded() {
+                    Icon { width: 12, height: 12, icon: FaCaretDown }
+                } else {
+                    Icon { width: 12, height: 12, icon: FaCaretRight }
+                }
+                span { class: if totals.failed + totals.error > 0 { "text-red-700" } else { "" },
+                    "{totals.failed} failed / {totals.success} passed"
+                }
+                if totals.error > 0 {
+                    span { class: "ml-1 text-red-700", "/ {totals.error} errors" }
+                }
+                if totals.skipped > 0 {
+                    span { class: "ml-1", "/ {totals.skipped} skipped" }
+                }
+            }
+            if expanded() {
+                match failing() {
+                    Some(Ok(cases)) if cases.is_empty() => rsx!(span { class: "font-ariel text-xs ml-3", "no failing test cases" }),
+                    Some(Ok(cases)) => rsx! {
+                        for case in cases.iter().take(MAX_FAILING_CASES) {
+                            span { class: "font-ariel text-xs ml-3 truncate", title: case.clone(), "{case}" }
+                        }
+                        if cases.len() > MAX_FAILING_CASES {
+                            span { class: "font-ariel text-xs ml-3", "+{cases.len() - MAX_FAILING_CASES} more" }
+                        }
+                    },
+                    Some(Err(e)) => rsx!(span { class: "font-ariel text-xs ml-3", "{e}" }),
+                    None => rsx!(span { class: "font-ariel text-xs ml-3", "loading" }),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failing_cases_are_named_by_suite() {
+        let payload = include_str!("../fixtures/gitlab/17.5/test_report.json");
+        let report: TestReport = serde_json::from_str(payload).unwrap();
+        assert_eq!(
+            failing_cases(&report),
+            [
+                "rspec: spec/uploads_spec.rb retries failed uploads",
+                "jest: times out slow requests",
+            ]
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::Utc;
+use dioxus::prelude::*;
+
+use crate::api::{MergeRequest, State};
+use crate::time_ago;
+
+/// Gantt style layout with a bar per merge request spanning from when it was created until it was
+/// merged, or until now while it is open, oldest first
+#[component]
+pub fn Timeline(merge_request_list: Vec<MergeRequest>) -> Element {
+    let now = Utc::now();
+    let mut merge_requests = merge_request_list
+        .into_iter()
+        .filter(|mr| matches!(mr.state, State::Opened | State::Merged))
+        .collect::<Vec<_>>();
+    merge_requests.sort_by_key(|mr| mr.created_at);
+    let Some(start) = merge_requests.first().map(|mr| mr.created_at) else {
+        return rsx!(span { class: "font-ariel text-xs", "no open or merged merge requests" });
+    };
+    let span = (now - start).num_seconds().max(1) as f64;
+
+    rsx! {
+        div { class: "flex flex-row justify-between font-ariel text-xs border-b",
+            span { title: start.to_string(), "{time_ago(start)}" }
+            span { "now" }
+        }
+        ul { class: "list-none",
+            for merge_request in merge_requests {
+                {
+                    let end = merge_request.merged_at.unwrap_or(now);
+                    let offset = (merge_request.created_at - start).num_seconds() as f64 / span * 100.0;
+                    let width = ((end - merge_request.created_at).num_seconds() as f64 / span * 100.0).max(0.5);
+                    let color = if merge_request.state == State::Merged { "#108548" } else { "#1f75cb" };
+                    rsx! {
+                        li { key: "{merge_request.references.full}", class: "flex flex-col py-1 border-b",
+                            a { class: "font-ariel text-xs truncate", href: merge_request.web_url, "{merge_request.references.full} {merge_request.title}" }
+                            div {
+                                class: "h-2 rounded-sm",
+                                style: "margin-left: {offset}%; width: {width}%; background-color: {color};",
+                                title: "created {time_ago(merge_request.created_at)}",
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
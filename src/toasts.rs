@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::fa_solid_icons::FaXmark;
+use dioxus_free_icons::Icon;
+use gloo_timers::future::sleep;
+
+use crate::api::{MergeRequest, PipelineStatus, ReviewState, State};
+
+/// How long a toast stays on screen unless dismissed earlier
+const TOAST_DURATION: Duration = Duration::from_secs(8);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Toast {
+    id: u64,
+    message: String,
+}
+
+/// Show a toast for each message, removing them again after [`TOAST_DURATION`]
+pub fn show(mut toasts: Signal<Vec<Toast>>, messages: Vec<String>) {
+    for message in messages {
+        let id = toasts
+            .read()
+            .iter()
+            .map(|t| t.id + 1)
+            .max()
+            .unwrap_or_default();
+        toasts.write().push(Toast { id, message });
+        spawn(async move {
+            sleep(TOAST_DURATION).await;
+            toasts.write().retain(|t| t.id != id);
+        });
+    }
+}
+
+/// Describe the meaningful changes between two refreshes of the same merge requests. Merge
+/// requests which were not part of the previous refresh are not reported.
+pub fn transitions(previous: &[MergeRequest], current: &[MergeRequest]) -> Vec<String> {
+    let mut messages = Vec::new();
+    for merge_request in current {
+        let Some(before) = previous.iter().find(|mr| mr.id == merge_request.id) else {
+            continue;
+        };
+        let reference = &merge_request.references.short;
+
+        if before.state != State::Merged && merge_request.state == State::Merged {
+            messages.push(format!("{reference} merged"));
+        }
+        let pipeline_status =
+            |mr: &MergeRequest| mr.head_pipeline.as_ref().map(|p| p.status.clone());
+        let status = pipeline_status(merge_request);
+        if status != pipeline_status(before) {
+            match status {
+                Some(PipelineStatus::Success) => {
+                    messages.push(format!("{reference} pipeline passed"))
+                }
+                Some(PipelineStatus::Failed) => {
+                    messages.push(format!("{reference} pipeline failed"))
+                }
+                _ => {}
+            }
+        }
+        for reviewer in &merge_request.reviewer_states {
+            let state_before = before
+                .reviewer_states
+                .iter()
+                .find(|r| r.user.id == reviewer.user.id)
+                .map(|r| r.state);
+            if state_before == Some(reviewer.state) {
+                continue;
+            }
+            let username = &reviewer.user.username;
+            match reviewer.state {
+                ReviewState::Approved => {
+                    messages.push(format!("{reference} approved by {username}"))
+                }
+                ReviewState::RequestedChanges => {
+                    messages.push(format!("{reference} changes requested by {username}"))
+                }
+                _ => {}
+            }
+        }
+    }
+    messages
+}
+
+/// The stack of toasts in the corner of the screen
+#[component]
+pub fn Toasts(toasts: Signal<Vec<Toast>>) -> Element {
+    rsx! {
+        div { class: "fixed bottom-0 right-0 flex flex-col items-end m-2",
+            for toast in toasts() {
+                div {
+                    key: "{toast.id}",
+                    class: "flex flex-row items-center font-ariel text-xs border rounded-sm border-gray-300 bg-gray-100 p-2 my-1",
+                    span { class: "mr-2", "{toast.message}" }
+                    span {
+                        class: "cursor-pointer",
+                        onclick: move |_event| toasts.write().retain(|t| t.id != toast.id),
+                        Icon { width: 12, height: 12, icon: FaXmark }
+                    }
+                }
+            }
+        }
+    }
+}
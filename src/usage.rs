@@ -0,0 +1,96 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use dioxus::prelude::*;
+
+use crate::{chart, storage};
+
+/// Local storage key of how often each feature was used, which never leaves the browser
+const USAGE_STORAGE_KEY: &str = "lab-bench-usage";
+
+/// How often each choice was made, by feature then choice
+type Counts = BTreeMap<String, BTreeMap<String, u32>>;
+
+/// Count one more use of the choice of a feature, like a layout or a menu action
+pub fn record(feature: &str, choice: impl Display) {
+    let mut counts = storage::load::<Counts>(USAGE_STORAGE_KEY).unwrap_or_default();
+    *counts
+        .entry(feature.to_string())
+        .or_default()
+        .entry(choice.to_string())
+        .or_default() += 1;
+    storage::save(USAGE_STORAGE_KEY, &counts);
+}
+
+/// The options of a feature with the most used first, named as they were recorded
+pub fn ranked<T>(
+    feature: &str,
+    options: impl IntoIterator<Item = T>,
+    name: impl Fn(&T) -> String,
+) -> Vec<T> {
+    let counts = storage::load::<Counts>(USAGE_STORAGE_KEY).unwrap_or_default();
+    rank(counts.get(feature), options, name)
+}
+
+/// Sort the options by how often they were chosen, the options chosen as often keep their order
+fn rank<T>(
+    counts: Option<&BTreeMap<String, u32>>,
+    options: impl IntoIterator<Item = T>,
+    name: impl Fn(&T) -> String,
+) -> Vec<T> {
+    let mut options = options.into_iter().collect::<Vec<_>>();
+    if let Some(counts) = counts {
+        options.sort_by_key(|o| Reverse(counts.get(&name(o)).copied().unwrap_or_default()));
+    }
+    options
+}
+
+/// How often each feature was used in this browser, which is only ever stored locally
+#[component]
+pub fn YourUsage() -> Element {
+    let mut counts = use_signal(|| storage::load::<Counts>(USAGE_STORAGE_KEY).unwrap_or_default());
+
+    rsx! {
+        div { class: "flex flex-row items-center font-ariel text-xs my-1",
+            span { class: "mr-2", "Counted in this browser only, never sent anywhere." }
+            button {
+                class: "px-4 py-1 border rounded-sm border-gray-300 bg-gray-100",
+                disabled: counts.read().is_empty(),
+                onclick: move |_event| {
+                    storage::remove_item(USAGE_STORAGE_KEY);
+                    counts.write().clear();
+                },
+                "Reset"
+            }
+        }
+        if counts.read().is_empty() {
+            span { class: "font-ariel text-xs", "Nothing used yet" }
+        }
+        for (feature, choices) in counts() {
+            div { key: "{feature}", class: "flex flex-col my-1",
+                h3 { class: "font-ariel text-sm", "{feature}" }
+                chart::Bars {
+                    bars: rank(Some(&choices), choices.iter().map(|(c, n)| (c.clone(), *n as f64)), |(c, _)| c.clone()),
+                    width: 320,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_used_options_come_first() {
+        let counts = BTreeMap::from([("board".to_string(), 3), ("calendar".to_string(), 5)]);
+        let options = ["list", "board", "calendar", "timeline"];
+        assert_eq!(
+            rank(Some(&counts), options, |o| o.to_string()),
+            ["calendar", "board", "list", "timeline"]
+        );
+        assert_eq!(rank(None, options, |o| o.to_string()), options);
+    }
+}
@@ -0,0 +1,189 @@
+use dioxus::prelude::*;
+
+use crate::api::{fetch_current_user, fetch_starred_projects, Auth, Instance, Scope};
+use crate::storage;
+
+/// Local storage key set once the setup wizard was finished or skipped
+pub const SETUP_DONE_STORAGE_KEY: &str = "lab-bench-setup-done";
+
+/// What the user chose in the setup wizard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Setup {
+    pub gitlab_url: String,
+    pub private_token: String,
+    pub repos: String,
+    pub groups: String,
+    pub scope: Scope,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Step {
+    Instance,
+    Token,
+    Projects,
+    Preset,
+}
+
+/// Whether the wizard should be shown, which is until it is finished or skipped once
+pub fn needed() -> bool {
+    storage::get_item(SETUP_DONE_STORAGE_KEY).is_none()
+}
+
+/// Guided first run setup going from the instance, to the token, to the projects, to a starting
+/// preset
+#[component]
+pub fn SetupWizard(gitlab_url: String, on_finish: EventHandler<Option<Setup>>) -> Element {
+    let mut step = use_signal(|| Step::Instance);
+    let mut setup = use_signal(|| Setup {
+        gitlab_url,
+        private_token: String::new(),
+        repos: String::new(),
+        groups: String::new(),
+        scope: Scope::CreatedByMe,
+    });
+    let mut status = use_signal(String::new);
+
+    let instance = move || Instance {
+        url: setup.read().gitlab_url.clone(),
+        auth: Auth::default(),
+        private_token: setup.read().private_token.clone(),
+        headers: Vec::new(),
+        request_tag: None,
+    };
+    let finish = move |setup: Option<Setup>| {
+        storage::set_item(SETUP_DONE_STORAGE_KEY, "true");
+        on_finish.call(setup);
+    };
+
+    rsx! {
+        div { class: "flex flex-col p-2 my-1 border rounded-sm border-gray-300",
+            h2 { class: "font-ariel text-sm mb-1", "Welcome to Lab Bench" }
+            match step() {
+                Step::Instance => rsx! {
+                    label { class: "block text-xs", "The API url of your GitLab instance" }
+                    input {
+                        r#type: "text",
+                        class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                        value: setup.read().gitlab_url.clone(),
+                        oninput: move |event| {
+                            setup.write().gitlab_url = event.value().trim().to_string();
+                        }
+                    }
+                },
+                Step::Token => rsx! {
+                    label { class: "block text-xs", "A personal access token with the api scope" }
+                    input {
+                        r#type: "password",
+                        class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                        value: setup.read().private_token.clone(),
+                        oninput: move |event| {
+                            setup.write().private_token = event.value().trim().to_string();
+                        }
+                    }
+                },
+                Step::Projects => rsx! {
+                    label { class: "block text-xs", "Whitespace separated project paths to follow" }
+                    div { class: "flex flex-row",
+                        input {
+                            r#type: "text",
+                            class: "block w-full p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                            value: setup.read().repos.clone(),
+                            oninput: move |event| {
+                                setup.write().repos = event.value();
+                            }
+                        }
+                        button {
+                            r#type: "button",
+                            class: "px-2 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                            onclick: move |_event| {
+                                spawn(async move {
+                                    match fetch_starred_projects(&instance()).await {
+                                        Ok(projects) => {
+                                            setup.write().repos = projects
+                                                .into_iter()
+                                                .map(|p| p.path_with_namespace)
+                                                .collect::<Vec<_>>()
+                                                .join(" ");
+                                        }
+                                        Err(e) => *status.write() = e.to_string(),
+                                    }
+                                });
+                            },
+                            "Use Starred"
+                        }
+                    }
+                    label { class: "block text-xs", "Whitespace separated group paths of your teams" }
+                    input {
+                        r#type: "text",
+                        class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                        value: setup.read().groups.clone(),
+                        oninput: move |event| {
+                            setup.write().groups = event.value();
+                        }
+                    }
+                },
+                Step::Preset => rsx! {
+                    label { class: "block text-xs", "What to show first" }
+                    select {
+                        class: "block p-1 border rounded-sm border-gray-300 bg-gray-100 text-xs text-ariel",
+                        onchange: move |event| {
+                            setup.write().scope = serde_json::from_str(&event.value()).unwrap_or_default();
+                        },
+                        option { value: serde_json::to_string(&Scope::CreatedByMe).unwrap(), "merge requests I created" }
+                        option { value: serde_json::to_string(&Scope::AssignedToMe).unwrap(), "merge requests assigned to me" }
+                        option { value: serde_json::to_string(&Scope::All).unwrap(), "all merge requests of the projects and groups" }
+                    }
+                },
+            }
+            div { class: "flex flex-row items-center mt-1",
+                button {
+                    r#type: "button",
+                    class: "px-2 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                    onclick: move |_event| finish(None),
+                    "Skip"
+                }
+                if step() != Step::Instance {
+                    button {
+                        r#type: "button",
+                        class: "px-2 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                        onclick: move |_event| {
+                            status.write().clear();
+                            *step.write() = match step() {
+                                Step::Instance | Step::Token => Step::Instance,
+                                Step::Projects => Step::Token,
+                                Step::Preset => Step::Projects,
+                            };
+                        },
+                        "Back"
+                    }
+                }
+                button {
+                    r#type: "button",
+                    class: "px-2 mr-1 border rounded-sm border-gray-300 bg-gray-100 text-xs",
+                    onclick: move |_event| {
+                        status.write().clear();
+                        match step() {
+                            Step::Instance => *step.write() = Step::Token,
+                            // Check the token works before moving on
+                            Step::Token => {
+                                spawn(async move {
+                                    match fetch_current_user(&instance()).await {
+                                        Ok(user) => {
+                                            *status.write() = format!("signed in as {}", user.username);
+                                            *step.write() = Step::Projects;
+                                        }
+                                        Err(e) => *status.write() = e.to_string(),
+                                    }
+                                });
+                            }
+                            Step::Projects => *step.write() = Step::Preset,
+                            Step::Preset => finish(Some(setup())),
+                        }
+                    },
+                    if step() == Step::Preset { "Finish" } else { "Next" }
+                }
+                span { class: "font-ariel text-xs", "{status}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use dioxus::prelude::*;
+
+/// The working hours of team members, shared through context so rows can show which reviewers
+/// are likely online
+#[derive(Clone, Copy)]
+pub struct Team {
+    /// Written by the user, see [`parse`]
+    pub configured: Signal<String>,
+    /// Parsed from `configured` whenever it changes, so the rows don't parse it on every render.
+    /// Empty while it doesn't parse.
+    pub members: Signal<HashMap<String, WorkingHours>>,
+}
+
+/// When a team member usually works, on weekdays in their time zone
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkingHours {
+    timezone: Tz,
+    /// Hour of the day work starts, inclusive
+    start: u32,
+    /// Hour of the day work ends, exclusive
+    end: u32,
+}
+
+impl WorkingHours {
+    pub fn is_online(&self, now: DateTime<Utc>) -> bool {
+        let now = now.with_timezone(&self.timezone);
+        !matches!(now.weekday(), Weekday::Sat | Weekday::Sun)
+            && (self.start..self.end).contains(&now.hour())
+    }
+
+    /// The local time of the team member and their working hours, e.g. `Tue 14:05 in
+    /// Europe/Berlin, works 9-17`
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        format!(
+            "{} in {}, works {}-{}",
+            now.with_timezone(&self.timezone).format("%a %H:%M"),
+            self.timezone,
+            self.start,
+            self.end
+        )
+    }
+}
+
+/// Parse the working hours of team members keyed by username, one per line as
+/// `<username> <time zone> [<start hour>-<end hour>]`, for example `alice Europe/Berlin 8-16`.
+/// Hours default to 9-17. Blank lines and lines starting with `#` are ignored.
+pub fn parse(team: &str) -> Result<HashMap<String, WorkingHours>> {
+    team.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        .map(|(i, line)| parse_line(line).map_err(|e| anyhow!("team member {}: {e}", i + 1)))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Result<(String, WorkingHours)> {
+    let mut parts = line.split_whitespace();
+    let (Some(username), Some(timezone)) = (parts.next(), parts.next()) else {
+        return Err(anyhow!("expected `<username> <time zone>`"));
+    };
+    let timezone = timezone.parse::<Tz>().map_err(|e| anyhow!("{e}"))?;
+    let (start, end) = match parts.next() {
+        Some(hours) => {
+            let (start, end) = hours
+                .split_once('-')
+                .ok_or_else(|| anyhow!("expected hours as `<start>-<end>`"))?;
+            (start.parse()?, end.parse()?)
+        }
+        None => (9, 17),
+    };
+    if start >= end || end > 24 {
+        return Err(anyhow!("invalid hours {start}-{end}"));
+    }
+    let username = username.trim_start_matches('@').to_string();
+    Ok((
+        username,
+        WorkingHours {
+            timezone,
+            start,
+            end,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn online_during_local_working_hours() {
+        let team = parse("@alice Asia/Tokyo 8-16\nbob America/New_York").unwrap();
+        // Wednesday 01:00 UTC is 10:00 in Tokyo and 21:00 the day before in New York
+        let now = Utc.with_ymd_and_hms(2024, 6, 12, 1, 0, 0).unwrap();
+        assert!(team["alice"].is_online(now));
+        assert!(!team["bob"].is_online(now));
+    }
+
+    #[test]
+    fn offline_on_weekends() {
+        let team = parse("alice Europe/Berlin").unwrap();
+        // Saturday noon
+        let now = Utc.with_ymd_and_hms(2024, 6, 8, 10, 0, 0).unwrap();
+        assert!(!team["alice"].is_online(now));
+    }
+
+    #[test]
+    fn invalid_lines_are_reported_by_line() {
+        let e = parse("# team\nalice Mars/Olympus").unwrap_err();
+        assert!(e.to_string().starts_with("team member 2"));
+        assert!(parse("alice Europe/Berlin 17-9").is_err());
+    }
+}